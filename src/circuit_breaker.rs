@@ -0,0 +1,196 @@
+//! Per-endpoint circuit breaker for the NTP FDW session
+//!
+//! Fan-out queries hit several API endpoints per scan. When one of them keeps
+//! failing (e.g. Jahresmarktpraemie answering 500), every scan would wait for
+//! it again. The breaker counts consecutive failures per endpoint and, once
+//! `threshold` is reached, marks the endpoint open for `cooldown_secs`: scans
+//! needing it fail without a request until the cooldown has passed.
+//!
+//! After the cooldown the next plan is fetched again (half-open). A success
+//! closes the breaker; another failure reopens it for a full cooldown.
+//!
+//! State lives on the `NtpFdw` singleton, so it persists across scans of one
+//! session. Time is passed in (seconds since epoch) to keep the breaker
+//! independent of the host clock.
+
+use std::collections::HashMap;
+
+/// Default for the `circuit_breaker_threshold` server option (off)
+pub(crate) const DEFAULT_THRESHOLD: u32 = 0;
+
+/// Default for the `circuit_breaker_cooldown_secs` server option
+pub(crate) const DEFAULT_COOLDOWN_SECS: i64 = 300;
+
+/// Failure tracking for one endpoint
+#[derive(Debug, Default)]
+struct EndpointState {
+    /// Failures since the last success
+    consecutive_failures: u32,
+
+    /// Seconds since epoch until which the endpoint is not fetched
+    open_until: Option<i64>,
+}
+
+/// Consecutive-failure circuit breaker keyed by API endpoint
+///
+/// A `threshold` of 0 disables the breaker (the `Default`).
+#[derive(Debug, Default)]
+pub(crate) struct CircuitBreaker {
+    threshold: u32,
+    cooldown_secs: i64,
+    endpoints: HashMap<String, EndpointState>,
+}
+
+impl CircuitBreaker {
+    /// Create a breaker tripping after `threshold` consecutive failures
+    pub(crate) fn new(threshold: u32, cooldown_secs: i64) -> Self {
+        Self {
+            threshold,
+            cooldown_secs,
+            endpoints: HashMap::new(),
+        }
+    }
+
+    /// Build a breaker from the `circuit_breaker_*` server option values
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - Consecutive failures before tripping (`0` disables)
+    /// * `cooldown_secs` - Seconds an open endpoint is not fetched (positive)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(CircuitBreaker)` - Configured breaker
+    /// * `Err(String)` - Option value is not a valid integer
+    pub(crate) fn from_options(threshold: &str, cooldown_secs: &str) -> Result<Self, String> {
+        let threshold = threshold.trim().parse::<u32>().map_err(|_| {
+            format!(
+                "Invalid server option 'circuit_breaker_threshold': '{}'. Expected a non-negative integer (0 disables).",
+                threshold
+            )
+        })?;
+        let cooldown_secs = match cooldown_secs.trim().parse::<i64>() {
+            Ok(secs) if secs >= 1 => secs,
+            _ => {
+                return Err(format!(
+                    "Invalid server option 'circuit_breaker_cooldown_secs': '{}'. Expected a positive integer.",
+                    cooldown_secs
+                ))
+            }
+        };
+
+        Ok(Self::new(threshold, cooldown_secs))
+    }
+
+    /// Seconds left until an open endpoint is fetched again
+    ///
+    /// # Returns
+    ///
+    /// * `Some(secs)` - Endpoint is open; do not fetch it
+    /// * `None` - Endpoint is closed or its cooldown has passed
+    pub(crate) fn remaining_cooldown(&self, endpoint: &str, now: i64) -> Option<i64> {
+        self.endpoints
+            .get(endpoint)
+            .and_then(|state| state.open_until)
+            .filter(|&until| now < until)
+            .map(|until| until - now)
+    }
+
+    /// Record a successful fetch, closing the endpoint's breaker
+    pub(crate) fn record_success(&mut self, endpoint: &str) {
+        self.endpoints.remove(endpoint);
+    }
+
+    /// Record a failed fetch
+    ///
+    /// # Returns
+    ///
+    /// `true` if this failure tripped the breaker (the endpoint is now open)
+    pub(crate) fn record_failure(&mut self, endpoint: &str, now: i64) -> bool {
+        if self.threshold == 0 {
+            return false;
+        }
+
+        let state = self.endpoints.entry(endpoint.to_string()).or_default();
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        if state.consecutive_failures < self.threshold {
+            return false;
+        }
+
+        state.open_until = Some(now.saturating_add(self.cooldown_secs));
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_749_988_800;
+
+    #[test]
+    fn test_trips_after_threshold() {
+        let mut breaker = CircuitBreaker::new(3, 300);
+
+        assert!(!breaker.record_failure("Jahresmarktpraemie", NOW));
+        assert!(!breaker.record_failure("Jahresmarktpraemie", NOW));
+        assert_eq!(breaker.remaining_cooldown("Jahresmarktpraemie", NOW), None);
+
+        assert!(breaker.record_failure("Jahresmarktpraemie", NOW));
+        assert_eq!(
+            breaker.remaining_cooldown("Jahresmarktpraemie", NOW + 100),
+            Some(200)
+        );
+
+        // Other endpoints are unaffected
+        assert_eq!(breaker.remaining_cooldown("Spotmarktpreise", NOW), None);
+    }
+
+    #[test]
+    fn test_success_resets_failures() {
+        let mut breaker = CircuitBreaker::new(2, 300);
+
+        breaker.record_failure("marktpraemie", NOW);
+        breaker.record_success("marktpraemie");
+
+        assert!(!breaker.record_failure("marktpraemie", NOW));
+    }
+
+    #[test]
+    fn test_half_open_after_cooldown() {
+        let mut breaker = CircuitBreaker::new(1, 60);
+
+        assert!(breaker.record_failure("NegativePreise", NOW));
+        assert_eq!(breaker.remaining_cooldown("NegativePreise", NOW + 60), None);
+
+        // A failed probe reopens the breaker for a full cooldown
+        assert!(breaker.record_failure("NegativePreise", NOW + 60));
+        assert_eq!(
+            breaker.remaining_cooldown("NegativePreise", NOW + 60),
+            Some(60)
+        );
+    }
+
+    #[test]
+    fn test_zero_threshold_disables() {
+        let mut breaker = CircuitBreaker::default();
+
+        for _ in 0..10 {
+            assert!(!breaker.record_failure("Spotmarktpreise", NOW));
+        }
+        assert_eq!(breaker.remaining_cooldown("Spotmarktpreise", NOW), None);
+    }
+
+    #[test]
+    fn test_from_options() {
+        let breaker = CircuitBreaker::from_options(" 5 ", "120").unwrap();
+        assert_eq!(breaker.threshold, 5);
+        assert_eq!(breaker.cooldown_secs, 120);
+
+        assert!(CircuitBreaker::from_options("0", "300").is_ok());
+        assert!(CircuitBreaker::from_options("-1", "300").is_err());
+        assert!(CircuitBreaker::from_options("three", "300").is_err());
+        assert!(CircuitBreaker::from_options("3", "0").is_err());
+        assert!(CircuitBreaker::from_options("3", "soon").is_err());
+    }
+}
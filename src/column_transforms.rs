@@ -0,0 +1,239 @@
+//! Built-in output transforms per column (server option `column_transforms`)
+//!
+//! Some schemas want a column's values adjusted on the way out, e.g. prices
+//! rounded to cents or zero TSO values read as missing. Instead of a code
+//! hook, server option `column_transforms` names transforms from a fixed set
+//! per column ("price_eur_mwh=round2, tso_50hertz_mw=null_if_zero"). They run
+//! on the converted cells right before a row is pushed, in the order listed,
+//! for every table that has the column.
+//!
+//! Transforms only touch numeric cells; NULL stays NULL. Columns are checked
+//! against the numeric columns of the known tables when the option is parsed,
+//! so a typo fails at server setup instead of being silently ignored.
+
+use std::collections::HashMap;
+
+use crate::bindings::supabase::wrappers::types::{Cell, TypeOid};
+use crate::column_types::expected_type;
+
+/// A built-in cell transform
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ColumnTransform {
+    /// Absolute value
+    Abs,
+    /// Round to 2 decimal places (integers are unchanged)
+    Round2,
+    /// NULL instead of 0
+    NullIfZero,
+}
+
+impl ColumnTransform {
+    /// Names accepted in `column_transforms`, in `from_name` order
+    const NAMES: [&'static str; 3] = ["abs", "round2", "null_if_zero"];
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "abs" => Some(Self::Abs),
+            "round2" => Some(Self::Round2),
+            "null_if_zero" => Some(Self::NullIfZero),
+            _ => None,
+        }
+    }
+
+    /// Apply to one cell; non-numeric cells pass through unchanged
+    fn apply(self, cell: Cell) -> Option<Cell> {
+        match self {
+            Self::Abs => Some(match cell {
+                Cell::Numeric(v) => Cell::Numeric(v.abs()),
+                Cell::F64(v) => Cell::F64(v.abs()),
+                Cell::F32(v) => Cell::F32(v.abs()),
+                Cell::I16(v) => Cell::I16(v.saturating_abs()),
+                Cell::I32(v) => Cell::I32(v.saturating_abs()),
+                Cell::I64(v) => Cell::I64(v.saturating_abs()),
+                other => other,
+            }),
+            Self::Round2 => Some(match cell {
+                Cell::Numeric(v) => Cell::Numeric(round2(v)),
+                Cell::F64(v) => Cell::F64(round2(v)),
+                Cell::F32(v) => Cell::F32(round2(f64::from(v)) as f32),
+                other => other,
+            }),
+            Self::NullIfZero => {
+                let zero = match &cell {
+                    Cell::Numeric(v) | Cell::F64(v) => *v == 0.0,
+                    Cell::F32(v) => *v == 0.0,
+                    Cell::I16(v) => *v == 0,
+                    Cell::I32(v) => *v == 0,
+                    Cell::I64(v) => *v == 0,
+                    _ => false,
+                };
+                (!zero).then_some(cell)
+            }
+        }
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Transforms per column name
+#[derive(Debug, Default)]
+pub(crate) struct ColumnTransforms {
+    by_column: HashMap<String, Vec<ColumnTransform>>,
+}
+
+impl ColumnTransforms {
+    /// Parse the `column_transforms` server option
+    ///
+    /// Comma-separated `column=transform` entries; repeating a column chains
+    /// its transforms in order ("price_eur_mwh=abs, price_eur_mwh=round2").
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ColumnTransforms)` - Transforms per column (empty for "")
+    /// * `Err(String)` - Entry without `=`, unknown transform, or a column
+    ///   that is not numeric in any table
+    pub(crate) fn parse(value: &str) -> Result<Self, String> {
+        let mut by_column: HashMap<String, Vec<ColumnTransform>> = HashMap::new();
+
+        for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let Some((column, name)) = entry.split_once('=') else {
+                return Err(format!(
+                    "Invalid server option 'column_transforms': entry '{}' is not column=transform",
+                    entry
+                ));
+            };
+            let (column, name) = (column.trim(), name.trim());
+
+            let Some(transform) = ColumnTransform::from_name(&name.to_lowercase()) else {
+                return Err(format!(
+                    "Invalid server option 'column_transforms': unknown transform '{}'. Expected one of: {}",
+                    name,
+                    ColumnTransform::NAMES.join(", ")
+                ));
+            };
+            if !is_numeric_column(column) {
+                return Err(format!(
+                    "Invalid server option 'column_transforms': '{}' is not a numeric column",
+                    column
+                ));
+            }
+
+            by_column
+                .entry(column.to_string())
+                .or_default()
+                .push(transform);
+        }
+
+        Ok(Self { by_column })
+    }
+
+    /// Apply the configured transforms to a converted row
+    ///
+    /// `cells` are in `columns` order, as the `*_row_to_cells` converters
+    /// return them.
+    pub(crate) fn apply(&self, columns: &[String], cells: Vec<Option<Cell>>) -> Vec<Option<Cell>> {
+        if self.by_column.is_empty() {
+            return cells;
+        }
+
+        columns
+            .iter()
+            .zip(cells)
+            .map(|(column, cell)| match self.by_column.get(column) {
+                Some(transforms) => transforms
+                    .iter()
+                    .try_fold(cell?, |cell, transform| transform.apply(cell)),
+                None => cell,
+            })
+            .collect()
+    }
+}
+
+/// Whether any known table emits `column` as a number
+fn is_numeric_column(column: &str) -> bool {
+    crate::TABLE_NAMES.iter().any(|table| {
+        matches!(
+            expected_type(table, column),
+            Some(TypeOid::Numeric | TypeOid::I16 | TypeOid::I32 | TypeOid::I64)
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_column_transforms() {
+        let transforms = ColumnTransforms::parse(
+            "price_eur_mwh=abs, price_eur_mwh=ROUND2,,interval_minutes=null_if_zero",
+        )
+        .unwrap();
+        assert_eq!(
+            transforms.by_column["price_eur_mwh"],
+            vec![ColumnTransform::Abs, ColumnTransform::Round2]
+        );
+        assert_eq!(
+            transforms.by_column["interval_minutes"],
+            vec![ColumnTransform::NullIfZero]
+        );
+        assert!(ColumnTransforms::parse("").unwrap().by_column.is_empty());
+
+        let err = ColumnTransforms::parse("price_eur_mwh").unwrap_err();
+        assert!(err.contains("is not column=transform"), "{}", err);
+        let err = ColumnTransforms::parse("price_eur_mwh=floor").unwrap_err();
+        assert!(err.contains("unknown transform 'floor'"), "{}", err);
+        let err = ColumnTransforms::parse("price_type=abs").unwrap_err();
+        assert!(
+            err.contains("'price_type' is not a numeric column"),
+            "{}",
+            err
+        );
+        let err = ColumnTransforms::parse("price_eur=abs").unwrap_err();
+        assert!(
+            err.contains("'price_eur' is not a numeric column"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_apply_transforms_in_order() {
+        let transforms =
+            ColumnTransforms::parse("price_eur_mwh=abs, price_eur_mwh=round2").unwrap();
+
+        let cells = transforms.apply(
+            &columns(&["price_type", "price_eur_mwh"]),
+            vec![
+                Some(Cell::String("spot_market".to_string())),
+                Some(Cell::Numeric(-85.456)),
+            ],
+        );
+
+        assert_eq!(
+            format!("{:?}", cells),
+            "[Some(Cell::String(\"spot_market\")), Some(Cell::Numeric(85.46))]"
+        );
+    }
+
+    #[test]
+    fn test_null_if_zero_and_null_cells() {
+        let transforms = ColumnTransforms::parse("tso_amprion_mw=null_if_zero").unwrap();
+
+        let cells = transforms.apply(
+            &columns(&["tso_amprion_mw", "tso_amprion_mw", "tso_amprion_mw"]),
+            vec![Some(Cell::Numeric(0.0)), Some(Cell::Numeric(12.5)), None],
+        );
+
+        assert_eq!(
+            format!("{:?}", cells),
+            "[None, Some(Cell::Numeric(12.5)), None]"
+        );
+    }
+}
@@ -0,0 +1,229 @@
+//! Declared column types checked against the cells the FDW emits
+//!
+//! The `*_row_to_cells` converters pick a `Cell` variant per column name
+//! (`price_eur_mwh` is always `Cell::Numeric`). When the foreign table DDL
+//! declares a different type (`price_eur_mwh TEXT`), the host fails deep in
+//! the datum conversion with a message that names neither the column nor the
+//! table. [`check_column_types`] compares the projected columns' declared
+//! types with the expected ones before any request is made and lists every
+//! mismatch at once.
+//!
+//! Unknown columns (always NULL) and columns the host reports as
+//! `TypeOid::Other` (e.g. `VARCHAR`, domains) are not checked.
+
+use crate::bindings::supabase::wrappers::types::TypeOid;
+use crate::metrics;
+use crate::scan_warnings;
+use crate::token_refresh;
+
+/// Expected type of a column the FDW populates
+///
+/// # Returns
+///
+/// `None` for columns the FDW leaves NULL whatever their type (`fetched_at`,
+/// unknown names) and for unknown tables.
+pub(crate) fn expected_type(table: &str, column: &str) -> Option<TypeOid> {
+    // Interval columns shared by every time series table
+    let shared = match column {
+        "timestamp_utc" | "interval_end_utc" | "query_window_start" | "query_window_end" => {
+            Some(TypeOid::Timestamptz)
+        }
+        "interval_range" | "timestamp_local" | "interval_end_local" | "source_endpoint" => {
+            Some(TypeOid::String)
+        }
+        _ => None,
+    };
+
+    match table {
+        "renewable_energy_timeseries" => shared.or(match column {
+            "interval_minutes" => Some(TypeOid::I16),
+            "product_type" | "api_product" | "data_category" | "_raw" => Some(TypeOid::String),
+            "tso_50hertz_mw" | "tso_amprion_mw" | "tso_tennet_mw" | "tso_transnetbw_mw"
+            | "total_germany_mw" => Some(TypeOid::Numeric),
+            "has_missing_data" => Some(TypeOid::Bool),
+            _ => None,
+        }),
+        "electricity_market_prices" => shared.or(match column {
+            "granularity"
+            | "price_type"
+            | "product_category"
+            | "negative_logic_hours"
+            | "bidding_zone"
+            | "currency"
+            | "_raw" => Some(TypeOid::String),
+            "resolution_minutes" => Some(TypeOid::I32),
+            "price_eur_mwh" | "price_ct_kwh" => Some(TypeOid::Numeric),
+            "negative_flag_value" | "is_negative" => Some(TypeOid::Bool),
+            _ => None,
+        }),
+        "redispatch_events" => shared.or(match column {
+            "reason" | "direction" | "requesting_tso" | "instructing_tso" | "affected_facility"
+            | "energy_type" => Some(TypeOid::String),
+            "avg_power_mw" | "max_power_mw" | "total_energy_mwh" => Some(TypeOid::Numeric),
+            "interval_minutes" => Some(TypeOid::I32),
+            _ => None,
+        }),
+        "grid_status_timeseries" => shared.or(match column {
+            "grid_status" => Some(TypeOid::String),
+            _ => None,
+        }),
+        metrics::TABLE_NAME => match column {
+            "scans" | "requests" | "bytes_received" | "retries" | "token_cache_hits"
+            | "token_cache_misses" | "tokens_fetched" => Some(TypeOid::I64),
+            _ => None,
+        },
+        scan_warnings::TABLE_NAME => match column {
+            "kind" | "message" | "source_endpoint" => Some(TypeOid::String),
+            "row_count" => Some(TypeOid::I64),
+            _ => None,
+        },
+        token_refresh::TABLE_NAME => match column {
+            "refreshed_at" | "expires_at" => Some(TypeOid::Timestamptz),
+            "expires_in_seconds" => Some(TypeOid::I64),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Other tables that have a column the scanned table lacks
+///
+/// Catches quals copied from another table's query, e.g. `product_type` on
+/// `electricity_market_prices`: the column is always NULL there and the qual
+/// is not sent to the API.
+///
+/// # Returns
+///
+/// The tables with `column`; empty if `table` has it or is not a known table.
+pub(crate) fn tables_with_foreign_column(table: &str, column: &str) -> Vec<&'static str> {
+    if !crate::TABLE_NAMES.contains(&table) || expected_type(table, column).is_some() {
+        return Vec::new();
+    }
+
+    crate::TABLE_NAMES
+        .iter()
+        .copied()
+        .filter(|other| expected_type(other, column).is_some())
+        .collect()
+}
+
+/// Fail if a projected column is declared with a type its cells don't have
+///
+/// # Arguments
+///
+/// * `table` - Detected table name
+/// * `columns` - Projected columns with their declared types
+///
+/// # Returns
+///
+/// * `Ok(())` - Every checked column has its expected type
+/// * `Err(String)` - Lists each mismatched column with both types, e.g.
+///   `price_eur_mwh is text, expected numeric`
+pub(crate) fn check_column_types(table: &str, columns: &[(String, TypeOid)]) -> Result<(), String> {
+    let mismatches: Vec<String> = columns
+        .iter()
+        .filter(|(_, declared)| !matches!(declared, TypeOid::Other(_)))
+        .filter_map(|(name, declared)| {
+            let expected = expected_type(table, name)?;
+            (type_name(declared) != type_name(&expected)).then(|| {
+                format!(
+                    "{} is {}, expected {}",
+                    name,
+                    type_name(declared),
+                    type_name(&expected)
+                )
+            })
+        })
+        .collect();
+
+    if mismatches.is_empty() {
+        return Ok(());
+    }
+
+    Err(format!(
+        "Column type mismatch in foreign table '{}': {}. Fix the column types in CREATE FOREIGN TABLE",
+        table,
+        mismatches.join("; ")
+    ))
+}
+
+/// PostgreSQL name of a type, as written in DDL
+fn type_name(type_oid: &TypeOid) -> &str {
+    match type_oid {
+        TypeOid::Bool => "boolean",
+        TypeOid::I8 => "\"char\"",
+        TypeOid::I16 => "smallint",
+        TypeOid::F32 => "real",
+        TypeOid::I32 => "integer",
+        TypeOid::F64 => "double precision",
+        TypeOid::I64 => "bigint",
+        TypeOid::Numeric => "numeric",
+        TypeOid::String => "text",
+        TypeOid::Date => "date",
+        TypeOid::Timestamp => "timestamp",
+        TypeOid::Timestamptz => "timestamptz",
+        TypeOid::Json => "jsonb",
+        TypeOid::Uuid => "uuid",
+        TypeOid::Other(name) => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected_type_per_table() {
+        // interval_minutes is SMALLINT on renewable rows, INTEGER on redispatch events
+        assert_eq!(
+            expected_type("renewable_energy_timeseries", "interval_minutes")
+                .map(|t| type_name(&t).to_string()),
+            Some("smallint".to_string())
+        );
+        assert_eq!(
+            expected_type("redispatch_events", "interval_minutes")
+                .map(|t| type_name(&t).to_string()),
+            Some("integer".to_string())
+        );
+        assert!(expected_type("scan_metrics", "scans").is_some());
+
+        // Always NULL: any type is accepted
+        assert!(expected_type("renewable_energy_timeseries", "fetched_at").is_none());
+        assert!(expected_type("renewable_energy_timeseries", "comment").is_none());
+        assert!(expected_type("unknown_table", "timestamp_utc").is_none());
+    }
+
+    #[test]
+    fn test_tables_with_foreign_column() {
+        assert_eq!(
+            tables_with_foreign_column("electricity_market_prices", "product_type"),
+            vec!["renewable_energy_timeseries"]
+        );
+        assert_eq!(
+            tables_with_foreign_column("renewable_energy_timeseries", "energy_type"),
+            vec!["redispatch_events"]
+        );
+
+        // Own, shared and unknown columns; unknown tables
+        assert!(tables_with_foreign_column("electricity_market_prices", "price_type").is_empty());
+        assert!(
+            tables_with_foreign_column("electricity_market_prices", "timestamp_utc").is_empty()
+        );
+        assert!(tables_with_foreign_column("electricity_market_prices", "fetched_at").is_empty());
+        assert!(tables_with_foreign_column("unknown_table", "product_type").is_empty());
+    }
+
+    #[test]
+    fn test_unknown_and_other_columns_not_checked() {
+        let columns = vec![
+            ("fetched_at".to_string(), TypeOid::Date),
+            ("comment".to_string(), TypeOid::I32),
+            (
+                "grid_status".to_string(),
+                TypeOid::Other("character varying".to_string()),
+            ),
+        ];
+
+        assert!(check_column_types("grid_status_timeseries", &columns).is_ok());
+    }
+}
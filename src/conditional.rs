@@ -0,0 +1,362 @@
+//! Conditional requests (ETag / Last-Modified) for the NTP FDW session
+//!
+//! Past date ranges rarely change, yet every scan downloads them again. With
+//! server option `conditional_requests` enabled, the validators of each
+//! successful response (`etag`, `last-modified`) are kept per URL together with
+//! the body. The next request for that URL sends them back as `if-none-match`
+//! / `if-modified-since`; a `304 Not Modified` answer is then served from the
+//! stored body instead of an empty one, so the scan parses the same rows
+//! without transferring them again. A `200` replaces the stored entry.
+//!
+//! Like the circuit breaker, the cache lives on the `NtpFdw` singleton and
+//! persists across scans of one session. It keeps at most [`MAX_ENTRIES`] URLs
+//! and [`MAX_BYTES`] of bodies, evicting the least recently used entries.
+
+use std::collections::HashMap;
+
+/// URLs kept at most
+pub(crate) const MAX_ENTRIES: usize = 256;
+
+/// Total body bytes kept at most (a larger body is not cached)
+pub(crate) const MAX_BYTES: usize = 32 * 1024 * 1024;
+
+/// Validators and body of the last `200` response for one URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CachedResponse {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    body: String,
+    /// Tick of the last store or `304` hit, for LRU eviction
+    last_used: u64,
+}
+
+/// Per-URL response validators, keyed by request URL
+#[derive(Debug)]
+pub(crate) struct ValidatorCache {
+    entries: HashMap<String, CachedResponse>,
+    max_entries: usize,
+    max_bytes: usize,
+    /// Body bytes of all entries
+    bytes: usize,
+    /// Use counter, incremented on every store or hit
+    tick: u64,
+}
+
+impl Default for ValidatorCache {
+    fn default() -> Self {
+        Self::with_limits(MAX_ENTRIES, MAX_BYTES)
+    }
+}
+
+impl ValidatorCache {
+    /// Empty cache keeping at most `max_entries` URLs and `max_bytes` of bodies
+    pub(crate) fn with_limits(max_entries: usize, max_bytes: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            max_entries,
+            max_bytes,
+            bytes: 0,
+            tick: 0,
+        }
+    }
+
+    /// Conditional request headers for `url`
+    ///
+    /// Empty when no validated response is cached for the URL.
+    pub(crate) fn request_headers(&self, url: &str) -> Vec<(String, String)> {
+        let Some(entry) = self.entries.get(url) else {
+            return Vec::new();
+        };
+
+        let mut headers = Vec::new();
+        if let Some(etag) = &entry.etag {
+            headers.push(("if-none-match".to_string(), etag.clone()));
+        }
+        if let Some(last_modified) = &entry.last_modified {
+            headers.push(("if-modified-since".to_string(), last_modified.clone()));
+        }
+        headers
+    }
+
+    /// Resolve a response against the cache
+    ///
+    /// A `304` for a cached URL returns the cached body. Every other response
+    /// goes through `classify` first, so only a `200` body that classifies as
+    /// data (e.g. not an expired-token error page) is stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - Request URL (cache key)
+    /// * `status` - HTTP status of the response
+    /// * `headers` - Response headers (names matched case-insensitively)
+    /// * `body` - Response body
+    /// * `classify` - Maps a status and body to the fetch result
+    ///
+    /// # Returns
+    ///
+    /// - `304` with a cached entry → the cached body
+    /// - `200` → `classify` result; on success the entry is stored, or dropped
+    ///   without validators
+    /// - Anything else → `classify` result, cache untouched
+    pub(crate) fn resolve<E>(
+        &mut self,
+        url: &str,
+        status: u16,
+        headers: &[(String, String)],
+        body: String,
+        classify: impl FnOnce(u16, String) -> Result<String, E>,
+    ) -> Result<String, E> {
+        self.tick += 1;
+        if status == 304 {
+            if let Some(entry) = self.entries.get_mut(url) {
+                entry.last_used = self.tick;
+                return Ok(entry.body.clone());
+            }
+        }
+
+        let body = classify(status, body)?;
+        if status == 200 {
+            self.remove(url);
+            let etag = header_value(headers, "etag");
+            let last_modified = header_value(headers, "last-modified");
+            if etag.is_some() || last_modified.is_some() {
+                self.insert(
+                    url,
+                    CachedResponse {
+                        etag,
+                        last_modified,
+                        body: body.clone(),
+                        last_used: self.tick,
+                    },
+                );
+            }
+        }
+        Ok(body)
+    }
+
+    /// Store an entry, evicting least recently used ones to stay in the limits
+    fn insert(&mut self, url: &str, entry: CachedResponse) {
+        if entry.body.len() > self.max_bytes || self.max_entries == 0 {
+            return;
+        }
+
+        while self.entries.len() >= self.max_entries
+            || self.bytes + entry.body.len() > self.max_bytes
+        {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(url, _)| url.clone())
+            else {
+                break;
+            };
+            self.remove(&oldest);
+        }
+
+        self.bytes += entry.body.len();
+        self.entries.insert(url.to_string(), entry);
+    }
+
+    /// Drop the entry of `url`, if any
+    fn remove(&mut self, url: &str) {
+        if let Some(entry) = self.entries.remove(url) {
+            self.bytes -= entry.body.len();
+        }
+    }
+}
+
+/// First value of a response header, by case-insensitive name
+fn header_value(headers: &[(String, String)], name: &str) -> Option<String> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str =
+        "https://ds.netztransparenz.de/api/v1/data/hochrechnung/Solar/2024-10-24/2024-10-25";
+
+    fn headers(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    /// Stand-in for the FDW classification: `200` bodies are data unless
+    /// they are an error page, every other status is an error
+    fn classify(status: u16, body: String) -> Result<String, String> {
+        match status {
+            200 if !body.starts_with('{') => Ok(body),
+            _ => Err(format!("{}: {}", status, body)),
+        }
+    }
+
+    fn store(cache: &mut ValidatorCache, url: &str, etag: &str, body: &str) {
+        cache
+            .resolve(
+                url,
+                200,
+                &headers(&[("etag", etag)]),
+                body.to_string(),
+                classify,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_no_headers_before_first_response() {
+        let cache = ValidatorCache::default();
+        assert!(cache.request_headers(URL).is_empty());
+    }
+
+    #[test]
+    fn test_200_stores_validators() {
+        let mut cache = ValidatorCache::default();
+
+        let resolved = cache.resolve(
+            URL,
+            200,
+            &headers(&[
+                ("ETag", "\"v1\""),
+                ("Last-Modified", "Thu, 24 Oct 2024 12:00:00 GMT"),
+            ]),
+            "csv v1".to_string(),
+            classify,
+        );
+
+        assert_eq!(resolved, Ok("csv v1".to_string()));
+        assert_eq!(
+            cache.request_headers(URL),
+            headers(&[
+                ("if-none-match", "\"v1\""),
+                ("if-modified-since", "Thu, 24 Oct 2024 12:00:00 GMT"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_304_reuses_cached_body() {
+        let mut cache = ValidatorCache::default();
+        store(&mut cache, URL, "\"v1\"", "csv v1");
+
+        let resolved = cache.resolve(URL, 304, &[], String::new(), classify);
+
+        assert_eq!(resolved, Ok("csv v1".to_string()));
+        assert_eq!(
+            cache.request_headers(URL),
+            headers(&[("if-none-match", "\"v1\"")])
+        );
+    }
+
+    #[test]
+    fn test_200_refreshes_cached_body() {
+        let mut cache = ValidatorCache::default();
+        store(&mut cache, URL, "\"v1\"", "csv v1");
+        store(&mut cache, URL, "\"v2\"", "csv v2");
+
+        assert_eq!(
+            cache.request_headers(URL),
+            headers(&[("if-none-match", "\"v2\"")])
+        );
+        assert_eq!(
+            cache.resolve(URL, 304, &[], String::new(), classify),
+            Ok("csv v2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_200_without_validators_drops_entry() {
+        let mut cache = ValidatorCache::default();
+        store(&mut cache, URL, "\"v1\"", "csv v1");
+
+        cache
+            .resolve(URL, 200, &[], "csv v2".to_string(), classify)
+            .unwrap();
+
+        assert!(cache.request_headers(URL).is_empty());
+    }
+
+    #[test]
+    fn test_200_error_body_is_not_cached() {
+        let mut cache = ValidatorCache::default();
+        store(&mut cache, URL, "\"v1\"", "csv v1");
+
+        // An expired-token page behind a 200 fails and keeps the valid entry
+        let resolved = cache.resolve(
+            URL,
+            200,
+            &headers(&[("etag", "\"err\"")]),
+            r#"{"error":"invalid_token"}"#.to_string(),
+            classify,
+        );
+
+        assert!(resolved.is_err());
+        assert_eq!(
+            cache.resolve(URL, 304, &[], String::new(), classify),
+            Ok("csv v1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_other_statuses_pass_through() {
+        let mut cache = ValidatorCache::default();
+
+        // 304 without a cached entry is left for classification
+        assert_eq!(
+            cache.resolve(URL, 304, &[], String::new(), classify),
+            Err("304: ".to_string())
+        );
+        assert_eq!(
+            cache.resolve(
+                URL,
+                500,
+                &headers(&[("etag", "\"x\"")]),
+                "oops".to_string(),
+                classify
+            ),
+            Err("500: oops".to_string())
+        );
+        assert!(cache.request_headers(URL).is_empty());
+    }
+
+    #[test]
+    fn test_entry_limit_evicts_least_recently_used() {
+        let mut cache = ValidatorCache::with_limits(2, MAX_BYTES);
+        store(&mut cache, "a", "\"a\"", "csv a");
+        store(&mut cache, "b", "\"b\"", "csv b");
+
+        // A hit on "a" makes "b" the least recently used entry
+        cache
+            .resolve("a", 304, &[], String::new(), classify)
+            .unwrap();
+        store(&mut cache, "c", "\"c\"", "csv c");
+
+        assert!(!cache.request_headers("a").is_empty());
+        assert!(cache.request_headers("b").is_empty());
+        assert!(!cache.request_headers("c").is_empty());
+    }
+
+    #[test]
+    fn test_byte_limit_evicts_and_skips_large_bodies() {
+        let mut cache = ValidatorCache::with_limits(MAX_ENTRIES, 10);
+        store(&mut cache, "a", "\"a\"", "12345");
+        store(&mut cache, "b", "\"b\"", "12345");
+        store(&mut cache, "c", "\"c\"", "123");
+
+        assert!(cache.request_headers("a").is_empty());
+        assert!(!cache.request_headers("b").is_empty());
+        assert!(!cache.request_headers("c").is_empty());
+        assert_eq!(cache.bytes, 8);
+
+        // A body over the whole limit is returned but not kept
+        store(&mut cache, "d", "\"d\"", "12345678901");
+        assert!(cache.request_headers("d").is_empty());
+        assert_eq!(cache.bytes, 8);
+    }
+}
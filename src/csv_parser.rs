@@ -21,33 +21,127 @@
 //!
 //! let rows = parse_renewable_csv(csv, "prognose", "Solar", "2024-10-24", "2024-10-25").unwrap();
 //! assert_eq!(rows.len(), 1);
-//! assert_eq!(rows[0].product_type, "solar");
+//! assert_eq!(&*rows[0].product_type, "solar");
 //! ```
 
+use std::cell::RefCell;
+use std::rc::Rc;
+
 use csv::ReaderBuilder;
 
-use crate::csv_utils::get_field;
+use crate::csv_utils::{get_field, join_record, Column, HeaderIndex};
 use crate::error::{ApiError, NtpFdwError, ParseError};
 use crate::transformations::*;
-use crate::types::{PriceRow, RenewableRow};
+use crate::types::{PriceRow, PriceUnit, RenewableRow};
 
 // ============================================================================
 // Helper Functions
 // ============================================================================
 
+/// Interval timezone columns of renewable and price CSVs
+const TIMEZONE_COLUMNS: [&str; 2] = ["Zeitzone von", "Zeitzone bis"];
+
+/// Check the timezone columns, unless absent ones are taken as implicit UTC
+///
+/// Some exports drop `Zeitzone von` / `Zeitzone bis` and document all times
+/// as UTC. With `assume_utc` (server option `assume_utc_when_missing`, default
+/// true) a missing column reads as "UTC" (see [`timezone_field`]).
+fn validate_timezone_columns(
+    headers: &csv::StringRecord,
+    assume_utc: bool,
+) -> Result<(), ParseError> {
+    if assume_utc {
+        return Ok(());
+    }
+
+    for col in TIMEZONE_COLUMNS {
+        if !headers.iter().any(|h| h == col) {
+            return Err(ParseError::MissingColumn(col.to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether a CSV response lacks a `Zeitzone von` or `Zeitzone bis` column
+///
+/// Such responses parse only with `assume_utc` (server option
+/// `assume_utc_when_missing`), which reads their times as UTC.
+pub(crate) fn lacks_timezone_columns(csv_content: &str) -> bool {
+    let mut reader = ReaderBuilder::new()
+        .delimiter(b';')
+        .has_headers(true)
+        .trim(csv::Trim::All)
+        .from_reader(csv_content.as_bytes());
+    let Ok(headers) = reader.headers() else {
+        return false;
+    };
+
+    !TIMEZONE_COLUMNS
+        .iter()
+        .all(|column| headers.iter().any(|header| header == *column))
+}
+
+/// Value of a timezone column, or "UTC" if the header has no such column
+///
+/// Only reached with the column present or `assume_utc` set, see
+/// [`validate_timezone_columns`].
+fn timezone_field(column: Option<Column>, record: &csv::StringRecord) -> Result<&str, ParseError> {
+    match column {
+        Some(column) => column.get(record),
+        None => Ok("UTC"),
+    }
+}
+
+/// Interval columns shared by renewable and price CSVs, resolved once per response
+#[derive(Debug, Clone, Copy)]
+struct IntervalColumns {
+    datum: Column,
+    von: Column,
+    bis: Column,
+    tz_von: Option<Column>,
+    tz_bis: Option<Column>,
+}
+
+impl IntervalColumns {
+    /// Resolve the interval columns of a validated header
+    fn new(index: &HeaderIndex) -> Result<Self, ParseError> {
+        Ok(Self {
+            datum: index.column("Datum")?,
+            von: index.column("von")?,
+            bis: index.column("bis")?,
+            tz_von: index.optional_column(TIMEZONE_COLUMNS[0]),
+            tz_bis: index.optional_column(TIMEZONE_COLUMNS[1]),
+        })
+    }
+
+    /// Parse a record's interval into (timestamp_utc, interval_end_utc)
+    fn parse(&self, record: &csv::StringRecord) -> Result<(String, String), ParseError> {
+        parse_interval_timestamps(
+            self.datum.get(record)?,
+            self.von.get(record)?,
+            self.bis.get(record)?,
+            timezone_field(self.tz_von, record)?,
+            timezone_field(self.tz_bis, record)?,
+        )
+    }
+}
+
 /// Validate renewable energy CSV header has all required columns
 ///
 /// # Required Columns
 ///
-/// - Datum, von, bis, Zeitzone von, Zeitzone bis
+/// - Datum, von, bis
+/// - Zeitzone von, Zeitzone bis (unless `assume_utc`)
 /// - 50Hertz (MW), Amprion (MW), TenneT TSO (MW), TransnetBW (MW)
-fn validate_renewable_header(headers: &csv::StringRecord) -> Result<(), ParseError> {
+fn validate_renewable_header(
+    headers: &csv::StringRecord,
+    assume_utc: bool,
+) -> Result<(), ParseError> {
     let required = vec![
         "Datum",
         "von",
         "bis",
-        "Zeitzone von",
-        "Zeitzone bis",
         "50Hertz (MW)",
         "Amprion (MW)",
         "TenneT TSO (MW)",
@@ -60,32 +154,43 @@ fn validate_renewable_header(headers: &csv::StringRecord) -> Result<(), ParseErr
         }
     }
 
-    Ok(())
+    validate_timezone_columns(headers, assume_utc)
 }
 
+/// Main spot price column headers, in order of preference
+///
+/// Existing endpoints label prices in ct/kWh; some market data endpoints
+/// publish EUR/MWh directly.
+const SPOT_PRICE_COLUMNS: [&str; 2] = ["Spotmarktpreis in ct/kWh", "Spotmarktpreis in EUR/MWh"];
+
 /// Validate price CSV header has all required columns
 ///
 /// # Required Columns
 ///
-/// - Datum, von, bis, Zeitzone von, Zeitzone bis
-/// - Spotmarktpreis in ct/kWh
-fn validate_price_header(headers: &csv::StringRecord) -> Result<(), ParseError> {
-    let required = vec![
-        "Datum",
-        "von",
-        "bis",
-        "Zeitzone von",
-        "Zeitzone bis",
-        "Spotmarktpreis in ct/kWh",
-    ];
+/// - Datum, von, bis
+/// - Zeitzone von, Zeitzone bis (unless `assume_utc`)
+/// - Spotmarktpreis in ct/kWh (or Spotmarktpreis in EUR/MWh)
+///
+/// # Returns
+///
+/// The main spot price column header present in the CSV
+fn validate_price_header(
+    headers: &csv::StringRecord,
+    assume_utc: bool,
+) -> Result<&'static str, ParseError> {
+    let required = vec!["Datum", "von", "bis"];
 
     for col in required {
         if !headers.iter().any(|h| h == col) {
             return Err(ParseError::MissingColumn(col.to_string()));
         }
     }
+    validate_timezone_columns(headers, assume_utc)?;
 
-    Ok(())
+    SPOT_PRICE_COLUMNS
+        .into_iter()
+        .find(|col| headers.iter().any(|h| h == *col))
+        .ok_or_else(|| ParseError::MissingColumn(SPOT_PRICE_COLUMNS[0].to_string()))
 }
 
 // ============================================================================
@@ -129,103 +234,449 @@ pub fn parse_renewable_csv(
     date_from: &str,
     date_to: &str,
 ) -> Result<Vec<RenewableRow>, NtpFdwError> {
-    // Stop at metadata footer (=== marker)
-    let csv_data = csv_content.split("===").next().unwrap_or(csv_content);
+    parse_renewable_records(
+        csv_content,
+        endpoint,
+        Some(product),
+        date_from,
+        date_to,
+        &[],
+        true,
+        true,
+    )
+}
 
-    // Configure CSV reader for German format
-    let mut reader = ReaderBuilder::new()
-        .delimiter(b';')
-        .has_headers(true)
-        .flexible(false) // Strict column count
-        .trim(csv::Trim::All) // Trim whitespace
-        .from_reader(csv_data.as_bytes());
+/// Parse a combined "all products" renewable CSV into RenewableRow structs
+///
+/// Same layout as [`parse_renewable_csv`] plus a `Produkt` column carrying the
+/// API product name per row ("Solar", "Wind", "Windonshore", "Windoffshore").
+/// Rows are demultiplexed into per-product `RenewableRow`s with the same
+/// `product_type` and `source_endpoint` a per-product fetch would produce.
+///
+/// # Arguments
+///
+/// * `csv_content` - Raw CSV string from the combined endpoint
+/// * `endpoint` - Endpoint name ("hochrechnung", "onlinehochrechnung")
+/// * `date_from` - Start date for source_endpoint metadata
+/// * `date_to` - End date for source_endpoint metadata
+///
+/// # Example
+///
+/// ```
+/// # use supabase_fdw_ntp::csv_parser::parse_combined_renewable_csv;
+/// let csv = r#"Datum;von;Zeitzone von;bis;Zeitzone bis;Produkt;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)
+/// 2024-10-24;06:00;UTC;06:15;UTC;Solar;100,5;200,3;300,7;150,2
+/// 2024-10-24;06:00;UTC;06:15;UTC;Wind;50,0;60,0;70,0;80,0"#;
+///
+/// let rows = parse_combined_renewable_csv(csv, "hochrechnung", "2024-10-24", "2024-10-25").unwrap();
+/// assert_eq!(&*rows[0].product_type, "solar");
+/// assert_eq!(&*rows[1].product_type, "wind_onshore");
+/// ```
+pub fn parse_combined_renewable_csv(
+    csv_content: &str,
+    endpoint: &str,
+    date_from: &str,
+    date_to: &str,
+) -> Result<Vec<RenewableRow>, NtpFdwError> {
+    parse_renewable_records(
+        csv_content,
+        endpoint,
+        None,
+        date_from,
+        date_to,
+        &[],
+        true,
+        true,
+    )
+}
 
-    // Get headers for column indexing
-    // Note: We clone the headers here to avoid borrow checker conflicts.
-    // The csv::Reader requires mutable access during iteration (reader.records()),
-    // but we also need immutable access to headers for column lookups in each row.
-    // Cloning is the simplest solution and has minimal performance impact
-    // (headers are a small StringRecord with ~9 column names).
-    let headers = reader
-        .headers()
-        .map_err(|e| {
-            // Provide detailed error instead of generic "EmptyResponse"
-            if csv_data.is_empty() {
-                NtpFdwError::from(ApiError::EmptyResponse)
-            } else {
-                NtpFdwError::from(ParseError::CsvFormat(format!(
-                    "Failed to read CSV headers: {}",
-                    e
-                )))
-            }
-        })?
-        .clone();
+/// Parse a per-product or combined renewable CSV with extra NULL sentinels
+///
+/// `product: Some(..)` parses like [`parse_renewable_csv`], `product: None` like
+/// [`parse_combined_renewable_csv`]. TSO zone values matching one of
+/// `null_sentinels` (server option `null_sentinels`) become NULL in addition to
+/// the built-in [`NULL_SENTINELS`](crate::transformations::NULL_SENTINELS).
+/// `assume_utc` reads missing `Zeitzone` columns as "UTC" instead of failing
+/// (server option `assume_utc_when_missing`). `keep_raw` keeps each row's
+/// original record in `raw` (server option `expose_raw`); without it, rows
+/// carry `raw: None` and the record is never joined.
+///
+/// # Example
+///
+/// ```
+/// # use supabase_fdw_ntp::csv_parser::parse_renewable_csv_with_sentinels;
+/// let csv = r#"Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)
+/// 2024-10-24;06:00;UTC;06:15;UTC;x;200,3;k.A.;150,2"#;
+///
+/// let sentinels = vec!["x".to_string()];
+/// let rows = parse_renewable_csv_with_sentinels(
+///     csv, "prognose", Some("Solar"), "2024-10-24", "2024-10-25", &sentinels, true, true,
+/// ).unwrap();
+/// assert_eq!(rows[0].tso_50hertz_mw, None);
+/// assert_eq!(rows[0].tso_tennet_mw, None);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn parse_renewable_csv_with_sentinels(
+    csv_content: &str,
+    endpoint: &str,
+    product: Option<&str>,
+    date_from: &str,
+    date_to: &str,
+    null_sentinels: &[String],
+    assume_utc: bool,
+    keep_raw: bool,
+) -> Result<Vec<RenewableRow>, NtpFdwError> {
+    parse_renewable_records(
+        csv_content,
+        endpoint,
+        product,
+        date_from,
+        date_to,
+        null_sentinels,
+        assume_utc,
+        keep_raw,
+    )
+}
 
-    // Validate required columns
-    validate_renewable_header(&headers)?;
+/// Parse a single pre-split renewable CSV record into a RenewableRow
+///
+/// Row-level counterpart of [`parse_renewable_csv_with_sentinels`] for callers
+/// that already have one record split into fields (e.g. a trigger validating an
+/// imported line). `header` is the header row of the response; the record is
+/// parsed exactly like a data row of that response, with the same column
+/// validation, transformations and metadata. Fields and header names are
+/// trimmed like the CSV reader does.
+///
+/// # Arguments
+///
+/// * `fields` - Values of one data row, in header order
+/// * `header` - Column names of the response
+/// * `endpoint` - Endpoint name ("prognose", "hochrechnung", "onlinehochrechnung")
+/// * `product` - API product name, or `None` to read it from the `Produkt` column
+/// * `date_from` - Start date for source_endpoint metadata
+/// * `date_to` - End date for source_endpoint metadata
+///
+/// # Returns
+///
+/// * `Ok(RenewableRow)` - Parsed row
+/// * `Err(NtpFdwError)` - Field count mismatch, missing columns, invalid data
+///
+/// # Example
+///
+/// ```
+/// # use supabase_fdw_ntp::csv_parser::parse_renewable_record;
+/// let header = [
+///     "Datum", "von", "Zeitzone von", "bis", "Zeitzone bis",
+///     "50Hertz (MW)", "Amprion (MW)", "TenneT TSO (MW)", "TransnetBW (MW)",
+/// ];
+/// let fields = ["2024-10-24", "06:00", "UTC", "06:15", "UTC", "100,5", "200,3", "N.A.", "150,2"];
+///
+/// let row = parse_renewable_record(
+///     &fields, &header, "hochrechnung", Some("Solar"), "2024-10-24", "2024-10-25",
+/// ).unwrap();
+/// assert_eq!(row.tso_50hertz_mw, Some(100.5));
+/// assert_eq!(row.tso_tennet_mw, None);
+/// ```
+pub fn parse_renewable_record(
+    fields: &[&str],
+    header: &[&str],
+    endpoint: &str,
+    product: Option<&str>,
+    date_from: &str,
+    date_to: &str,
+) -> Result<RenewableRow, NtpFdwError> {
+    // Strict column count, like the CSV reader (flexible = false)
+    if fields.len() != header.len() {
+        return Err(ParseError::CsvFormat(format!(
+            "Record has {} fields, header has {} columns",
+            fields.len(),
+            header.len()
+        ))
+        .into());
+    }
+
+    let headers: csv::StringRecord = header.iter().map(|name| name.trim()).collect();
+    let record: csv::StringRecord = fields.iter().map(|field| field.trim()).collect();
+
+    let parser = RenewableRecordParser::new(
+        &headers,
+        endpoint,
+        product,
+        date_from,
+        date_to,
+        &[],
+        true,
+        true,
+    )?;
+    Ok(parser.parse(&record)?)
+}
 
-    // Pre-compute metadata (same for all rows)
-    // Note: These clones are necessary because we push each row into the Vec,
-    // transferring ownership. We could use Rc<String> to eliminate per-row clones,
-    // but the performance gain would be negligible (~100 rows × 3 strings = 300 allocations),
-    // and code simplicity is more valuable here.
-    let product_type = normalize_product_type(product)?;
-    let data_category = extract_data_category(endpoint)?;
-    let source_endpoint = build_source_endpoint(endpoint, product, date_from, date_to);
+/// Row parser for one renewable response
+///
+/// Validates the header once and pre-computes the metadata shared by all
+/// records, so [`parse_renewable_records`] and [`parse_renewable_record`] parse
+/// every record through the same code.
+struct RenewableRecordParser<'a> {
+    interval: IntervalColumns,
+    tso_columns: [Column; 4],
+    endpoint: &'a str,
+    date_from: &'a str,
+    date_to: &'a str,
+    null_sentinels: &'a [String],
+    /// Keep each row's original record in `raw`
+    keep_raw: bool,
+    data_category: Rc<str>,
+
+    /// Product of every row, or the `Produkt` column of a combined response
+    product: RowProduct,
+
+    /// Products seen so far in a combined response, so rows of one product
+    /// share its strings
+    combined_products: RefCell<Vec<ProductStrings>>,
+}
 
-    let mut rows = Vec::new();
+/// Row strings of one product: (product_type, api_product, source_endpoint)
+type ProductStrings = (Rc<str>, Rc<str>, Rc<str>);
 
-    // Parse each data row
-    for result in reader.records() {
-        let record =
-            result.map_err(|e| ParseError::CsvFormat(format!("CSV parse error: {}", e)))?;
+/// Where a renewable response's rows take their product from
+enum RowProduct {
+    /// Per-product response: the same strings for every row
+    Fixed(ProductStrings),
+
+    /// Combined response: the product is read per row from this column
+    Column(Column),
+}
+
+impl<'a> RenewableRecordParser<'a> {
+    /// Validate the header row and pre-compute the response metadata
+    ///
+    /// With `product: None`, the header must have a `Produkt` column. With
+    /// `assume_utc`, missing `Zeitzone` columns read as "UTC".
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        headers: &csv::StringRecord,
+        endpoint: &'a str,
+        product: Option<&str>,
+        date_from: &'a str,
+        date_to: &'a str,
+        null_sentinels: &'a [String],
+        assume_utc: bool,
+        keep_raw: bool,
+    ) -> Result<Self, NtpFdwError> {
+        // Validate required columns
+        validate_renewable_header(headers, assume_utc)?;
+
+        // Resolve column indices once instead of looking them up per field
+        let index = HeaderIndex::new(headers);
+        let interval = IntervalColumns::new(&index)?;
+        let tso_columns = [
+            index.column("50Hertz (MW)")?,
+            index.column("Amprion (MW)")?,
+            index.column("TenneT TSO (MW)")?,
+            index.column("TransnetBW (MW)")?,
+        ];
+
+        // Pre-compute metadata (same for all rows of a per-product response),
+        // allocated once and shared by the rows
+        let data_category = Rc::from(extract_data_category(endpoint)?);
+        let product = match product {
+            Some(product) => {
+                RowProduct::Fixed(product_strings(endpoint, product, date_from, date_to)?)
+            }
+            None => RowProduct::Column(index.column("Produkt")?),
+        };
 
-        // Extract timestamp fields
-        let datum = get_field(&record, &headers, "Datum")?;
-        let von = get_field(&record, &headers, "von")?;
-        let bis = get_field(&record, &headers, "bis")?;
-        let tz_von = get_field(&record, &headers, "Zeitzone von")?;
-        let tz_bis = get_field(&record, &headers, "Zeitzone bis")?;
+        Ok(Self {
+            interval,
+            tso_columns,
+            endpoint,
+            date_from,
+            date_to,
+            null_sentinels,
+            keep_raw,
+            data_category,
+            product,
+            combined_products: RefCell::default(),
+        })
+    }
 
+    /// Parse one data row (callers tag errors with the row number)
+    fn parse(&self, record: &csv::StringRecord) -> Result<RenewableRow, ParseError> {
         // Parse timestamps with midnight-crossing detection (Bug #5 fix)
-        let (timestamp_utc, interval_end_utc) =
-            parse_interval_timestamps(datum, von, bis, tz_von, tz_bis)?;
+        let (timestamp_utc, interval_end_utc) = self.interval.parse(record)?;
         let interval_minutes = calculate_interval_minutes(&timestamp_utc, &interval_end_utc)?;
 
-        // Extract TSO zone values
-        let tso_50hertz = get_field(&record, &headers, "50Hertz (MW)")?;
-        let tso_amprion = get_field(&record, &headers, "Amprion (MW)")?;
-        let tso_tennet = get_field(&record, &headers, "TenneT TSO (MW)")?;
-        let tso_transnetbw = get_field(&record, &headers, "TransnetBW (MW)")?;
-
-        // Parse TSO zones with transformation functions
-        let tso_data = vec![
-            ("50Hertz (MW)", tso_50hertz),
-            ("Amprion (MW)", tso_amprion),
-            ("TenneT TSO (MW)", tso_tennet),
-            ("TransnetBW (MW)", tso_transnetbw),
+        // Extract and parse TSO zone values
+        let [tso_50hertz, tso_amprion, tso_tennet, tso_transnetbw] = self.tso_columns;
+        let tso_data = [
+            (tso_50hertz.name(), tso_50hertz.get(record)?),
+            (tso_amprion.name(), tso_amprion.get(record)?),
+            (tso_tennet.name(), tso_tennet.get(record)?),
+            (tso_transnetbw.name(), tso_transnetbw.get(record)?),
         ];
-        let zones = parse_tso_zones(&tso_data)?;
+        let zones = parse_tso_zones_with_sentinels(&tso_data, self.null_sentinels)?;
+
+        // Combined responses carry the product per row
+        let (product_type, api_product, source_endpoint) = match &self.product {
+            RowProduct::Fixed(strings) => strings.clone(),
+            RowProduct::Column(product_column) => {
+                let row_product = product_column.get(record)?;
+                let mut seen = self.combined_products.borrow_mut();
+                match seen.iter().find(|(_, api, _)| &**api == row_product) {
+                    Some(strings) => strings.clone(),
+                    None => {
+                        let strings = product_strings(
+                            self.endpoint,
+                            row_product,
+                            self.date_from,
+                            self.date_to,
+                        )?;
+                        seen.push(strings.clone());
+                        strings
+                    }
+                }
+            }
+        };
 
-        rows.push(RenewableRow {
+        Ok(RenewableRow {
             timestamp_utc,
             interval_end_utc,
             interval_minutes,
-            product_type: product_type.clone(),
-            data_category: data_category.clone(),
+            product_type,
+            api_product,
+            data_category: self.data_category.clone(),
             tso_50hertz_mw: zones.tso_50hertz_mw,
             tso_amprion_mw: zones.tso_amprion_mw,
             tso_tennet_mw: zones.tso_tennet_mw,
             tso_transnetbw_mw: zones.tso_transnetbw_mw,
-            source_endpoint: source_endpoint.clone(),
-        });
+            source_endpoint,
+            raw: self.keep_raw.then(|| join_record(record)),
+        })
+    }
+}
+
+/// Allocate the row strings of one product of a renewable response
+fn product_strings(
+    endpoint: &str,
+    product: &str,
+    date_from: &str,
+    date_to: &str,
+) -> Result<ProductStrings, ParseError> {
+    Ok((
+        Rc::from(normalize_product_type(product)?),
+        Rc::from(product),
+        Rc::from(build_source_endpoint(endpoint, product, date_from, date_to)),
+    ))
+}
+
+/// Shared renewable CSV parse loop
+///
+/// With `product: None`, the product is read per row from the `Produkt` column.
+#[allow(clippy::too_many_arguments)]
+fn parse_renewable_records(
+    csv_content: &str,
+    endpoint: &str,
+    product: Option<&str>,
+    date_from: &str,
+    date_to: &str,
+    null_sentinels: &[String],
+    assume_utc: bool,
+    keep_raw: bool,
+) -> Result<Vec<RenewableRow>, NtpFdwError> {
+    // Stop at metadata footer (=== marker)
+    let csv_data = csv_content.split("===").next().unwrap_or(csv_content);
+
+    // Configure CSV reader for German format
+    let mut reader = ReaderBuilder::new()
+        .delimiter(b';')
+        .has_headers(true)
+        .flexible(false) // Strict column count
+        .trim(csv::Trim::All) // Trim whitespace
+        .from_reader(csv_data.as_bytes());
+
+    // Get headers for validation; per-row lookups go through the parser's HeaderIndex
+    let headers = reader.headers().map_err(|e| {
+        // Provide detailed error instead of generic "EmptyResponse"
+        if csv_data.is_empty() {
+            NtpFdwError::from(ApiError::EmptyResponse)
+        } else {
+            NtpFdwError::from(ParseError::CsvFormat(format!(
+                "Failed to read CSV headers: {}",
+                e
+            )))
+        }
+    })?;
+
+    let parser = RenewableRecordParser::new(
+        headers,
+        endpoint,
+        product,
+        date_from,
+        date_to,
+        null_sentinels,
+        assume_utc,
+        keep_raw,
+    )?;
+
+    let mut rows = Vec::new();
+
+    // Parse each data row (1-based row numbers, header excluded)
+    for (index, result) in reader.records().enumerate() {
+        let record =
+            result.map_err(|e| ParseError::CsvFormat(format!("CSV parse error: {}", e)))?;
+        rows.push(parser.parse(&record).map_err(|e| e.at_row(index + 1))?);
     }
 
     Ok(rows)
 }
 
+/// Optional extra price columns in spot price exports, with the `price_type` their rows get
+///
+/// The main `Spotmarktpreis` column is always required; these are only
+/// read when present in the header, so single-column feeds are unaffected.
+const EXTRA_SPOT_PRICE_COLUMNS: [(&str, &str); 3] = [
+    ("Day-Ahead in ct/kWh", "spot_day_ahead"),
+    ("Intraday in ct/kWh", "spot_intraday"),
+    ("Auktion in ct/kWh", "spot_auction"),
+];
+
+/// Header names of an optional bidding zone column in spot price exports
+const BIDDING_ZONE_COLUMNS: [&str; 2] = ["Gebotszone", "Bidding Zone"];
+
+/// Header names of an optional currency column in spot price exports
+const CURRENCY_COLUMNS: [&str; 2] = ["Währung", "Currency"];
+
+/// First of `names` present in the header
+fn optional_column(index: &HeaderIndex, names: &[&'static str]) -> Option<Column> {
+    names.iter().find_map(|name| index.optional_column(name))
+}
+
+/// Value of an optional column, `None` if the column is absent or the cell blank
+fn optional_field(
+    record: &csv::StringRecord,
+    column: Option<Column>,
+) -> Result<Option<String>, ParseError> {
+    let Some(column) = column else {
+        return Ok(None);
+    };
+    let value = column.get(record)?;
+    Ok((!value.is_empty()).then(|| value.to_string()))
+}
+
 /// Parse spot market price CSV into PriceRow structs
 ///
+/// Emits one row per CSV line for the main price column, plus one row per
+/// recognized extra price column present in the header (see
+/// `EXTRA_SPOT_PRICE_COLUMNS`), tagged via `price_type`. Extra column cells
+/// holding a NULL sentinel (built-in or `null_sentinels`) produce no row.
+///
+/// An optional bidding zone (`Gebotszone`) or currency (`Währung`) column, when
+/// present, is carried into `bidding_zone` / `currency` of every row of its
+/// line, so multi-zone feeds stay distinguishable. Both are `None` otherwise.
+///
+/// The unit is detected from each price column header: `in ct/kWh` values are
+/// converted to EUR/MWh, `in EUR/MWh` values are passed through unchanged.
+///
 /// # Arguments
 ///
 /// * `csv_content` - Raw CSV string from API
@@ -248,7 +699,7 @@ pub fn parse_renewable_csv(
 ///
 /// let rows = parse_price_csv(csv, "Spotmarktpreise", "2024-10-23", "2024-10-24").unwrap();
 /// assert_eq!(rows.len(), 2);
-/// assert_eq!(rows[0].price_type, "spot_market");
+/// assert_eq!(&*rows[0].price_type, "spot_market");
 /// assert!(rows[0].price_eur_mwh.unwrap() > 0.0);
 /// assert!(rows[1].price_eur_mwh.unwrap() < 0.0); // Negative price
 /// ```
@@ -257,6 +708,27 @@ pub fn parse_price_csv(
     endpoint: &str,
     date_from: &str,
     date_to: &str,
+) -> Result<Vec<PriceRow>, NtpFdwError> {
+    parse_price_csv_with_mode(csv_content, endpoint, date_from, date_to, true, &[], true)
+}
+
+/// Parse spot market price CSV, optionally requiring the timezone columns
+///
+/// With `assume_utc = true` (the default, see [`parse_price_csv`]) missing
+/// `Zeitzone von` / `Zeitzone bis` columns read as "UTC"; with
+/// `assume_utc = false` (server option `assume_utc_when_missing 'false'`)
+/// they fail the parse. `null_sentinels` (server option `null_sentinels`) are
+/// read as NULL in extra price columns, in addition to the built-ins.
+/// `keep_raw` keeps each row's original record in `raw` (server option
+/// `expose_raw`).
+pub fn parse_price_csv_with_mode(
+    csv_content: &str,
+    endpoint: &str,
+    date_from: &str,
+    date_to: &str,
+    assume_utc: bool,
+    null_sentinels: &[String],
+    keep_raw: bool,
 ) -> Result<Vec<PriceRow>, NtpFdwError> {
     // Stop at metadata footer
     let csv_data = csv_content.split("===").next().unwrap_or(csv_content);
@@ -268,62 +740,91 @@ pub fn parse_price_csv(
         .trim(csv::Trim::All)
         .from_reader(csv_data.as_bytes());
 
-    // Get and validate headers
-    // Note: Clone to avoid borrow checker conflict (see renewable parser above for details)
-    let headers = reader
-        .headers()
-        .map_err(|e| {
-            // Provide detailed error instead of generic "EmptyResponse"
-            if csv_data.is_empty() {
-                NtpFdwError::from(ApiError::EmptyResponse)
-            } else {
-                NtpFdwError::from(ParseError::CsvFormat(format!(
-                    "Failed to read CSV headers: {}",
-                    e
-                )))
-            }
-        })?
-        .clone();
-    validate_price_header(&headers)?;
-
-    // Pre-compute metadata (cloned per row - see note above)
-    let price_type = detect_price_type(endpoint);
-    let source_endpoint = format!("{}/{}/{}", endpoint, date_from, date_to);
+    // Get and validate headers (columns are resolved to indices below)
+    let headers = reader.headers().map_err(|e| {
+        // Provide detailed error instead of generic "EmptyResponse"
+        if csv_data.is_empty() {
+            NtpFdwError::from(ApiError::EmptyResponse)
+        } else {
+            NtpFdwError::from(ParseError::CsvFormat(format!(
+                "Failed to read CSV headers: {}",
+                e
+            )))
+        }
+    })?;
+    let price_header = validate_price_header(headers, assume_utc)?;
+    let price_unit = PriceUnit::from_header(price_header).unwrap_or(PriceUnit::CtKwh);
+
+    // Resolve column indices once instead of looking them up per field
+    let index = HeaderIndex::new(headers);
+    let interval = IntervalColumns::new(&index)?;
+    let price_column = index.column(price_header)?;
+
+    // Pre-compute metadata, allocated once and shared by the rows
+    let granularity: Rc<str> = Rc::from("hourly");
+    let price_type: Rc<str> = Rc::from(detect_price_type(endpoint));
+    let source_endpoint: Rc<str> = Rc::from(format!("{}/{}/{}", endpoint, date_from, date_to));
+
+    // Extra price columns present in this feed (presence-gated)
+    let extra_columns: Vec<(Column, Rc<str>)> = EXTRA_SPOT_PRICE_COLUMNS
+        .iter()
+        .filter_map(|(column, price_type)| {
+            Some((index.optional_column(column)?, Rc::from(*price_type)))
+        })
+        .collect();
+    let bidding_zone_column = optional_column(&index, &BIDDING_ZONE_COLUMNS);
+    let currency_column = optional_column(&index, &CURRENCY_COLUMNS);
 
     let mut rows = Vec::new();
 
-    // Parse each data row
-    for result in reader.records() {
-        let record =
-            result.map_err(|e| ParseError::CsvFormat(format!("CSV parse error: {}", e)))?;
-
-        // Extract fields
-        let datum = get_field(&record, &headers, "Datum")?;
-        let von = get_field(&record, &headers, "von")?;
-        let bis = get_field(&record, &headers, "bis")?;
-        let tz_von = get_field(&record, &headers, "Zeitzone von")?;
-        let tz_bis = get_field(&record, &headers, "Zeitzone bis")?;
-        let price_ct_kwh = get_field(&record, &headers, "Spotmarktpreis in ct/kWh")?;
-
+    // Parse one data row (errors are tagged with the row number below)
+    let parse_record = |record: csv::StringRecord| -> Result<Vec<PriceRow>, ParseError> {
         // Parse timestamps with midnight-crossing detection (Bug #5 fix)
-        let (timestamp_utc, interval_end_utc) =
-            parse_interval_timestamps(datum, von, bis, tz_von, tz_bis)?;
-
-        // Parse and convert price
-        let price_ct = parse_german_decimal(price_ct_kwh)?;
-        let price_eur_mwh = convert_price_to_eur_mwh(price_ct);
-
-        rows.push(PriceRow {
-            timestamp_utc,
-            interval_end_utc,
-            granularity: "hourly".to_string(),
-            price_type: price_type.clone(),
+        let (timestamp_utc, interval_end_utc) = interval.parse(&record)?;
+        let price_value = price_column.get(&record)?;
+
+        // Every row of this record carries the same original line, zone and currency
+        let raw = keep_raw.then(|| join_record(&record));
+        let bidding_zone = optional_field(&record, bidding_zone_column)?;
+        let currency = optional_field(&record, currency_column)?;
+
+        // Parse and convert price (only ct/kWh sources are converted)
+        let price_eur_mwh = price_unit.to_eur_mwh(parse_german_decimal(price_value)?);
+
+        let spot_row = |row_price_type: &Rc<str>, price_eur_mwh: f64| PriceRow {
+            timestamp_utc: timestamp_utc.clone(),
+            interval_end_utc: interval_end_utc.clone(),
+            granularity: granularity.clone(),
+            price_type: row_price_type.clone(),
             price_eur_mwh: Some(price_eur_mwh),
             product_category: None,
             negative_logic_hours: None,
             negative_flag_value: None,
+            bidding_zone: bidding_zone.clone(),
+            currency: currency.clone(),
             source_endpoint: source_endpoint.clone(),
-        });
+            raw: raw.clone(),
+        };
+
+        let mut record_rows = vec![spot_row(&price_type, price_eur_mwh)];
+
+        for (column, column_price_type) in &extra_columns {
+            let value = column.get(&record)?;
+            let Some(price) = parse_price_value_with_sentinels(value, null_sentinels)? else {
+                continue;
+            };
+            let unit = PriceUnit::from_header(column.name()).unwrap_or(PriceUnit::CtKwh);
+            record_rows.push(spot_row(column_price_type, unit.to_eur_mwh(price)));
+        }
+
+        Ok(record_rows)
+    };
+
+    // Parse each data row (1-based row numbers, header excluded)
+    for (index, result) in reader.records().enumerate() {
+        let record =
+            result.map_err(|e| ParseError::CsvFormat(format!("CSV parse error: {}", e)))?;
+        rows.extend(parse_record(record).map_err(|e| e.at_row(index + 1))?);
     }
 
     Ok(rows)
@@ -332,7 +833,8 @@ pub fn parse_price_csv(
 /// Parse NegativePreise CSV (different format from spot prices) - Bug #7 fix
 ///
 /// The NegativePreise endpoint has a completely different CSV structure:
-/// - Combined datetime column: "2024-10-20 00:00" (not separate Datum/von/bis)
+/// - Combined datetime column: "2024-10-20 00:00" (not separate Datum/von/bis),
+///   seconds ("2024-10-20 00:00:30") are accepted too
 /// - Duration flag columns: Stunde1, Stunde3, Stunde4, Stunde6
 /// - Boolean format: "1" (true) or "0" (false)
 ///
@@ -341,6 +843,7 @@ pub fn parse_price_csv(
 /// * `csv_content` - CSV string from NegativePreise endpoint
 /// * `_date_from` - Start date (for logging, not used in parsing)
 /// * `_date_to` - End date (for logging, not used in parsing)
+/// * `keep_raw` - Keep each row's original record in `raw` (server option `expose_raw`)
 ///
 /// # Returns
 ///
@@ -358,6 +861,7 @@ pub fn parse_negative_price_flags_csv(
     csv_content: &str,
     _date_from: &str,
     _date_to: &str,
+    keep_raw: bool,
 ) -> Result<Vec<PriceRow>, NtpFdwError> {
     let mut reader = ReaderBuilder::new()
         .delimiter(b';')
@@ -380,6 +884,11 @@ pub fn parse_negative_price_flags_csv(
         .clone();
     let mut rows = Vec::new();
 
+    // Shared by every row (4 per hour)
+    let granularity: Rc<str> = Rc::from("hourly");
+    let price_type: Rc<str> = Rc::from("negative_flag");
+    let source_endpoint: Rc<str> = Rc::from("NegativePreise");
+
     // Validate required columns
     let required_columns = ["Datum", "Stunde1", "Stunde3", "Stunde4", "Stunde6"];
     for col in &required_columns {
@@ -392,21 +901,25 @@ pub fn parse_negative_price_flags_csv(
         let record = result
             .map_err(|e| ParseError::CsvFormat(format!("Failed to read CSV record: {}", e)))?;
 
-        // Parse combined datetime (format: "2024-10-20 00:00")
+        // Parse combined datetime (format: "2024-10-20 00:00", seconds optional)
         let datum_zeit = get_field(&record, &headers, "Datum")?;
 
         // Split datetime into date and time components
         let parts: Vec<&str> = datum_zeit.split(' ').collect();
-        if parts.len() != 2 {
-            return Err(ParseError::InvalidTimestamp(format!(
-                "Expected 'YYYY-MM-DD HH:MM' format, got: {}",
-                datum_zeit
-            ))
-            .into());
-        }
+        let seconds = match parts.as_slice() {
+            [_, time] if time.matches(':').count() == 1 => ":00",
+            [_, time] if time.matches(':').count() == 2 => "",
+            _ => {
+                return Err(ParseError::InvalidTimestamp(format!(
+                    "Expected 'YYYY-MM-DD HH:MM' or 'YYYY-MM-DD HH:MM:SS' format, got: {}",
+                    datum_zeit
+                ))
+                .into())
+            }
+        };
 
         // Parse timestamp (format: "2024-10-20T00:00:00Z")
-        let timestamp_utc = format!("{}T{}:00Z", parts[0], parts[1]);
+        let timestamp_utc = format!("{}T{}{}Z", parts[0], parts[1], seconds);
 
         // Calculate end timestamp (+1 hour, using chrono)
         let dt = chrono::DateTime::parse_from_rfc3339(&timestamp_utc)
@@ -421,6 +934,9 @@ pub fn parse_negative_price_flags_csv(
         let flag_4h = get_field(&record, &headers, "Stunde4")? == "1";
         let flag_6h = get_field(&record, &headers, "Stunde6")? == "1";
 
+        // Every row of this record carries the same original line
+        let raw = keep_raw.then(|| join_record(&record));
+
         // UNPIVOT: Create 4 rows per timestamp (one for each logic type)
         // This allows users to query specific negative price logic durations
         let logic_types = [
@@ -434,13 +950,16 @@ pub fn parse_negative_price_flags_csv(
             rows.push(PriceRow {
                 timestamp_utc: timestamp_utc.clone(),
                 interval_end_utc: interval_end_utc.clone(),
-                price_type: "negative_flag".to_string(),
-                granularity: "hourly".to_string(),
+                price_type: price_type.clone(),
+                granularity: granularity.clone(),
                 price_eur_mwh: None, // Not provided in NegativePreise CSV
                 product_category: None,
                 negative_logic_hours: Some(logic_hours.to_string()),
                 negative_flag_value: Some(flag_value),
-                source_endpoint: "NegativePreise".to_string(),
+                bidding_zone: None,
+                currency: None,
+                source_endpoint: source_endpoint.clone(),
+                raw: raw.clone(),
             });
         }
     }
@@ -471,6 +990,7 @@ pub fn parse_negative_price_flags_csv(
 ///
 /// * `content` - Raw response body from API
 /// * `year` - Year for the data (e.g., "2024")
+/// * `keep_raw` - Keep each row's original record in `raw` (server option `expose_raw`)
 ///
 /// # Returns
 ///
@@ -487,7 +1007,7 @@ pub fn parse_negative_price_flags_csv(
 /// ```
 /// # use supabase_fdw_ntp::csv_parser::parse_annual_price_response;
 /// let response = "JW;7,946\nJW Solar;4,624";
-/// let rows = parse_annual_price_response(response, "2024").unwrap();
+/// let rows = parse_annual_price_response(response, "2024", true).unwrap();
 /// assert_eq!(rows.len(), 2);
 /// assert_eq!(rows[0].price_eur_mwh, Some(79.46)); // 7.946 ct/kWh × 10
 /// assert_eq!(rows[0].product_category, Some("annual_overall".to_string()));
@@ -496,6 +1016,7 @@ pub fn parse_negative_price_flags_csv(
 pub fn parse_annual_price_response(
     content: &str,
     year: &str,
+    keep_raw: bool,
 ) -> Result<Vec<PriceRow>, NtpFdwError> {
     // Handle empty response
     if content.trim().is_empty() {
@@ -534,6 +1055,11 @@ pub fn parse_annual_price_response(
 
     let mut rows = Vec::new();
 
+    // Shared by every row
+    let granularity: Rc<str> = Rc::from("annual");
+    let price_type: Rc<str> = Rc::from("annual_market_value");
+    let source_endpoint: Rc<str> = Rc::from("Jahresmarktpraemie");
+
     // Parse each item
     for item in items {
         // Split by semicolon to get category and value
@@ -566,13 +1092,16 @@ pub fn parse_annual_price_response(
         rows.push(PriceRow {
             timestamp_utc,
             interval_end_utc,
-            granularity: "annual".to_string(),
-            price_type: "annual_market_value".to_string(),
+            granularity: granularity.clone(),
+            price_type: price_type.clone(),
             price_eur_mwh: Some(price_eur_mwh),
             product_category: Some(product_category),
             negative_logic_hours: None,
             negative_flag_value: None,
-            source_endpoint: "Jahresmarktpraemie".to_string(),
+            bidding_zone: None,
+            currency: None,
+            source_endpoint: source_endpoint.clone(),
+            raw: keep_raw.then(|| parts.join(";")),
         });
     }
 
@@ -600,6 +1129,37 @@ fn normalize_annual_product(category: &str) -> String {
     }
 }
 
+/// Negative-hours columns of the marktpraemie response → `negative_logic_hours`
+///
+/// Each holds `Ja`/`Nein` for whether the month had negative spot prices for at
+/// least that many consecutive hours (or quarter hours for `15MIN`).
+const MONTHLY_NEGATIVE_HOURS_COLUMNS: [(&str, &str); 5] = [
+    ("Negative Stunden (6H)", "6h"),
+    ("Negative Stunden (4H)", "4h"),
+    ("Negative Stunden (3H)", "3h"),
+    ("Negative Stunden (1H)", "1h"),
+    ("Negative Stunden (15MIN)", "15min"),
+];
+
+/// Parse a `Ja`/`Nein` flag of the marktpraemie response
+///
+/// # Returns
+///
+/// * `Ok(Some(true))` / `Ok(Some(false))` - `Ja` / `Nein`
+/// * `Ok(None)` - Blank (not published for that month)
+/// * `Err(ParseError::CsvFormat)` - Any other value
+fn parse_ja_nein(value: &str, column: &str) -> Result<Option<bool>, ParseError> {
+    match value.trim() {
+        "Ja" => Ok(Some(true)),
+        "Nein" => Ok(Some(false)),
+        "" => Ok(None),
+        other => Err(ParseError::CsvFormat(format!(
+            "Invalid value '{}' in column '{}': expected 'Ja', 'Nein' or blank",
+            other, column
+        ))),
+    }
+}
+
 /// Parse monthly market premium response (marktpraemie)
 ///
 /// The marktpraemie endpoint returns CSV with one row per month containing
@@ -623,6 +1183,7 @@ fn normalize_annual_product(category: &str) -> String {
 /// * `csv_content` - Raw CSV response from API
 /// * `date_from` - Start date for metadata (not used in parsing)
 /// * `date_to` - End date for metadata (not used in parsing)
+/// * `keep_raw` - Keep each row's original record in `raw` (server option `expose_raw`)
 ///
 /// # Returns
 ///
@@ -634,18 +1195,25 @@ fn normalize_annual_product(category: &str) -> String {
 /// - `price_eur_mwh`: Converted from API (ct/kWh × 10 = EUR/MWh)
 /// - `product_category`: "base", "wind_onshore", "wind_offshore", "solar"
 ///
+/// plus, for each `Negative Stunden (...)` column present in the header, one
+/// row per month with `negative_logic_hours` "6h", "4h", "3h", "1h" or "15min",
+/// `negative_flag_value` from `Ja`/`Nein` (None when blank) and no price or
+/// product. These keep `price_type` "market_premium": a `price_type` qual
+/// routes to one endpoint, and `'negative_flag'` routes to NegativePreise.
+///
 /// # Example
 ///
 /// ```
 /// # use supabase_fdw_ntp::csv_parser::parse_monthly_price_csv;
 /// let csv = "Monat;MW-EPEX in ct/kWh;MW Wind Onshore in ct/kWh;MW Wind Offshore in ct/kWh;MW Solar in ct/kWh\n1/2020;3,503;3,091;3,321;3,831";
-/// let rows = parse_monthly_price_csv(csv, "2020-01-01", "2020-12-31").unwrap();
+/// let rows = parse_monthly_price_csv(csv, "2020-01-01", "2020-12-31", true).unwrap();
 /// assert_eq!(rows.len(), 4); // 1 CSV row → 4 database rows
 /// ```
 pub fn parse_monthly_price_csv(
     csv_content: &str,
     _date_from: &str,
     _date_to: &str,
+    keep_raw: bool,
 ) -> Result<Vec<PriceRow>, NtpFdwError> {
     // Stop at metadata footer
     let csv_data = csv_content.split("===").next().unwrap_or(csv_content);
@@ -686,8 +1254,19 @@ pub fn parse_monthly_price_csv(
         }
     }
 
+    // Negative-hours flags are only parsed where the header has them
+    let negative_columns: Vec<(&str, &str)> = MONTHLY_NEGATIVE_HOURS_COLUMNS
+        .into_iter()
+        .filter(|(column, _)| headers.iter().any(|h| h == *column))
+        .collect();
+
     let mut rows = Vec::new();
 
+    // Shared by every row
+    let granularity: Rc<str> = Rc::from("monthly");
+    let price_type: Rc<str> = Rc::from("market_premium");
+    let source_endpoint: Rc<str> = Rc::from("marktpraemie");
+
     // Parse each month row
     for result in reader.records() {
         let record =
@@ -740,6 +1319,9 @@ pub fn parse_monthly_price_csv(
         };
         let interval_end_utc = format!("{:04}-{:02}-{:02}T23:59:59Z", year, month, last_day);
 
+        // Every row of this record carries the same original line
+        let raw = keep_raw.then(|| join_record(&record));
+
         // Define product columns to UNPIVOT
         let products = vec![
             ("MW-EPEX in ct/kWh", "base"),
@@ -766,13 +1348,36 @@ pub fn parse_monthly_price_csv(
             rows.push(PriceRow {
                 timestamp_utc: timestamp_utc.clone(),
                 interval_end_utc: interval_end_utc.clone(),
-                granularity: "monthly".to_string(),
-                price_type: "market_premium".to_string(),
+                granularity: granularity.clone(),
+                price_type: price_type.clone(),
                 price_eur_mwh: Some(price_eur_mwh),
                 product_category: Some(product_category.to_string()),
                 negative_logic_hours: None,
                 negative_flag_value: None,
-                source_endpoint: "marktpraemie".to_string(),
+                bidding_zone: None,
+                currency: None,
+                source_endpoint: source_endpoint.clone(),
+                raw: raw.clone(),
+            });
+        }
+
+        // UNPIVOT: one flag row per negative-hours column
+        for (column_name, logic_hours) in &negative_columns {
+            let flag = parse_ja_nein(get_field(&record, &headers, column_name)?, column_name)?;
+
+            rows.push(PriceRow {
+                timestamp_utc: timestamp_utc.clone(),
+                interval_end_utc: interval_end_utc.clone(),
+                granularity: granularity.clone(),
+                price_type: price_type.clone(),
+                price_eur_mwh: None,
+                product_category: None,
+                negative_logic_hours: Some(logic_hours.to_string()),
+                negative_flag_value: flag,
+                bidding_zone: None,
+                currency: None,
+                source_endpoint: source_endpoint.clone(),
+                raw: raw.clone(),
             });
         }
     }
@@ -807,7 +1412,7 @@ mod tests {
             "TransnetBW (MW)",
         ]);
 
-        assert!(validate_renewable_header(&headers).is_ok());
+        assert!(validate_renewable_header(&headers, false).is_ok());
     }
 
     #[test]
@@ -820,7 +1425,36 @@ mod tests {
             "50Hertz (MW)",
         ]);
 
-        assert!(validate_renewable_header(&headers).is_err());
+        assert!(validate_renewable_header(&headers, false).is_err());
+    }
+
+    #[test]
+    fn test_validate_headers_missing_timezone_columns() {
+        let renewable = csv::StringRecord::from(vec![
+            "Datum",
+            "von",
+            "bis",
+            "50Hertz (MW)",
+            "Amprion (MW)",
+            "TenneT TSO (MW)",
+            "TransnetBW (MW)",
+        ]);
+        let price =
+            csv::StringRecord::from(vec!["Datum", "von", "bis", "Spotmarktpreis in ct/kWh"]);
+
+        // Implicit UTC
+        assert!(validate_renewable_header(&renewable, true).is_ok());
+        assert!(validate_price_header(&price, true).is_ok());
+
+        // Required when assume_utc_when_missing is off
+        assert!(matches!(
+            validate_renewable_header(&renewable, false),
+            Err(ParseError::MissingColumn(col)) if col == "Zeitzone von"
+        ));
+        assert!(matches!(
+            validate_price_header(&price, false),
+            Err(ParseError::MissingColumn(col)) if col == "Zeitzone von"
+        ));
     }
 
     #[test]
@@ -834,14 +1468,31 @@ mod tests {
             "Spotmarktpreis in ct/kWh",
         ]);
 
-        assert!(validate_price_header(&headers).is_ok());
+        assert!(validate_price_header(&headers, false).is_ok());
+    }
+
+    #[test]
+    fn test_validate_price_header_eur_mwh_column() {
+        let headers = csv::StringRecord::from(vec![
+            "Datum",
+            "von",
+            "bis",
+            "Zeitzone von",
+            "Zeitzone bis",
+            "Spotmarktpreis in EUR/MWh",
+        ]);
+
+        assert_eq!(
+            validate_price_header(&headers, false).unwrap(),
+            "Spotmarktpreis in EUR/MWh"
+        );
     }
 
     #[test]
     fn test_validate_price_header_missing_column() {
         let headers = csv::StringRecord::from(vec!["Datum", "von", "bis"]);
 
-        assert!(validate_price_header(&headers).is_err());
+        assert!(validate_price_header(&headers, true).is_err());
     }
 
     #[test]
@@ -860,13 +1511,87 @@ mod tests {
             "ExtraColumn", // Extra column - should be ignored
         ]);
 
-        assert!(validate_renewable_header(&headers).is_ok());
+        assert!(validate_renewable_header(&headers, false).is_ok());
     }
 
     // ========================================================================
     // parse_renewable_csv Tests
     // ========================================================================
 
+    #[test]
+    fn test_parse_renewable_csv_without_timezone_columns() {
+        let csv = r#"Datum;von;bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)
+2024-10-24;23:45;00:00;100,0;200,0;300,0;150,0"#;
+
+        // Implicit UTC by default, midnight crossing included
+        let rows =
+            parse_renewable_csv(csv, "hochrechnung", "Solar", "2024-10-24", "2024-10-25").unwrap();
+        assert_eq!(rows[0].timestamp_utc, "2024-10-24T23:45:00Z");
+        assert_eq!(rows[0].interval_end_utc, "2024-10-25T00:00:00Z");
+        assert_eq!(rows[0].interval_minutes, 15);
+
+        // Same rows as the explicit UTC export
+        let explicit = r#"Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)
+2024-10-24;23:45;UTC;00:00;UTC;100,0;200,0;300,0;150,0"#;
+        let explicit_rows = parse_renewable_csv(
+            explicit,
+            "hochrechnung",
+            "Solar",
+            "2024-10-24",
+            "2024-10-25",
+        )
+        .unwrap();
+        assert_eq!(rows[0].timestamp_utc, explicit_rows[0].timestamp_utc);
+        assert_eq!(rows[0].tso_tennet_mw, explicit_rows[0].tso_tennet_mw);
+
+        // assume_utc_when_missing 'false' keeps the columns required
+        let err = parse_renewable_csv_with_sentinels(
+            csv,
+            "hochrechnung",
+            Some("Solar"),
+            "2024-10-24",
+            "2024-10-25",
+            &[],
+            false,
+            true,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Zeitzone von"));
+    }
+
+    #[test]
+    fn test_parse_price_csv_without_timezone_columns() {
+        let csv = "Datum;von;bis;Spotmarktpreis in ct/kWh\n23.10.2024;00:00;01:00;8,273";
+
+        let rows = parse_price_csv(csv, "Spotmarktpreise", "2024-10-23", "2024-10-24").unwrap();
+        assert_eq!(rows[0].timestamp_utc, "2024-10-23T00:00:00Z");
+        assert_eq!(rows[0].interval_end_utc, "2024-10-23T01:00:00Z");
+
+        assert!(parse_price_csv_with_mode(
+            csv,
+            "Spotmarktpreise",
+            "2024-10-23",
+            "2024-10-24",
+            false,
+            &[],
+            true
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_renewable_csv_times_with_seconds() {
+        let csv = r#"Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)
+2024-10-24;06:00:15;UTC;06:15:15;UTC;100,0;200,0;300,0;150,0"#;
+
+        let rows =
+            parse_renewable_csv(csv, "hochrechnung", "Solar", "2024-10-24", "2024-10-25").unwrap();
+
+        assert_eq!(rows[0].timestamp_utc, "2024-10-24T06:00:15Z");
+        assert_eq!(rows[0].interval_end_utc, "2024-10-24T06:15:15Z");
+        assert_eq!(rows[0].interval_minutes, 15);
+    }
+
     #[test]
     fn test_parse_renewable_csv_wind_extrapolation() {
         let csv = r#"Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)
@@ -876,8 +1601,8 @@ mod tests {
             parse_renewable_csv(csv, "hochrechnung", "Wind", "2024-10-23", "2024-10-24").unwrap();
 
         assert_eq!(rows.len(), 1);
-        assert_eq!(rows[0].product_type, "wind_onshore");
-        assert_eq!(rows[0].data_category, "extrapolation");
+        assert_eq!(&*rows[0].product_type, "wind_onshore");
+        assert_eq!(&*rows[0].data_category, "extrapolation");
     }
 
     #[test]
@@ -895,8 +1620,9 @@ mod tests {
         .unwrap();
 
         assert_eq!(rows.len(), 1);
-        assert_eq!(rows[0].product_type, "wind_onshore");
-        assert_eq!(rows[0].data_category, "online_actual");
+        assert_eq!(&*rows[0].product_type, "wind_onshore");
+        assert_eq!(&*rows[0].api_product, "Windonshore");
+        assert_eq!(&*rows[0].data_category, "online_actual");
         assert_eq!(rows[0].interval_minutes, 60); // Hourly
     }
 
@@ -904,6 +1630,108 @@ mod tests {
     // parse_price_csv Tests
     // ========================================================================
 
+    #[test]
+    fn test_parse_combined_renewable_csv() {
+        let csv = r#"Datum;von;Zeitzone von;bis;Zeitzone bis;Produkt;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)
+2024-10-24;06:00;UTC;06:15;UTC;Solar;100,5;200,3;300,7;150,2
+2024-10-24;06:00;UTC;06:15;UTC;Wind;50,0;60,0;70,0;80,0
+2024-10-24;06:15;UTC;06:30;UTC;Solar;N.A.;N.A.;N.A.;N.A.
+2024-10-24;06:15;UTC;06:30;UTC;Wind;51,0;61,0;71,0;81,0"#;
+
+        let rows =
+            parse_combined_renewable_csv(csv, "hochrechnung", "2024-10-24", "2024-10-25").unwrap();
+
+        assert_eq!(rows.len(), 4);
+        assert_eq!(&*rows[0].product_type, "solar");
+        assert_eq!(&*rows[0].data_category, "extrapolation");
+        assert_eq!(rows[0].tso_50hertz_mw, Some(100.5));
+        assert_eq!(
+            &*rows[0].source_endpoint,
+            "hochrechnung/Solar/2024-10-24/2024-10-25"
+        );
+        assert_eq!(&*rows[1].product_type, "wind_onshore");
+        assert_eq!(&*rows[1].api_product, "Wind");
+        assert_eq!(
+            &*rows[1].source_endpoint,
+            "hochrechnung/Wind/2024-10-24/2024-10-25"
+        );
+        assert_eq!(rows[2].tso_50hertz_mw, None);
+        assert_eq!(rows[3].timestamp_utc, "2024-10-24T06:15:00Z");
+        assert_eq!(rows[3].tso_transnetbw_mw, Some(81.0));
+    }
+
+    #[test]
+    fn test_rows_share_response_strings() {
+        let csv = r#"Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)
+2024-10-24;06:00;UTC;06:15;UTC;1;2;3;4
+2024-10-24;06:15;UTC;06:30;UTC;5;6;7;8"#;
+        let rows =
+            parse_renewable_csv(csv, "hochrechnung", "Solar", "2024-10-24", "2024-10-25").unwrap();
+
+        // Same values as owned strings, one allocation for both rows
+        for row in &rows {
+            assert_eq!(&*row.product_type, "solar");
+            assert_eq!(&*row.api_product, "Solar");
+            assert_eq!(&*row.data_category, "extrapolation");
+            assert_eq!(
+                &*row.source_endpoint,
+                "hochrechnung/Solar/2024-10-24/2024-10-25"
+            );
+        }
+        assert!(Rc::ptr_eq(&rows[0].product_type, &rows[1].product_type));
+        assert!(Rc::ptr_eq(&rows[0].data_category, &rows[1].data_category));
+        assert!(Rc::ptr_eq(
+            &rows[0].source_endpoint,
+            &rows[1].source_endpoint
+        ));
+
+        // Combined responses share per product
+        let csv = r#"Datum;von;Zeitzone von;bis;Zeitzone bis;Produkt;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)
+2024-10-24;06:00;UTC;06:15;UTC;Solar;1;2;3;4
+2024-10-24;06:00;UTC;06:15;UTC;Wind;1;2;3;4
+2024-10-24;06:15;UTC;06:30;UTC;Solar;1;2;3;4"#;
+        let rows =
+            parse_combined_renewable_csv(csv, "hochrechnung", "2024-10-24", "2024-10-25").unwrap();
+        assert!(Rc::ptr_eq(&rows[0].product_type, &rows[2].product_type));
+        assert!(!Rc::ptr_eq(&rows[0].product_type, &rows[1].product_type));
+        assert_eq!(&*rows[2].api_product, "Solar");
+
+        // Spot prices share granularity, price type and endpoint across records
+        let csv = "Datum;von;Zeitzone von;bis;Zeitzone bis;Spotmarktpreis in ct/kWh\n\
+                   23.10.2024;00:00;UTC;01:00;UTC;8,5\n\
+                   23.10.2024;01:00;UTC;02:00;UTC;8,1";
+        let rows = parse_price_csv(csv, "Spotmarktpreise", "2024-10-23", "2024-10-24").unwrap();
+        assert_eq!(&*rows[1].granularity, "hourly");
+        assert_eq!(&*rows[1].price_type, "spot_market");
+        assert_eq!(
+            &*rows[1].source_endpoint,
+            "Spotmarktpreise/2024-10-23/2024-10-24"
+        );
+        assert!(Rc::ptr_eq(&rows[0].price_type, &rows[1].price_type));
+        assert!(Rc::ptr_eq(
+            &rows[0].source_endpoint,
+            &rows[1].source_endpoint
+        ));
+    }
+
+    #[test]
+    fn test_parse_combined_renewable_csv_missing_product_column() {
+        let csv = r#"Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)
+2024-10-24;06:00;UTC;06:15;UTC;100,5;200,3;300,7;150,2"#;
+
+        let result = parse_combined_renewable_csv(csv, "hochrechnung", "2024-10-24", "2024-10-25");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_combined_renewable_csv_unknown_product() {
+        let csv = r#"Datum;von;Zeitzone von;bis;Zeitzone bis;Produkt;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)
+2024-10-24;06:00;UTC;06:15;UTC;Biomasse;1,0;2,0;3,0;4,0"#;
+
+        let result = parse_combined_renewable_csv(csv, "hochrechnung", "2024-10-24", "2024-10-25");
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_parse_price_csv_valid() {
         let csv = r#"Datum;von;Zeitzone von;bis;Zeitzone bis;Spotmarktpreis in ct/kWh
@@ -913,8 +1741,8 @@ mod tests {
         let rows = parse_price_csv(csv, "Spotmarktpreise", "2024-10-23", "2024-10-24").unwrap();
 
         assert_eq!(rows.len(), 2);
-        assert_eq!(rows[0].price_type, "spot_market");
-        assert_eq!(rows[0].granularity, "hourly");
+        assert_eq!(&*rows[0].price_type, "spot_market");
+        assert_eq!(&*rows[0].granularity, "hourly");
         // 8.273 ct/kWh × 10 = 82.73 EUR/MWh
         assert!((rows[0].price_eur_mwh.unwrap() - 82.73).abs() < 0.01);
     }
@@ -981,6 +1809,346 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_renewable_csv_error_names_row() {
+        let csv = r#"Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)
+2024-10-24;06:00;UTC;06:15;UTC;100,5;200,3;300,7;150,2
+2024-10-24;06:15;UTC;06:30;UTC;101,5;201,3;301,7;151,2
+2024-10-24;06:30;UTC;06:45;UTC;102,5;abc;302,7;152,2"#;
+
+        let err = parse_renewable_csv(csv, "hochrechnung", "Solar", "2024-10-24", "2024-10-25")
+            .unwrap_err();
+
+        match &err {
+            NtpFdwError::Parse(ParseError::RowError { row, source }) => {
+                assert_eq!(*row, 3);
+                assert!(matches!(**source, ParseError::InvalidDecimal(_)));
+            }
+            other => panic!("Expected RowError, got {:?}", other),
+        }
+        assert!(err.to_string().contains("Row 3: "));
+    }
+
+    const RENEWABLE_HEADER: [&str; 9] = [
+        "Datum",
+        "von",
+        "Zeitzone von",
+        "bis",
+        "Zeitzone bis",
+        "50Hertz (MW)",
+        "Amprion (MW)",
+        "TenneT TSO (MW)",
+        "TransnetBW (MW)",
+    ];
+
+    #[test]
+    fn test_parse_renewable_record_matches_csv_loop() {
+        let fields = [
+            "2024-10-24",
+            "23:45",
+            "UTC",
+            "00:00",
+            "UTC",
+            "100,5",
+            "N.A.",
+            "300,7",
+            "150,2",
+        ];
+        let csv = format!("{}\n{}", RENEWABLE_HEADER.join(";"), fields.join(";"));
+
+        let row = parse_renewable_record(
+            &fields,
+            &RENEWABLE_HEADER,
+            "hochrechnung",
+            Some("Solar"),
+            "2024-10-24",
+            "2024-10-25",
+        )
+        .unwrap();
+        let rows =
+            parse_renewable_csv(&csv, "hochrechnung", "Solar", "2024-10-24", "2024-10-25").unwrap();
+
+        assert_eq!(format!("{:?}", row), format!("{:?}", rows[0]));
+        assert_eq!(row.raw.as_deref(), Some(fields.join(";").as_str()));
+        assert_eq!(row.interval_end_utc, "2024-10-25T00:00:00Z");
+        assert_eq!(row.tso_amprion_mw, None);
+        assert_eq!(
+            &*row.source_endpoint,
+            "hochrechnung/Solar/2024-10-24/2024-10-25"
+        );
+    }
+
+    #[test]
+    fn test_parse_renewable_record_product_column() {
+        let mut header = RENEWABLE_HEADER.to_vec();
+        header.push("Produkt");
+        let fields = [
+            " 2024-10-24 ",
+            "06:00",
+            "UTC",
+            "06:15",
+            "UTC",
+            "1,0",
+            "2,0",
+            "3,0",
+            "4,0",
+            "Wind",
+        ];
+
+        let row = parse_renewable_record(
+            &fields,
+            &header,
+            "onlinehochrechnung",
+            None,
+            "2024-10-24",
+            "2024-10-25",
+        )
+        .unwrap();
+
+        assert_eq!(row.timestamp_utc, "2024-10-24T06:00:00Z");
+        assert_eq!(&*row.product_type, "wind_onshore");
+        assert_eq!(&*row.api_product, "Wind");
+    }
+
+    #[test]
+    fn test_parse_renewable_record_errors() {
+        let fields = [
+            "2024-10-24",
+            "06:00",
+            "UTC",
+            "06:15",
+            "UTC",
+            "1,0",
+            "2,0",
+            "3,0",
+            "4,0",
+        ];
+
+        // Field count must match the header
+        let result = parse_renewable_record(
+            &fields[..8],
+            &RENEWABLE_HEADER,
+            "hochrechnung",
+            Some("Solar"),
+            "2024-10-24",
+            "2024-10-25",
+        );
+        assert!(matches!(
+            result,
+            Err(NtpFdwError::Parse(ParseError::CsvFormat(_)))
+        ));
+
+        // Required columns are validated like the CSV header
+        let result = parse_renewable_record(
+            &fields[1..],
+            &RENEWABLE_HEADER[1..],
+            "hochrechnung",
+            Some("Solar"),
+            "2024-10-24",
+            "2024-10-25",
+        );
+        assert!(matches!(
+            result,
+            Err(NtpFdwError::Parse(ParseError::MissingColumn(_)))
+        ));
+
+        // No Produkt column without a fixed product
+        let result = parse_renewable_record(
+            &fields,
+            &RENEWABLE_HEADER,
+            "hochrechnung",
+            None,
+            "2024-10-24",
+            "2024-10-25",
+        );
+        assert!(matches!(
+            result,
+            Err(NtpFdwError::Parse(ParseError::MissingColumn(_)))
+        ));
+
+        // Field errors are not tagged with a row number
+        let mut invalid = fields;
+        invalid[6] = "abc";
+        let result = parse_renewable_record(
+            &invalid,
+            &RENEWABLE_HEADER,
+            "hochrechnung",
+            Some("Solar"),
+            "2024-10-24",
+            "2024-10-25",
+        );
+        assert!(matches!(
+            result,
+            Err(NtpFdwError::Parse(ParseError::InvalidDecimal(_)))
+        ));
+    }
+
+    #[test]
+    fn test_parse_price_csv_error_names_row() {
+        let csv = r#"Datum;von;Zeitzone von;bis;Zeitzone bis;Spotmarktpreis in ct/kWh
+23.10.2024;00:00;UTC;01:00;UTC;8,5
+23.10.2024;01:00;UTC;02:00;UTC;8,1
+23.10.2024;02:00;UTC;03:00;UTC;invalid"#;
+
+        let err = parse_price_csv(csv, "Spotmarktpreise", "2024-10-23", "2024-10-24").unwrap_err();
+
+        assert!(matches!(
+            err,
+            NtpFdwError::Parse(ParseError::RowError { row: 3, .. })
+        ));
+        assert!(err.to_string().contains("Row 3: "));
+        assert!(err.to_string().contains("invalid"));
+    }
+
+    #[test]
+    fn test_parse_price_csv_multiple_price_columns() {
+        let csv = r#"Datum;von;Zeitzone von;bis;Zeitzone bis;Spotmarktpreis in ct/kWh;Day-Ahead in ct/kWh;Intraday in ct/kWh
+23.10.2024;00:00;UTC;01:00;UTC;8,5;8,4;9,1
+23.10.2024;01:00;UTC;02:00;UTC;8,1;;N.A.
+23.10.2024;02:00;UTC;03:00;UTC;7,9;N.E.;-"#;
+
+        let rows = parse_price_csv(csv, "Spotmarktpreise", "2024-10-23", "2024-10-24").unwrap();
+
+        assert_eq!(rows.len(), 5);
+        assert_eq!(&*rows[0].price_type, "spot_market");
+        assert!((rows[0].price_eur_mwh.unwrap() - 85.0).abs() < 1e-9);
+        assert_eq!(&*rows[1].price_type, "spot_day_ahead");
+        assert!((rows[1].price_eur_mwh.unwrap() - 84.0).abs() < 1e-9);
+        assert_eq!(&*rows[2].price_type, "spot_intraday");
+        assert!((rows[2].price_eur_mwh.unwrap() - 91.0).abs() < 1e-9);
+        assert_eq!(rows[1].timestamp_utc, rows[0].timestamp_utc);
+
+        // Blank and NULL sentinel extra cells produce no rows
+        assert_eq!(&*rows[3].price_type, "spot_market");
+        assert_eq!(rows[3].timestamp_utc, "2024-10-23T01:00:00Z");
+        assert_eq!(&*rows[4].price_type, "spot_market");
+        assert_eq!(rows[4].timestamp_utc, "2024-10-23T02:00:00Z");
+
+        // Server option null_sentinels applies to extra columns too
+        let csv =
+            "Datum;von;Zeitzone von;bis;Zeitzone bis;Spotmarktpreis in ct/kWh;Intraday in ct/kWh\n\
+                   23.10.2024;00:00;UTC;01:00;UTC;8,5;keine Daten";
+        let sentinels = vec!["keine Daten".to_string()];
+        let rows = parse_price_csv_with_mode(
+            csv,
+            "Spotmarktpreise",
+            "2024-10-23",
+            "2024-10-24",
+            true,
+            &sentinels,
+            true,
+        )
+        .unwrap();
+        assert_eq!(rows.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_price_csv_bidding_zone_and_currency() {
+        let csv = r#"Datum;von;Zeitzone von;bis;Zeitzone bis;Gebotszone;Währung;Spotmarktpreis in ct/kWh;Intraday in ct/kWh
+23.10.2024;00:00;UTC;01:00;UTC;DE-LU;EUR;8,5;9,1
+23.10.2024;00:00;UTC;01:00;UTC;AT;EUR;9,2;
+23.10.2024;01:00;UTC;02:00;UTC;;;8,1;"#;
+
+        let rows = parse_price_csv(csv, "Spotmarktpreise", "2024-10-23", "2024-10-24").unwrap();
+
+        let zones: Vec<(&str, Option<&str>, Option<&str>)> = rows
+            .iter()
+            .map(|row| {
+                (
+                    &*row.price_type,
+                    row.bidding_zone.as_deref(),
+                    row.currency.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            zones,
+            vec![
+                ("spot_market", Some("DE-LU"), Some("EUR")),
+                // Extra price column rows share their line's zone
+                ("spot_intraday", Some("DE-LU"), Some("EUR")),
+                ("spot_market", Some("AT"), Some("EUR")),
+                // Blank cells are NULL
+                ("spot_market", None, None),
+            ]
+        );
+        assert!((rows[2].price_eur_mwh.unwrap() - 92.0).abs() < 1e-9);
+
+        // Single-zone feeds carry neither
+        let rows = parse_price_csv(
+            "Datum;von;Zeitzone von;bis;Zeitzone bis;Spotmarktpreis in ct/kWh\n23.10.2024;00:00;UTC;01:00;UTC;8,5",
+            "Spotmarktpreise",
+            "2024-10-23",
+            "2024-10-24",
+        )
+        .unwrap();
+        assert_eq!(
+            (rows[0].bidding_zone.as_deref(), rows[0].currency.as_deref()),
+            (None, None)
+        );
+    }
+
+    #[test]
+    fn test_parse_price_csv_ct_kwh_header_converted() {
+        let csv = r#"Datum;von;Zeitzone von;bis;Zeitzone bis;Spotmarktpreis in ct/kWh
+23.10.2024;00:00;UTC;01:00;UTC;8,273"#;
+
+        let rows = parse_price_csv(csv, "Spotmarktpreise", "2024-10-23", "2024-10-24").unwrap();
+
+        // 8.273 ct/kWh × 10 = 82.73 EUR/MWh
+        assert!((rows[0].price_eur_mwh.unwrap() - 82.73).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_price_csv_eur_mwh_header_passed_through() {
+        let csv = r#"Datum;von;Zeitzone von;bis;Zeitzone bis;Spotmarktpreis in EUR/MWh
+23.10.2024;00:00;UTC;01:00;UTC;82,73
+23.10.2024;01:00;UTC;02:00;UTC;-2,01"#;
+
+        let rows = parse_price_csv(csv, "Spotmarktpreise", "2024-10-23", "2024-10-24").unwrap();
+
+        // Already EUR/MWh: no ×10 conversion
+        assert_eq!(rows.len(), 2);
+        assert!((rows[0].price_eur_mwh.unwrap() - 82.73).abs() < 1e-9);
+        assert!((rows[1].price_eur_mwh.unwrap() + 2.01).abs() < 1e-9);
+        assert_eq!(&*rows[0].price_type, "spot_market");
+    }
+
+    #[test]
+    fn test_parse_price_csv_single_column_unchanged() {
+        let csv = r#"Datum;von;Zeitzone von;bis;Zeitzone bis;Spotmarktpreis in ct/kWh
+23.10.2024;00:00;UTC;01:00;UTC;8,5
+23.10.2024;01:00;UTC;02:00;UTC;8,1"#;
+
+        let rows = parse_price_csv(csv, "Spotmarktpreise", "2024-10-23", "2024-10-24").unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|r| &*r.price_type == "spot_market"));
+    }
+
+    #[test]
+    fn test_parse_price_csv_invalid_extra_column() {
+        let csv = r#"Datum;von;Zeitzone von;bis;Zeitzone bis;Spotmarktpreis in ct/kWh;Auktion in ct/kWh
+23.10.2024;00:00;UTC;01:00;UTC;8,5;abc"#;
+
+        let err = parse_price_csv(csv, "Spotmarktpreise", "2024-10-23", "2024-10-24").unwrap_err();
+        assert!(err.to_string().contains("Row 1: "));
+    }
+
+    #[test]
+    fn test_parse_price_csv_non_finite_price() {
+        for price in ["inf", "-inf", "NaN"] {
+            let csv = format!(
+                "Datum;von;Zeitzone von;bis;Zeitzone bis;Spotmarktpreis in ct/kWh\n\
+                 23.10.2024;00:00;UTC;01:00;UTC;{}",
+                price
+            );
+
+            let result = parse_price_csv(&csv, "Spotmarktpreise", "2024-10-23", "2024-10-24");
+            assert!(result.is_err(), "price '{}' should be rejected", price);
+        }
+    }
+
     #[test]
     fn test_parse_price_csv_with_metadata_footer() {
         let csv = r#"Datum;von;Zeitzone von;bis;Zeitzone bis;Spotmarktpreis in ct/kWh
@@ -1005,19 +2173,19 @@ SIZE:142"#;
         // Actual format from Jahresmarktpraemie endpoint (line-separated with header)
         let response = "Alle Werte in ct/kWh;2024\nJW;7,946\nJW Wind an Land;6,293\nJW Wind auf See;6,777\nJW Solar;4,624";
 
-        let rows = parse_annual_price_response(response, "2024").unwrap();
+        let rows = parse_annual_price_response(response, "2024", true).unwrap();
 
         assert_eq!(rows.len(), 4);
 
         // Check first row (JW - overall annual value)
         assert_eq!(rows[0].timestamp_utc, "2024-01-01T00:00:00Z");
         assert_eq!(rows[0].interval_end_utc, "2024-12-31T23:59:59Z");
-        assert_eq!(rows[0].granularity, "annual");
-        assert_eq!(rows[0].price_type, "annual_market_value");
+        assert_eq!(&*rows[0].granularity, "annual");
+        assert_eq!(&*rows[0].price_type, "annual_market_value");
         assert_eq!(rows[0].product_category, Some("annual_overall".to_string()));
         // Price conversion: 7.946 ct/kWh × 10 = 79.46 EUR/MWh
         assert_eq!(rows[0].price_eur_mwh, Some(79.46));
-        assert_eq!(rows[0].source_endpoint, "Jahresmarktpraemie");
+        assert_eq!(&*rows[0].source_endpoint, "Jahresmarktpraemie");
 
         // Check product normalization
         assert_eq!(rows[1].product_category, Some("wind_onshore".to_string()));
@@ -1030,7 +2198,7 @@ SIZE:142"#;
         // Test German decimal format (comma as decimal separator)
         let response = "JW;10,5\nJW Solar;3,142";
 
-        let rows = parse_annual_price_response(response, "2023").unwrap();
+        let rows = parse_annual_price_response(response, "2023", true).unwrap();
 
         assert_eq!(rows.len(), 2);
         // 10.5 ct/kWh × 10 = 105.0 EUR/MWh
@@ -1042,12 +2210,12 @@ SIZE:142"#;
     #[test]
     fn test_parse_annual_price_response_empty() {
         let response = "";
-        let rows = parse_annual_price_response(response, "2024").unwrap();
+        let rows = parse_annual_price_response(response, "2024", true).unwrap();
         assert_eq!(rows.len(), 0);
 
         // Test whitespace-only
         let response2 = "   \n  \t  ";
-        let rows2 = parse_annual_price_response(response2, "2024").unwrap();
+        let rows2 = parse_annual_price_response(response2, "2024", true).unwrap();
         assert_eq!(rows2.len(), 0);
     }
 
@@ -1055,17 +2223,17 @@ SIZE:142"#;
     fn test_parse_annual_price_response_malformed() {
         // Missing semicolon separator
         let response1 = "JW 7,946";
-        let result1 = parse_annual_price_response(response1, "2024");
+        let result1 = parse_annual_price_response(response1, "2024", true);
         assert!(result1.is_err());
 
         // Too many parts (extra semicolons)
         let response2 = "JW;7;946";
-        let result2 = parse_annual_price_response(response2, "2024");
+        let result2 = parse_annual_price_response(response2, "2024", true);
         assert!(result2.is_err());
 
         // Empty lines between valid lines (should be filtered)
         let response3 = "JW;7,946\n\nJW Solar;4,624";
-        let rows3 = parse_annual_price_response(response3, "2024").unwrap();
+        let rows3 = parse_annual_price_response(response3, "2024", true).unwrap();
         // Should skip empty lines, parse 2 valid ones
         assert_eq!(rows3.len(), 2);
     }
@@ -1075,7 +2243,7 @@ SIZE:142"#;
         let response =
             "JW;1,0\nJW Wind an Land;2,0\nJW Wind auf See;3,0\nJW Solar;4,0\nUnknown Category;5,0";
 
-        let rows = parse_annual_price_response(response, "2024").unwrap();
+        let rows = parse_annual_price_response(response, "2024", true).unwrap();
 
         assert_eq!(rows.len(), 5);
         assert_eq!(rows[0].product_category, Some("annual_overall".to_string()));
@@ -1094,11 +2262,11 @@ SIZE:142"#;
         let response = "JW;7,946";
 
         // Test different years
-        let rows_2024 = parse_annual_price_response(response, "2024").unwrap();
+        let rows_2024 = parse_annual_price_response(response, "2024", true).unwrap();
         assert_eq!(rows_2024[0].timestamp_utc, "2024-01-01T00:00:00Z");
         assert_eq!(rows_2024[0].interval_end_utc, "2024-12-31T23:59:59Z");
 
-        let rows_2020 = parse_annual_price_response(response, "2020").unwrap();
+        let rows_2020 = parse_annual_price_response(response, "2020", true).unwrap();
         assert_eq!(rows_2020[0].timestamp_utc, "2020-01-01T00:00:00Z");
         assert_eq!(rows_2020[0].interval_end_utc, "2020-12-31T23:59:59Z");
     }
@@ -1114,19 +2282,19 @@ SIZE:142"#;
 1/2020;3,503;3,091;0,400;3,321;0,400;3,831;0,400;3,503;0,200;Nein;Nein;;Ja;
 2/2020;2,192;1,680;0,400;1,920;0,400;2,319;0,400;2,192;0,200;Ja;Ja;;Ja;"#;
 
-        let rows = parse_monthly_price_csv(csv, "2020-01-01", "2020-02-29").unwrap();
+        let rows = parse_monthly_price_csv(csv, "2020-01-01", "2020-02-29", true).unwrap();
 
-        // 2 months × 4 products = 8 rows
-        assert_eq!(rows.len(), 8);
+        // 2 months × (4 products + 5 negative-hours flags) = 18 rows
+        assert_eq!(rows.len(), 18);
 
         // Check first row (January 2020, base product)
         assert_eq!(rows[0].timestamp_utc, "2020-01-01T00:00:00Z");
         assert_eq!(rows[0].interval_end_utc, "2020-01-31T23:59:59Z");
-        assert_eq!(rows[0].granularity, "monthly");
-        assert_eq!(rows[0].price_type, "market_premium");
+        assert_eq!(&*rows[0].granularity, "monthly");
+        assert_eq!(&*rows[0].price_type, "market_premium");
         assert_eq!(rows[0].product_category, Some("base".to_string()));
         assert_eq!(rows[0].price_eur_mwh, Some(35.03)); // 3.503 ct/kWh × 10
-        assert_eq!(rows[0].source_endpoint, "marktpraemie");
+        assert_eq!(&*rows[0].source_endpoint, "marktpraemie");
 
         // Check UNPIVOT worked: same timestamp, different products
         assert_eq!(rows[1].timestamp_utc, "2020-01-01T00:00:00Z");
@@ -1142,9 +2310,82 @@ SIZE:142"#;
         assert!((rows[3].price_eur_mwh.unwrap() - 38.31).abs() < 0.01); // 3.831 ct/kWh × 10
 
         // Check February 2020 (leap year - 29 days)
-        assert_eq!(rows[4].timestamp_utc, "2020-02-01T00:00:00Z");
-        assert_eq!(rows[4].interval_end_utc, "2020-02-29T23:59:59Z");
-        assert_eq!(rows[4].product_category, Some("base".to_string()));
+        assert_eq!(rows[9].timestamp_utc, "2020-02-01T00:00:00Z");
+        assert_eq!(rows[9].interval_end_utc, "2020-02-29T23:59:59Z");
+        assert_eq!(rows[9].product_category, Some("base".to_string()));
+
+        // Product and negative-hours rows of both months share one allocation
+        assert!(Rc::ptr_eq(&rows[0].price_type, &rows[17].price_type));
+        assert!(Rc::ptr_eq(&rows[0].granularity, &rows[17].granularity));
+        assert!(Rc::ptr_eq(
+            &rows[0].source_endpoint,
+            &rows[17].source_endpoint
+        ));
+    }
+
+    #[test]
+    fn test_parse_monthly_price_csv_negative_hours_flags() {
+        let csv = r#"Monat;MW-EPEX in ct/kWh;MW Wind Onshore in ct/kWh;PM Wind Onshore fernsteuerbar in ct/kWh;MW Wind Offshore in ct/kWh;PM Wind Offshore fernsteuerbar in ct/kWh;MW Solar in ct/kWh;PM Solar fernsteuerbar in ct/kWh;MW steuerbar in ct/kWh;PM steuerbar in ct/kWh;Negative Stunden (6H);Negative Stunden (4H);Negative Stunden (3H);Negative Stunden (1H);Negative Stunden (15MIN)
+1/2020;3,503;3,091;0,400;3,321;0,400;3,831;0,400;3,503;0,200;Nein;Nein;;Ja;
+2/2020;2,192;1,680;0,400;1,920;0,400;2,319;0,400;2,192;0,200;Ja;Ja;;Ja;Ja"#;
+
+        let rows = parse_monthly_price_csv(csv, "2020-01-01", "2020-02-29", true).unwrap();
+
+        let flags = |month: &str| -> Vec<(String, Option<bool>)> {
+            rows.iter()
+                .filter(|row| row.negative_logic_hours.is_some() && row.timestamp_utc == month)
+                .map(|row| {
+                    (
+                        row.negative_logic_hours.clone().unwrap(),
+                        row.negative_flag_value,
+                    )
+                })
+                .collect()
+        };
+        let expected = |values: [Option<bool>; 5]| -> Vec<(String, Option<bool>)> {
+            ["6h", "4h", "3h", "1h", "15min"]
+                .iter()
+                .map(|hours| hours.to_string())
+                .zip(values)
+                .collect()
+        };
+
+        assert_eq!(
+            flags("2020-01-01T00:00:00Z"),
+            expected([Some(false), Some(false), None, Some(true), None])
+        );
+        assert_eq!(
+            flags("2020-02-01T00:00:00Z"),
+            expected([Some(true), Some(true), None, Some(true), Some(true)])
+        );
+
+        let flag_row = rows
+            .iter()
+            .find(|row| row.negative_logic_hours.is_some())
+            .unwrap();
+        assert_eq!(&*flag_row.price_type, "market_premium");
+        assert_eq!(&*flag_row.granularity, "monthly");
+        assert_eq!(flag_row.interval_end_utc, "2020-01-31T23:59:59Z");
+        assert_eq!(flag_row.price_eur_mwh, None);
+        assert_eq!(flag_row.product_category, None);
+        assert_eq!(&*flag_row.source_endpoint, "marktpraemie");
+    }
+
+    #[test]
+    fn test_parse_monthly_price_csv_negative_hours_partial_header() {
+        // Only the columns present in the header produce flag rows
+        let csv = "Monat;MW-EPEX in ct/kWh;MW Wind Onshore in ct/kWh;MW Wind Offshore in ct/kWh;MW Solar in ct/kWh;Negative Stunden (15MIN)\n\
+                   3/2024;6,0;5,0;5,5;4,0;Nein";
+
+        let rows = parse_monthly_price_csv(csv, "2024-03-01", "2024-03-31", true).unwrap();
+
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[4].negative_logic_hours, Some("15min".to_string()));
+        assert_eq!(rows[4].negative_flag_value, Some(false));
+
+        let invalid = csv.replace(";Nein", ";Vielleicht");
+        let err = parse_monthly_price_csv(&invalid, "2024-03-01", "2024-03-31", true).unwrap_err();
+        assert!(err.to_string().contains("Negative Stunden (15MIN)"));
     }
 
     #[test]
@@ -1153,7 +2394,7 @@ SIZE:142"#;
 2/2020;2,192;1,680;1,920;2,319
 2/2021;3,000;2,500;2,800;3,200"#;
 
-        let rows = parse_monthly_price_csv(csv, "2020-02-01", "2021-02-28").unwrap();
+        let rows = parse_monthly_price_csv(csv, "2020-02-01", "2021-02-28", true).unwrap();
 
         // Check leap year (2020) has 29 days
         assert_eq!(rows[0].timestamp_utc, "2020-02-01T00:00:00Z");
@@ -1171,7 +2412,7 @@ SIZE:142"#;
 4/2024;3,100;2,600;2,900;3,300
 9/2024;3,200;2,700;3,000;3,400"#;
 
-        let rows = parse_monthly_price_csv(csv, "2024-01-01", "2024-09-30").unwrap();
+        let rows = parse_monthly_price_csv(csv, "2024-01-01", "2024-09-30", true).unwrap();
 
         // January (31 days)
         assert_eq!(rows[0].interval_end_utc, "2024-01-31T23:59:59Z");
@@ -1188,7 +2429,7 @@ SIZE:142"#;
         let csv = r#"Monat;MW-EPEX in ct/kWh;MW Wind Onshore in ct/kWh;MW Wind Offshore in ct/kWh;MW Solar in ct/kWh
 10/2024;123,456;78,901;234,567;345,678"#;
 
-        let rows = parse_monthly_price_csv(csv, "2024-10-01", "2024-10-31").unwrap();
+        let rows = parse_monthly_price_csv(csv, "2024-10-01", "2024-10-31", true).unwrap();
 
         // Check German decimal conversion (comma → period, then × 10)
         assert!((rows[0].price_eur_mwh.unwrap() - 1234.56).abs() < 0.01);
@@ -1200,7 +2441,7 @@ SIZE:142"#;
     #[test]
     fn test_parse_monthly_price_csv_empty() {
         let csv = "";
-        let result = parse_monthly_price_csv(csv, "2024-01-01", "2024-12-31");
+        let result = parse_monthly_price_csv(csv, "2024-01-01", "2024-12-31", true);
         assert!(result.is_err());
     }
 
@@ -1209,7 +2450,7 @@ SIZE:142"#;
         let csv = r#"Monat;MW-EPEX in ct/kWh
 1/2020;3,503"#;
 
-        let result = parse_monthly_price_csv(csv, "2020-01-01", "2020-12-31");
+        let result = parse_monthly_price_csv(csv, "2020-01-01", "2020-12-31", true);
         assert!(result.is_err());
     }
 
@@ -1218,7 +2459,7 @@ SIZE:142"#;
         let csv = r#"Monat;MW-EPEX in ct/kWh;MW Wind Onshore in ct/kWh;MW Wind Offshore in ct/kWh;MW Solar in ct/kWh
 2024-10-01;3,503;3,091;3,321;3,831"#;
 
-        let result = parse_monthly_price_csv(csv, "2024-10-01", "2024-10-31");
+        let result = parse_monthly_price_csv(csv, "2024-10-01", "2024-10-31", true);
         assert!(result.is_err());
     }
 
@@ -1227,7 +2468,7 @@ SIZE:142"#;
         let csv = r#"Monat;MW-EPEX in ct/kWh;MW Wind Onshore in ct/kWh;MW Wind Offshore in ct/kWh;MW Solar in ct/kWh
 13/2024;3,503;3,091;3,321;3,831"#;
 
-        let result = parse_monthly_price_csv(csv, "2024-01-01", "2024-12-31");
+        let result = parse_monthly_price_csv(csv, "2024-01-01", "2024-12-31", true);
         assert!(result.is_err());
     }
 
@@ -1242,7 +2483,7 @@ SIZE:142"#;
 2024-10-20 00:00;0;1;1;1
 2024-10-20 01:00;1;1;0;0"#;
 
-        let rows = parse_negative_price_flags_csv(csv, "2024-10-20", "2024-10-21").unwrap();
+        let rows = parse_negative_price_flags_csv(csv, "2024-10-20", "2024-10-21", true).unwrap();
 
         // Should have 8 rows (2 timestamps × 4 logic types)
         assert_eq!(rows.len(), 8);
@@ -1299,9 +2540,9 @@ SIZE:142"#;
         assert_eq!(logic_4h_2.negative_flag_value, Some(false)); // 0 in CSV
 
         // Verify other metadata is correct
-        assert_eq!(logic_1h.price_type, "negative_flag");
-        assert_eq!(logic_1h.granularity, "hourly");
-        assert_eq!(logic_1h.source_endpoint, "NegativePreise");
+        assert_eq!(&*logic_1h.price_type, "negative_flag");
+        assert_eq!(&*logic_1h.granularity, "hourly");
+        assert_eq!(&*logic_1h.source_endpoint, "NegativePreise");
         assert_eq!(logic_1h.interval_end_utc, "2024-10-20T01:00:00Z");
     }
 
@@ -1311,7 +2552,7 @@ SIZE:142"#;
         let csv = r#"Datum;Stunde1;Stunde3;Stunde4;Stunde6
 2024-10-20 12:00;0;0;0;0"#;
 
-        let rows = parse_negative_price_flags_csv(csv, "2024-10-20", "2024-10-21").unwrap();
+        let rows = parse_negative_price_flags_csv(csv, "2024-10-20", "2024-10-21", true).unwrap();
 
         // Should still have 4 rows (UNPIVOT always creates 4 rows)
         assert_eq!(rows.len(), 4);
@@ -1328,7 +2569,7 @@ SIZE:142"#;
         let csv = r#"Datum;Stunde1;Stunde3;Stunde4;Stunde6
 2024-10-20 03:00;1;1;1;1"#;
 
-        let rows = parse_negative_price_flags_csv(csv, "2024-10-20", "2024-10-21").unwrap();
+        let rows = parse_negative_price_flags_csv(csv, "2024-10-20", "2024-10-21", true).unwrap();
 
         // Should have 4 rows
         assert_eq!(rows.len(), 4);
@@ -1349,10 +2590,47 @@ SIZE:142"#;
         assert!(logic_types.contains(&"6h".to_string()));
     }
 
+    #[test]
+    fn test_parse_negative_price_flags_datetime_with_seconds() {
+        // HH:MM and HH:MM:SS combined datetimes in one response
+        let csv = r#"Datum;Stunde1;Stunde3;Stunde4;Stunde6
+2024-10-20 00:00;1;0;0;0
+2024-10-20 01:00:00;1;0;0;0
+2024-10-20 02:00:30;1;0;0;0"#;
+
+        let rows = parse_negative_price_flags_csv(csv, "2024-10-20", "2024-10-21", true).unwrap();
+
+        let intervals: Vec<(&str, &str)> = rows
+            .iter()
+            .step_by(4)
+            .map(|r| (r.timestamp_utc.as_str(), r.interval_end_utc.as_str()))
+            .collect();
+        assert_eq!(
+            intervals,
+            vec![
+                ("2024-10-20T00:00:00Z", "2024-10-20T01:00:00Z"),
+                ("2024-10-20T01:00:00Z", "2024-10-20T02:00:00Z"),
+                ("2024-10-20T02:00:30Z", "2024-10-20T03:00:30Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_negative_price_flags_invalid_datetime() {
+        for datum in ["2024-10-20", "2024-10-20 00", "2024-10-20 00:00:00:00"] {
+            let csv = format!("Datum;Stunde1;Stunde3;Stunde4;Stunde6\n{};1;0;0;0", datum);
+
+            let err = parse_negative_price_flags_csv(&csv, "2024-10-20", "2024-10-21", true)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("HH:MM:SS"), "{}: {}", datum, err);
+        }
+    }
+
     #[test]
     fn test_parse_negative_price_flags_empty() {
         let csv = "";
-        let result = parse_negative_price_flags_csv(csv, "2024-10-20", "2024-10-21");
+        let result = parse_negative_price_flags_csv(csv, "2024-10-20", "2024-10-21", true);
         assert!(result.is_err());
     }
 
@@ -1361,7 +2639,91 @@ SIZE:142"#;
         let csv = r#"Datum;Stunde1;Stunde3
 2024-10-20 00:00;0;1"#;
 
-        let result = parse_negative_price_flags_csv(csv, "2024-10-20", "2024-10-21");
+        let result = parse_negative_price_flags_csv(csv, "2024-10-20", "2024-10-21", true);
         assert!(result.is_err()); // Should fail due to missing Stunde4 and Stunde6
     }
+
+    #[test]
+    fn test_parse_renewable_csv_column_order_independent() {
+        // Same data with shuffled columns parses to identical rows
+        let ordered = "Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)\n\
+                       2024-10-24;06:00;UTC;06:15;UTC;100,5;200,3;N.A.;150,2";
+        let shuffled = "TransnetBW (MW);bis;50Hertz (MW);Zeitzone bis;Datum;TenneT TSO (MW);von;Amprion (MW);Zeitzone von\n\
+                        150,2;06:15;100,5;UTC;2024-10-24;N.A.;06:00;200,3;UTC";
+
+        let expected =
+            parse_renewable_csv(ordered, "hochrechnung", "Solar", "2024-10-24", "2024-10-25")
+                .unwrap();
+        let mut actual = parse_renewable_csv(
+            shuffled,
+            "hochrechnung",
+            "Solar",
+            "2024-10-24",
+            "2024-10-25",
+        )
+        .unwrap();
+
+        // Only the original record keeps the source column order
+        assert_eq!(
+            actual[0].raw.as_deref(),
+            Some("150,2;06:15;100,5;UTC;2024-10-24;N.A.;06:00;200,3;UTC")
+        );
+        actual[0].raw = expected[0].raw.clone();
+        assert_eq!(format!("{:?}", actual), format!("{:?}", expected));
+        assert_eq!(actual[0].tso_transnetbw_mw, Some(150.2));
+    }
+
+    #[test]
+    fn test_parse_without_keep_raw_leaves_raw_empty() {
+        let renewable = "Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)\n\
+                         2024-10-24;06:00;UTC;06:15;UTC;100,5;200,3;300,1;150,2";
+        let rows = parse_renewable_csv_with_sentinels(
+            renewable,
+            "hochrechnung",
+            Some("Solar"),
+            "2024-10-24",
+            "2024-10-25",
+            &[],
+            true,
+            false,
+        )
+        .unwrap();
+        assert_eq!(rows[0].raw, None);
+
+        let price = "Datum;von;Zeitzone von;bis;Zeitzone bis;Spotmarktpreis in ct/kWh\n\
+                     23.10.2024;00:00;UTC;01:00;UTC;8,273";
+        let rows = parse_price_csv_with_mode(
+            price,
+            "Spotmarktpreise",
+            "2024-10-23",
+            "2024-10-24",
+            true,
+            &[],
+            false,
+        )
+        .unwrap();
+        assert_eq!(rows[0].raw, None);
+
+        let negative = "Datum;Stunde1;Stunde3;Stunde4;Stunde6\n2024-10-20 00:00;1;1;1;1";
+        let rows =
+            parse_negative_price_flags_csv(negative, "2024-10-20", "2024-10-21", false).unwrap();
+        assert!(rows.iter().all(|row| row.raw.is_none()));
+
+        let rows = parse_annual_price_response("JW;7,946", "2024", false).unwrap();
+        assert_eq!(rows[0].raw, None);
+    }
+
+    #[test]
+    fn test_parse_renewable_csv_end_of_day_24_00() {
+        let csv = "Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)\n\
+                   2024-10-24;23:45;UTC;24:00;UTC;100,5;200,3;300,1;150,2";
+
+        let rows =
+            parse_renewable_csv(csv, "hochrechnung", "Solar", "2024-10-24", "2024-10-25").unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].timestamp_utc, "2024-10-24T23:45:00Z");
+        assert_eq!(rows[0].interval_end_utc, "2024-10-25T00:00:00Z");
+        assert_eq!(rows[0].interval_minutes, 15);
+    }
 }
@@ -3,6 +3,8 @@
 //! This module provides common helper functions used across CSV parsers
 //! to avoid code duplication.
 
+use std::collections::HashMap;
+
 use crate::error::ParseError;
 
 /// Helper to get field value by column name from CSV record
@@ -45,10 +47,124 @@ pub fn get_field<'a>(
         .ok_or_else(|| ParseError::MissingColumn(field_name.to_string()))
 }
 
+/// Column name → index map, built once per CSV response
+///
+/// [`get_field`] scans the header row on every call, i.e. once per field of
+/// every row. Parsers reading many fields per row build a `HeaderIndex` from
+/// the header row once and resolve each column they read to a [`Column`]
+/// before the first record, so per-field reads are plain index accesses.
+///
+/// # Example
+///
+/// ```rust
+/// use csv::StringRecord;
+/// use supabase_fdw_ntp::csv_utils::HeaderIndex;
+///
+/// let headers = StringRecord::from(vec!["Datum", "von", "bis"]);
+/// let record = StringRecord::from(vec!["2024-10-24", "00:00", "01:00"]);
+///
+/// let index = HeaderIndex::new(&headers);
+/// let von = index.column("von").unwrap();
+/// assert_eq!(von.get(&record).unwrap(), "00:00");
+/// assert!(index.column("Zeitzone von").is_err());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HeaderIndex {
+    columns: HashMap<String, usize>,
+}
+
+impl HeaderIndex {
+    /// Index the columns of a CSV header row
+    pub fn new(headers: &csv::StringRecord) -> Self {
+        let mut columns = HashMap::with_capacity(headers.len());
+        for (idx, name) in headers.iter().enumerate() {
+            // Duplicate headers resolve to the first occurrence, like `get_field`
+            columns.entry(name.to_string()).or_insert(idx);
+        }
+
+        Self { columns }
+    }
+
+    /// Whether the header row has a column named `field_name`
+    pub fn contains(&self, field_name: &str) -> bool {
+        self.columns.contains_key(field_name)
+    }
+
+    /// Resolve a required column
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Column)` - Column position
+    /// * `Err(ParseError::MissingColumn)` - Column not found
+    pub fn column(&self, field_name: &'static str) -> Result<Column, ParseError> {
+        self.optional_column(field_name)
+            .ok_or_else(|| ParseError::MissingColumn(field_name.to_string()))
+    }
+
+    /// Resolve a column that may be absent from the header row
+    pub fn optional_column(&self, field_name: &'static str) -> Option<Column> {
+        self.columns.get(field_name).map(|&idx| Column {
+            name: field_name,
+            idx,
+        })
+    }
+}
+
+/// A CSV column resolved to its position by [`HeaderIndex`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Column {
+    name: &'static str,
+    idx: usize,
+}
+
+impl Column {
+    /// Column name, as in the header row
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Get this column's value from a CSV record
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(&str)` - Field value
+    /// * `Err(ParseError::MissingColumn)` - Record shorter than the header row
+    pub fn get<'a>(&self, record: &'a csv::StringRecord) -> Result<&'a str, ParseError> {
+        record
+            .get(self.idx)
+            .ok_or_else(|| ParseError::MissingColumn(self.name.to_string()))
+    }
+}
+
+/// Serialize a CSV record back to one semicolon-delimited line
+///
+/// Fields are joined as parsed (trimmed, unquoted), which reproduces the
+/// source line for the unquoted records the NTP API returns.
+///
+/// # Example
+///
+/// ```rust
+/// use csv::StringRecord;
+/// use supabase_fdw_ntp::csv_utils::join_record;
+///
+/// let record = StringRecord::from(vec!["2024-10-24", "00:00", "8,273"]);
+/// assert_eq!(join_record(&record), "2024-10-24;00:00;8,273");
+/// ```
+pub fn join_record(record: &csv::StringRecord) -> String {
+    record.iter().collect::<Vec<_>>().join(";")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_join_record() {
+        let record = csv::StringRecord::from(vec!["a", "1,5", ""]);
+        assert_eq!(join_record(&record), "a;1,5;");
+        assert_eq!(join_record(&csv::StringRecord::new()), "");
+    }
+
     #[test]
     fn test_get_field_success() {
         let headers = csv::StringRecord::from(vec!["col1", "col2", "col3"]);
@@ -75,4 +191,31 @@ mod tests {
         // Empty string is valid - get_field returns it
         assert_eq!(get_field(&record, &headers, "col1").unwrap(), "");
     }
+
+    #[test]
+    fn test_header_index_matches_get_field() {
+        // Duplicate "von" resolves to the first column in both lookups
+        let headers = csv::StringRecord::from(vec!["Datum", "von", "bis", "von", ""]);
+        let record = csv::StringRecord::from(vec!["2024-10-24", "00:00", "01:00", "02:00", "x"]);
+        let index = HeaderIndex::new(&headers);
+
+        for name in ["Datum", "von", "bis", "", "missing"] {
+            assert_eq!(
+                format!("{:?}", index.column(name).and_then(|c| c.get(&record))),
+                format!("{:?}", get_field(&record, &headers, name))
+            );
+        }
+        assert!(index.contains("bis"));
+        assert!(!index.contains("missing"));
+    }
+
+    #[test]
+    fn test_header_index_short_record() {
+        // Record shorter than the header row: missing column, not a panic
+        let headers = csv::StringRecord::from(vec!["col1", "col2"]);
+        let record = csv::StringRecord::from(vec!["a"]);
+
+        let col2 = HeaderIndex::new(&headers).column("col2").unwrap();
+        assert!(col2.get(&record).is_err());
+    }
 }
@@ -106,6 +106,11 @@ pub enum ParseError {
     /// Example: Direction not in allowed set (increase_generation, reduce_generation)
     UnknownDirection(String),
 
+    /// Unknown redispatch primary energy type
+    ///
+    /// Example: Energy type not in allowed set (Konventionell, Erneuerbar, Sonstige)
+    UnknownEnergyType(String),
+
     /// Invalid grid status value
     ///
     /// Example: Status not in allowed set (GREEN, YELLOW, RED)
@@ -113,6 +118,26 @@ pub enum ParseError {
 
     /// CSV format error (wrong delimiter, malformed row)
     CsvFormat(String),
+
+    /// JSON format error (malformed body, unexpected shape)
+    ///
+    /// Carries the serde error, including its line and column
+    JsonFormat(String),
+
+    /// Error in a specific CSV data row
+    ///
+    /// `row` is 1-based and excludes the header line
+    RowError { row: usize, source: Box<ParseError> },
+}
+
+impl ParseError {
+    /// Attach the 1-based CSV data row number (header excluded) to this error
+    pub fn at_row(self, row: usize) -> Self {
+        ParseError::RowError {
+            row,
+            source: Box::new(self),
+        }
+    }
 }
 
 impl fmt::Display for ParseError {
@@ -152,6 +177,9 @@ impl fmt::Display for ParseError {
             ParseError::UnknownDirection(dir) => {
                 write!(f, "Unknown redispatch direction: '{}' (expected 'Wirkleistungseinspeisung erhöhen' or 'Wirkleistungseinspeisung reduzieren')", dir)
             }
+            ParseError::UnknownEnergyType(energy_type) => {
+                write!(f, "Unknown redispatch energy type: '{}' (expected 'Konventionell', 'Erneuerbar' or 'Sonstige')", energy_type)
+            }
             ParseError::InvalidGridStatus(status) => {
                 write!(
                     f,
@@ -162,11 +190,24 @@ impl fmt::Display for ParseError {
             ParseError::CsvFormat(msg) => {
                 write!(f, "CSV format error: {}", msg)
             }
+            ParseError::JsonFormat(msg) => {
+                write!(f, "JSON format error: {}", msg)
+            }
+            ParseError::RowError { row, source } => {
+                write!(f, "Row {}: {}", row, source)
+            }
         }
     }
 }
 
-impl std::error::Error for ParseError {}
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::RowError { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
 
 /// OAuth2 authentication errors
 ///
@@ -221,11 +262,19 @@ pub enum ApiError {
     ///
     /// Common codes:
     /// - 401: Unauthorized (expired token)
+    /// - 403: Forbidden (see [`ApiError::Forbidden`])
     /// - 404: Not found (invalid endpoint or date range)
     /// - 429: Rate limit exceeded
     /// - 500: Server error
     HttpError { status: u16, body: String },
 
+    /// Access denied (HTTP 403)
+    ///
+    /// The token is valid but may not use the endpoint: missing API scope or
+    /// a client IP restriction. A fresh token would be denied the same way,
+    /// so this never triggers the token refresh retry.
+    Forbidden { body: String },
+
     /// Rate limit exceeded (HTTP 429)
     ///
     /// Should trigger exponential backoff retry
@@ -247,6 +296,18 @@ impl fmt::Display for ApiError {
             ApiError::HttpError { status, body } => {
                 write!(f, "HTTP {} error: {}", status, body)
             }
+            ApiError::Forbidden { body } => {
+                write!(
+                    f,
+                    "HTTP 403 Forbidden: access to this endpoint was denied. Check that the \
+                     OAuth2 client's API scopes include the endpoint and that the database \
+                     server's IP address is allowed for the client"
+                )?;
+                if !body.trim().is_empty() {
+                    write!(f, ": {}", body.trim())?;
+                }
+                Ok(())
+            }
             ApiError::RateLimited => {
                 write!(
                     f,
@@ -319,6 +380,15 @@ mod tests {
         assert!(msg.contains("Datum"));
     }
 
+    #[test]
+    fn test_row_error_formatting() {
+        let err = ParseError::InvalidDecimal("abc".to_string()).at_row(3);
+        let msg = format!("{}", err);
+        assert!(msg.starts_with("Row 3: "));
+        assert!(msg.contains("Invalid decimal format: 'abc'"));
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
     #[test]
     fn test_http_error_formatting() {
         let err = ApiError::HttpError {
@@ -329,4 +399,24 @@ mod tests {
         assert!(msg.contains("HTTP 404"));
         assert!(msg.contains("Endpoint not found"));
     }
+
+    #[test]
+    fn test_forbidden_error_formatting() {
+        let err = ApiError::Forbidden {
+            body: "insufficient_scope\n".to_string(),
+        };
+        let msg = format!("{}", err);
+        assert!(msg.starts_with("HTTP 403 Forbidden"));
+        assert!(msg.contains("API scopes"));
+        assert!(msg.contains("IP address"));
+        assert!(msg.ends_with(": insufficient_scope"));
+
+        let msg = format!(
+            "{}",
+            ApiError::Forbidden {
+                body: String::new()
+            }
+        );
+        assert!(msg.ends_with("allowed for the client"));
+    }
 }
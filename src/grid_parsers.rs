@@ -13,8 +13,8 @@
 use csv::ReaderBuilder;
 use serde::Deserialize;
 
-use crate::csv_utils::get_field;
-use crate::error::{ApiError, NtpFdwError, ParseError};
+use crate::csv_utils::HeaderIndex;
+use crate::error::{NtpFdwError, ParseError};
 use crate::transformations::*;
 use crate::types_grid::{GridStatusRow, RedispatchRow};
 
@@ -22,6 +22,85 @@ use crate::types_grid::{GridStatusRow, RedispatchRow};
 // Redispatch CSV Parser
 // ============================================================================
 
+/// English redispatch column names → the German names the parser looks up
+///
+/// Some API versions export the redispatch CSV with English headers. Values
+/// (dates, decimals, `RICHTUNG`, `PRIMAERENERGIEART`) keep the German format.
+const REDISPATCH_HEADER_ALIASES: [(&str, &str); 15] = [
+    ("START_DATE", "BEGINN_DATUM"),
+    ("START_TIME", "BEGINN_UHRZEIT"),
+    ("TIMEZONE_FROM", "ZEITZONE_VON"),
+    ("END_DATE", "ENDE_DATUM"),
+    ("END_TIME", "ENDE_UHRZEIT"),
+    ("TIMEZONE_TO", "ZEITZONE_BIS"),
+    ("REASON", "GRUND_DER_MASSNAHME"),
+    ("DIRECTION", "RICHTUNG"),
+    ("AVERAGE_POWER_MW", "MITTLERE_LEISTUNG_MW"),
+    ("MAXIMUM_POWER_MW", "MAXIMALE_LEISTUNG_MW"),
+    ("TOTAL_ENERGY_MWH", "GESAMTE_ARBEIT_MWH"),
+    ("INSTRUCTING_TSO", "ANWEISENDER_UENB"),
+    ("REQUESTING_TSO", "ANFORDERNDER_UENB"),
+    ("AFFECTED_FACILITY", "BETROFFENE_ANLAGE"),
+    ("PRIMARY_ENERGY_TYPE", "PRIMAERENERGIEART"),
+];
+
+/// Normalize a redispatch header row to the German uppercase column names
+///
+/// Matching is case-insensitive (`Beginn_Datum` → `BEGINN_DATUM`), and the
+/// English aliases of [`REDISPATCH_HEADER_ALIASES`] map to their German
+/// column. Unknown columns are kept (uppercased) and ignored by the parser.
+fn normalize_redispatch_header(headers: &csv::StringRecord) -> csv::StringRecord {
+    headers
+        .iter()
+        .map(|name| {
+            let upper = name.to_uppercase();
+            REDISPATCH_HEADER_ALIASES
+                .iter()
+                .find(|(alias, _)| *alias == upper)
+                .map_or(upper.clone(), |(_, german)| german.to_string())
+        })
+        .collect()
+}
+
+/// Prefixes marking an approximate power or energy value ("ca. 120", "~120")
+const APPROXIMATION_PREFIXES: [&str; 3] = ["ca.", "~", "≈"];
+
+/// Parse a redispatch power or energy cell (German decimal format)
+///
+/// A known approximation prefix (see [`APPROXIMATION_PREFIXES`], matched
+/// case-insensitively) is stripped, as is surrounding noise: leading letters
+/// and whitespace, trailing non-digits (units like "MW"). The numeric value
+/// is kept as is. Values without a plain decimal at their core still fail.
+///
+/// # Returns
+///
+/// * `Ok(None)` - Blank cell
+/// * `Ok(Some(f64))` - Parsed value
+/// * `Err(ParseError::InvalidDecimal)` - No decimal, names the original cell
+fn parse_redispatch_amount(value: &str) -> Result<Option<f64>, ParseError> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+
+    let unprefixed = APPROXIMATION_PREFIXES
+        .iter()
+        .find_map(|prefix| {
+            trimmed
+                .get(..prefix.len())
+                .filter(|head| head.eq_ignore_ascii_case(prefix))
+                .map(|_| &trimmed[prefix.len()..])
+        })
+        .unwrap_or(trimmed);
+    let core = unprefixed
+        .trim_start_matches(|c: char| c.is_alphabetic() || c.is_whitespace())
+        .trim_end_matches(|c: char| !c.is_ascii_digit());
+
+    parse_german_decimal(core)
+        .map(Some)
+        .map_err(|_| ParseError::InvalidDecimal(value.to_string()))
+}
+
 /// Parse redispatch CSV response
 ///
 /// Parses German-formatted CSV with grid intervention events.
@@ -29,11 +108,14 @@ use crate::types_grid::{GridStatusRow, RedispatchRow};
 /// # CSV Format
 ///
 /// - **Delimiter:** Semicolon (`;`)
-/// - **Decimal separator:** Comma (`,`) - German format
+/// - **Decimal separator:** Comma (`,`) - German format; power and energy
+///   values may carry an approximation prefix ("ca. 120", "~119,5")
 /// - **Date format:** DD.MM.YYYY
 /// - **Time format:** HH:MM (24-hour)
 /// - **Timezone:** UTC
-/// - **Columns:** 15 German columns (BEGINN_DATUM, RICHTUNG, etc.)
+/// - **Columns:** 15 German columns (BEGINN_DATUM, RICHTUNG, etc.), matched
+///   case-insensitively; English header variants (START_DATE, DIRECTION, etc.)
+///   are accepted as aliases
 ///
 /// # Arguments
 ///
@@ -56,12 +138,45 @@ use crate::types_grid::{GridStatusRow, RedispatchRow};
 /// let rows = parse_redispatch_csv(csv, "2024-10-23", "2024-10-24").unwrap();
 /// assert_eq!(rows.len(), 1);
 /// assert_eq!(rows[0].direction, "increase_generation");
+/// assert_eq!(rows[0].energy_type, Some("conventional".to_string()));
 /// ```
 pub fn parse_redispatch_csv(
+    csv_content: &str,
+    date_from: &str,
+    date_to: &str,
+) -> Result<Vec<RedispatchRow>, NtpFdwError> {
+    parse_redispatch_csv_with_mode(csv_content, date_from, date_to, true)
+}
+
+/// Parse redispatch CSV, optionally failing on unknown energy types
+///
+/// `PRIMAERENERGIEART` is normalized with [`normalize_energy_type`]. With
+/// `lenient = true` (the default, see [`parse_redispatch_csv`]) unknown values
+/// are kept as-is in `energy_type`; with `lenient = false` (server option
+/// `energy_type_strict 'true'`) they fail the scan.
+///
+/// # Example
+///
+/// ```
+/// # use supabase_fdw_ntp::grid_parsers::parse_redispatch_csv_with_mode;
+/// let csv = r#"BEGINN_DATUM;BEGINN_UHRZEIT;ZEITZONE_VON;ENDE_DATUM;ENDE_UHRZEIT;ZEITZONE_BIS;GRUND_DER_MASSNAHME;RICHTUNG;MITTLERE_LEISTUNG_MW;MAXIMALE_LEISTUNG_MW;GESAMTE_ARBEIT_MWH;ANWEISENDER_UENB;ANFORDERNDER_UENB;BETROFFENE_ANLAGE;PRIMAERENERGIEART
+/// 23.10.2024;22:00;UTC;24.10.2024;08:00;UTC;Netzengpass;Wirkleistungseinspeisung erhöhen;119,5;120;1195;TransnetBW;TransnetBW;Anlage;Kernkraft"#;
+///
+/// assert!(parse_redispatch_csv_with_mode(csv, "2024-10-23", "2024-10-24", false).is_err());
+/// let rows = parse_redispatch_csv_with_mode(csv, "2024-10-23", "2024-10-24", true).unwrap();
+/// assert_eq!(rows[0].energy_type, Some("Kernkraft".to_string()));
+/// ```
+pub fn parse_redispatch_csv_with_mode(
     csv_content: &str,
     _date_from: &str,
     _date_to: &str,
+    lenient: bool,
 ) -> Result<Vec<RedispatchRow>, NtpFdwError> {
+    // An empty body has no header row to resolve columns from, and no events
+    if csv_content.is_empty() {
+        return Ok(Vec::new());
+    }
+
     // Configure CSV reader for German format
     let mut reader = ReaderBuilder::new()
         .delimiter(b';')
@@ -70,20 +185,27 @@ pub fn parse_redispatch_csv(
         .trim(csv::Trim::All)
         .from_reader(csv_content.as_bytes());
 
-    // Get headers for column indexing
-    let headers = reader
+    // Resolve column indices once instead of looking them up per field
+    // (header names normalized to the German uppercase columns)
+    let index = reader
         .headers()
-        .map_err(|e| {
-            if csv_content.is_empty() {
-                NtpFdwError::from(ApiError::EmptyResponse)
-            } else {
-                NtpFdwError::from(ParseError::CsvFormat(format!(
-                    "Failed to read CSV headers: {}",
-                    e
-                )))
-            }
-        })?
-        .clone();
+        .map_err(|e| ParseError::CsvFormat(format!("Failed to read CSV headers: {}", e)))
+        .map(|headers| HeaderIndex::new(&normalize_redispatch_header(headers)))?;
+    let beginn_datum_column = index.column("BEGINN_DATUM")?;
+    let beginn_uhrzeit_column = index.column("BEGINN_UHRZEIT")?;
+    let zeitzone_von_column = index.column("ZEITZONE_VON")?;
+    let ende_datum_column = index.column("ENDE_DATUM")?;
+    let ende_uhrzeit_column = index.column("ENDE_UHRZEIT")?;
+    let zeitzone_bis_column = index.column("ZEITZONE_BIS")?;
+    let grund_column = index.column("GRUND_DER_MASSNAHME")?;
+    let richtung_column = index.column("RICHTUNG")?;
+    let mittlere_leistung_column = index.column("MITTLERE_LEISTUNG_MW")?;
+    let maximale_leistung_column = index.column("MAXIMALE_LEISTUNG_MW")?;
+    let gesamte_arbeit_column = index.column("GESAMTE_ARBEIT_MWH")?;
+    let anweisender_column = index.column("ANWEISENDER_UENB")?;
+    let anfordernder_column = index.column("ANFORDERNDER_UENB")?;
+    let anlage_column = index.column("BETROFFENE_ANLAGE")?;
+    let energieart_column = index.column("PRIMAERENERGIEART")?;
 
     let mut rows = Vec::new();
 
@@ -93,53 +215,64 @@ pub fn parse_redispatch_csv(
             result.map_err(|e| ParseError::CsvFormat(format!("CSV parse error: {}", e)))?;
 
         // Extract timestamp fields
-        let beginn_datum = get_field(&record, &headers, "BEGINN_DATUM")?;
-        let beginn_uhrzeit = get_field(&record, &headers, "BEGINN_UHRZEIT")?;
-        let zeitzone_von = get_field(&record, &headers, "ZEITZONE_VON")?;
-        let ende_datum = get_field(&record, &headers, "ENDE_DATUM")?;
-        let ende_uhrzeit = get_field(&record, &headers, "ENDE_UHRZEIT")?;
-        let zeitzone_bis = get_field(&record, &headers, "ZEITZONE_BIS")?;
+        let beginn_datum = beginn_datum_column.get(&record)?;
+        let beginn_uhrzeit = beginn_uhrzeit_column.get(&record)?;
+        let zeitzone_von = zeitzone_von_column.get(&record)?;
+        let ende_datum = ende_datum_column.get(&record)?;
+        let ende_uhrzeit = ende_uhrzeit_column.get(&record)?;
+        let zeitzone_bis = zeitzone_bis_column.get(&record)?;
 
         // Parse timestamps (German format → ISO 8601)
         let timestamp_utc = parse_redispatch_timestamp(beginn_datum, beginn_uhrzeit, zeitzone_von)?;
-        let interval_end_utc = parse_redispatch_timestamp(ende_datum, ende_uhrzeit, zeitzone_bis)?;
+
+        // Blank end date and time marks an ongoing event (open-ended interval)
+        let interval_end_utc = if ende_datum.trim().is_empty() && ende_uhrzeit.trim().is_empty() {
+            None
+        } else {
+            Some(parse_redispatch_timestamp(
+                ende_datum,
+                ende_uhrzeit,
+                zeitzone_bis,
+            )?)
+        };
 
         // Extract event details
-        let grund = get_field(&record, &headers, "GRUND_DER_MASSNAHME")?;
-        let richtung = get_field(&record, &headers, "RICHTUNG")?;
+        let grund = grund_column.get(&record)?;
+        let richtung = richtung_column.get(&record)?;
 
         // Normalize direction (German → English enum)
         let direction = normalize_direction(richtung)?;
 
         // Extract power metrics (German decimal format)
-        let mittlere_leistung = get_field(&record, &headers, "MITTLERE_LEISTUNG_MW")?;
-        let maximale_leistung = get_field(&record, &headers, "MAXIMALE_LEISTUNG_MW")?;
-        let gesamte_arbeit = get_field(&record, &headers, "GESAMTE_ARBEIT_MWH")?;
-
-        let avg_power_mw = if mittlere_leistung.trim().is_empty() {
-            None
-        } else {
-            Some(parse_german_decimal(mittlere_leistung)?)
-        };
-
-        let max_power_mw = if maximale_leistung.trim().is_empty() {
+        let mittlere_leistung = mittlere_leistung_column.get(&record)?;
+        let maximale_leistung = maximale_leistung_column.get(&record)?;
+        let gesamte_arbeit = gesamte_arbeit_column.get(&record)?;
+
+        // Blank cells are NULL; approximate values ("ca. 120") keep their number
+        let avg_power_mw = parse_redispatch_amount(mittlere_leistung)?;
+        let max_power_mw = parse_redispatch_amount(maximale_leistung)?;
+        let total_energy_mwh = parse_redispatch_amount(gesamte_arbeit)?;
+
+        // Extract TSO and facility info (looked up by name, so column order is irrelevant)
+        // - ANFORDERNDER_UENB ("requesting") → requesting_tso
+        // - ANWEISENDER_UENB ("instructing") → instructing_tso
+        // A self-instructed event (both equal) keeps instructing_tso populated
+        let anweisender = anweisender_column.get(&record)?;
+        let anfordernder = anfordernder_column.get(&record)?;
+        let anlage = anlage_column.get(&record)?;
+        let energieart = energieart_column.get(&record)?;
+
+        // Normalize energy type (lenient mode passes unknown values through)
+        let energy_type = if energieart.trim().is_empty() {
             None
         } else {
-            Some(parse_german_decimal(maximale_leistung)?)
-        };
-
-        let total_energy_mwh = if gesamte_arbeit.trim().is_empty() {
-            None
-        } else {
-            Some(parse_german_decimal(gesamte_arbeit)?)
+            match normalize_energy_type(energieart) {
+                Ok(energy_type) => Some(energy_type),
+                Err(_) if lenient => Some(energieart.to_string()),
+                Err(e) => return Err(e.into()),
+            }
         };
 
-        // Extract TSO and facility info
-        let anweisender = get_field(&record, &headers, "ANWEISENDER_UENB")?;
-        let anfordernder = get_field(&record, &headers, "ANFORDERNDER_UENB")?;
-        let anlage = get_field(&record, &headers, "BETROFFENE_ANLAGE")?;
-        let energieart = get_field(&record, &headers, "PRIMAERENERGIEART")?;
-
         rows.push(RedispatchRow {
             timestamp_utc,
             interval_end_utc,
@@ -159,11 +292,7 @@ pub fn parse_redispatch_csv(
             } else {
                 Some(anlage.to_string())
             },
-            energy_type: if energieart.trim().is_empty() {
-                None
-            } else {
-                Some(energieart.to_string())
-            },
+            energy_type,
             source_endpoint: "redispatch".to_string(),
         });
     }
@@ -237,13 +366,39 @@ struct TrafficLightRecord {
 /// assert_eq!(rows[1].grid_status, "YELLOW");
 /// ```
 pub fn parse_trafficlight_json(
+    json_content: &str,
+    date_from: &str,
+    date_to: &str,
+) -> Result<Vec<GridStatusRow>, NtpFdwError> {
+    parse_trafficlight_json_with_mode(json_content, date_from, date_to, false)
+}
+
+/// Parse TrafficLight JSON, optionally passing unknown status values through
+///
+/// With `lenient = false` this is [`parse_trafficlight_json`]: any status outside
+/// the six known values is an error. With `lenient = true` (server option
+/// `grid_status_lenient`), unknown values are kept as-is in `grid_status`, so a
+/// new API variant shows up in query results instead of failing the scan.
+///
+/// # Example
+///
+/// ```
+/// # use supabase_fdw_ntp::grid_parsers::parse_trafficlight_json_with_mode;
+/// let json = r#"[{"From":"2024-10-24T00:00:00","To":"2024-10-24T00:01:00","Value":"ORANGE"}]"#;
+///
+/// assert!(parse_trafficlight_json_with_mode(json, "2024-10-24", "2024-10-25", false).is_err());
+/// let rows = parse_trafficlight_json_with_mode(json, "2024-10-24", "2024-10-25", true).unwrap();
+/// assert_eq!(rows[0].grid_status, "ORANGE");
+/// ```
+pub fn parse_trafficlight_json_with_mode(
     json_content: &str,
     _date_from: &str,
     _date_to: &str,
+    lenient: bool,
 ) -> Result<Vec<GridStatusRow>, NtpFdwError> {
-    // Parse JSON array
-    let records: Vec<TrafficLightRecord> = serde_json::from_str(json_content)
-        .map_err(|e| ParseError::CsvFormat(format!("Failed to parse TrafficLight JSON: {}", e)))?;
+    // Parse JSON array (after any BOM or XSSI prefix)
+    let records: Vec<TrafficLightRecord> = serde_json::from_str(strip_json_preamble(json_content))
+        .map_err(|e| ParseError::JsonFormat(format!("TrafficLight response: {}", e)))?;
 
     let mut rows = Vec::new();
 
@@ -252,8 +407,12 @@ pub fn parse_trafficlight_json(
         let timestamp_utc = parse_iso8601_timestamp(&record.from)?;
         let interval_end_utc = parse_iso8601_timestamp(&record.to)?;
 
-        // Validate grid status value
-        let grid_status = validate_grid_status(&record.value)?;
+        // Validate grid status value (lenient mode passes unknown values through)
+        let grid_status = match validate_grid_status(&record.value) {
+            Ok(status) => status,
+            Err(_) if lenient => record.value,
+            Err(e) => return Err(e.into()),
+        };
 
         rows.push(GridStatusRow {
             timestamp_utc,
@@ -266,6 +425,21 @@ pub fn parse_trafficlight_json(
     Ok(rows)
 }
 
+/// XSSI protection prefix some JSON APIs put before the payload
+const XSSI_PREFIX: &str = ")]}'";
+
+/// Strip a leading byte order mark and XSSI prefix from a JSON body
+///
+/// Only these known preambles (plus surrounding whitespace) are removed;
+/// anything else is left for `serde_json` to reject.
+fn strip_json_preamble(json_content: &str) -> &str {
+    let body = json_content.trim_start_matches('\u{feff}').trim_start();
+    match body.strip_prefix(XSSI_PREFIX) {
+        Some(rest) => rest.strip_prefix(',').unwrap_or(rest).trim_start(),
+        None => body,
+    }
+}
+
 // ============================================================================
 // TESTS
 // ============================================================================
@@ -287,7 +461,10 @@ mod tests {
 
         assert_eq!(rows.len(), 1);
         assert_eq!(rows[0].timestamp_utc, "2024-10-23T22:00:00Z");
-        assert_eq!(rows[0].interval_end_utc, "2024-10-24T08:00:00Z");
+        assert_eq!(
+            rows[0].interval_end_utc,
+            Some("2024-10-24T08:00:00Z".to_string())
+        );
         assert_eq!(rows[0].reason, "Probestart (NetzRes)");
         assert_eq!(rows[0].direction, "increase_generation");
         assert_eq!(rows[0].avg_power_mw, Some(119.5));
@@ -299,7 +476,51 @@ mod tests {
             rows[0].affected_facility,
             Some("Grosskraftwerk Mannheim Block 8".to_string())
         );
-        assert_eq!(rows[0].energy_type, Some("Konventionell".to_string()));
+        assert_eq!(rows[0].energy_type, Some("conventional".to_string()));
+    }
+
+    #[test]
+    fn test_parse_redispatch_csv_approximate_power() {
+        let csv = r#"BEGINN_DATUM;BEGINN_UHRZEIT;ZEITZONE_VON;ENDE_DATUM;ENDE_UHRZEIT;ZEITZONE_BIS;GRUND_DER_MASSNAHME;RICHTUNG;MITTLERE_LEISTUNG_MW;MAXIMALE_LEISTUNG_MW;GESAMTE_ARBEIT_MWH;ANWEISENDER_UENB;ANFORDERNDER_UENB;BETROFFENE_ANLAGE;PRIMAERENERGIEART
+23.10.2024;22:00;UTC;24.10.2024;08:00;UTC;Netzengpass;Wirkleistungseinspeisung erhöhen;~119,5;ca. 120;≈ 1195 MWh;TransnetBW;TransnetBW;Anlage;Konventionell"#;
+
+        let rows = parse_redispatch_csv(csv, "2024-10-23", "2024-10-24").unwrap();
+
+        assert_eq!(rows[0].avg_power_mw, Some(119.5));
+        assert_eq!(rows[0].max_power_mw, Some(120.0));
+        assert_eq!(rows[0].total_energy_mwh, Some(1195.0));
+    }
+
+    #[test]
+    fn test_parse_redispatch_amount() {
+        assert_eq!(parse_redispatch_amount("ca. 120").unwrap(), Some(120.0));
+        assert_eq!(parse_redispatch_amount("CA.120").unwrap(), Some(120.0));
+        assert_eq!(parse_redispatch_amount("~119,5").unwrap(), Some(119.5));
+        assert_eq!(parse_redispatch_amount("~-30").unwrap(), Some(-30.0));
+        assert_eq!(parse_redispatch_amount("120 MW").unwrap(), Some(120.0));
+        assert_eq!(parse_redispatch_amount("  ").unwrap(), None);
+
+        // Junk is rejected, naming the original cell
+        let err = parse_redispatch_amount("ca. viel").unwrap_err();
+        assert!(err.to_string().contains("ca. viel"), "{}", err);
+        assert!(parse_redispatch_amount("12x5").is_err());
+        assert!(parse_redispatch_amount("n/a").is_err());
+    }
+
+    #[test]
+    fn test_parse_redispatch_csv_quoted_reason() {
+        let csv = r#"BEGINN_DATUM;BEGINN_UHRZEIT;ZEITZONE_VON;ENDE_DATUM;ENDE_UHRZEIT;ZEITZONE_BIS;GRUND_DER_MASSNAHME;RICHTUNG;MITTLERE_LEISTUNG_MW;MAXIMALE_LEISTUNG_MW;GESAMTE_ARBEIT_MWH;ANWEISENDER_UENB;ANFORDERNDER_UENB;BETROFFENE_ANLAGE;PRIMAERENERGIEART
+23.10.2024;22:00;UTC;24.10.2024;08:00;UTC;"Netzengpass; vorsorglich";Wirkleistungseinspeisung erhöhen;119,5;120;1195;TransnetBW;TransnetBW;Grosskraftwerk Mannheim Block 8;Konventionell
+24.10.2024;14:30;UTC;24.10.2024;20:45;UTC;"Engpass ""Nord-Süd""; Leitung A";Wirkleistungseinspeisung reduzieren;228;300;741;TenneT DE;TenneT DE;OWP UW Büttel;Erneuerbar"#;
+
+        let rows = parse_redispatch_csv(csv, "2024-10-23", "2024-10-25").unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].reason, "Netzengpass; vorsorglich");
+        assert_eq!(rows[0].direction, "increase_generation");
+        assert_eq!(rows[0].energy_type, Some("conventional".to_string()));
+        assert_eq!(rows[1].reason, "Engpass \"Nord-Süd\"; Leitung A");
+        assert_eq!(rows[1].avg_power_mw, Some(228.0));
     }
 
     #[test]
@@ -312,7 +533,24 @@ mod tests {
         assert_eq!(rows.len(), 1);
         assert_eq!(rows[0].direction, "reduce_generation");
         assert_eq!(rows[0].avg_power_mw, Some(228.0));
-        assert_eq!(rows[0].energy_type, Some("Erneuerbar".to_string()));
+        assert_eq!(rows[0].energy_type, Some("renewable".to_string()));
+    }
+
+    #[test]
+    fn test_parse_redispatch_csv_unknown_energy_type() {
+        let csv = r#"BEGINN_DATUM;BEGINN_UHRZEIT;ZEITZONE_VON;ENDE_DATUM;ENDE_UHRZEIT;ZEITZONE_BIS;GRUND_DER_MASSNAHME;RICHTUNG;MITTLERE_LEISTUNG_MW;MAXIMALE_LEISTUNG_MW;GESAMTE_ARBEIT_MWH;ANWEISENDER_UENB;ANFORDERNDER_UENB;BETROFFENE_ANLAGE;PRIMAERENERGIEART
+24.10.2024;14:30;UTC;24.10.2024;20:45;UTC;Strombedingter Redispatch;Wirkleistungseinspeisung reduzieren;228;300;741;TenneT DE;TenneT DE;Speicher Nord;Speicher"#;
+
+        // Lenient (default): unknown value kept as-is
+        let rows = parse_redispatch_csv(csv, "2024-10-24", "2024-10-25").unwrap();
+        assert_eq!(rows[0].energy_type, Some("Speicher".to_string()));
+
+        // Strict: unknown value fails the parse
+        let err =
+            parse_redispatch_csv_with_mode(csv, "2024-10-24", "2024-10-25", false).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unknown redispatch energy type: 'Speicher'"));
     }
 
     #[test]
@@ -330,6 +568,84 @@ mod tests {
         assert_eq!(result2.unwrap().len(), 0);
     }
 
+    #[test]
+    fn test_parse_redispatch_csv_tso_mapping() {
+        // Distinct TSOs lock the semantics: requesting=ANFORDERNDER, instructing=ANWEISENDER
+        let csv = r#"BEGINN_DATUM;BEGINN_UHRZEIT;ZEITZONE_VON;ENDE_DATUM;ENDE_UHRZEIT;ZEITZONE_BIS;GRUND_DER_MASSNAHME;RICHTUNG;MITTLERE_LEISTUNG_MW;MAXIMALE_LEISTUNG_MW;GESAMTE_ARBEIT_MWH;ANWEISENDER_UENB;ANFORDERNDER_UENB;BETROFFENE_ANLAGE;PRIMAERENERGIEART
+24.10.2024;14:30;UTC;24.10.2024;20:45;UTC;Strombedingter Redispatch;Wirkleistungseinspeisung reduzieren;228;300;741;TenneT DE;Amprion;OWP UW Büttel;Erneuerbar"#;
+
+        let rows = parse_redispatch_csv(csv, "2024-10-24", "2024-10-25").unwrap();
+
+        assert_eq!(rows[0].requesting_tso, "Amprion");
+        assert_eq!(rows[0].instructing_tso, Some("TenneT DE".to_string()));
+    }
+
+    #[test]
+    fn test_parse_redispatch_csv_tso_columns_reordered() {
+        // API has historically reordered these columns - lookup is by name
+        let csv = r#"BEGINN_DATUM;BEGINN_UHRZEIT;ZEITZONE_VON;ENDE_DATUM;ENDE_UHRZEIT;ZEITZONE_BIS;GRUND_DER_MASSNAHME;RICHTUNG;MITTLERE_LEISTUNG_MW;MAXIMALE_LEISTUNG_MW;GESAMTE_ARBEIT_MWH;ANFORDERNDER_UENB;ANWEISENDER_UENB;BETROFFENE_ANLAGE;PRIMAERENERGIEART
+24.10.2024;14:30;UTC;24.10.2024;20:45;UTC;Strombedingter Redispatch;Wirkleistungseinspeisung reduzieren;228;300;741;Amprion;TenneT DE;OWP UW Büttel;Erneuerbar"#;
+
+        let rows = parse_redispatch_csv(csv, "2024-10-24", "2024-10-25").unwrap();
+
+        assert_eq!(rows[0].requesting_tso, "Amprion");
+        assert_eq!(rows[0].instructing_tso, Some("TenneT DE".to_string()));
+    }
+
+    #[test]
+    fn test_parse_redispatch_csv_self_instructed() {
+        // Same TSO requests and instructs: instructing_tso is kept, not collapsed to None
+        let csv = r#"BEGINN_DATUM;BEGINN_UHRZEIT;ZEITZONE_VON;ENDE_DATUM;ENDE_UHRZEIT;ZEITZONE_BIS;GRUND_DER_MASSNAHME;RICHTUNG;MITTLERE_LEISTUNG_MW;MAXIMALE_LEISTUNG_MW;GESAMTE_ARBEIT_MWH;ANWEISENDER_UENB;ANFORDERNDER_UENB;BETROFFENE_ANLAGE;PRIMAERENERGIEART
+23.10.2024;22:00;UTC;24.10.2024;08:00;UTC;Probestart (NetzRes);Wirkleistungseinspeisung erhöhen;119,5;120;1195;TransnetBW;TransnetBW;Grosskraftwerk Mannheim Block 8;Konventionell"#;
+
+        let rows = parse_redispatch_csv(csv, "2024-10-23", "2024-10-24").unwrap();
+
+        assert_eq!(rows[0].requesting_tso, "TransnetBW");
+        assert_eq!(rows[0].instructing_tso, Some("TransnetBW".to_string()));
+    }
+
+    #[test]
+    fn test_parse_redispatch_csv_english_header() {
+        let german = r#"BEGINN_DATUM;BEGINN_UHRZEIT;ZEITZONE_VON;ENDE_DATUM;ENDE_UHRZEIT;ZEITZONE_BIS;GRUND_DER_MASSNAHME;RICHTUNG;MITTLERE_LEISTUNG_MW;MAXIMALE_LEISTUNG_MW;GESAMTE_ARBEIT_MWH;ANWEISENDER_UENB;ANFORDERNDER_UENB;BETROFFENE_ANLAGE;PRIMAERENERGIEART
+23.10.2024;22:00;UTC;24.10.2024;08:00;UTC;Probestart (NetzRes);Wirkleistungseinspeisung erhöhen;119,5;120;1195;TransnetBW;Amprion;Grosskraftwerk Mannheim Block 8;Konventionell"#;
+        let english = r#"START_DATE;START_TIME;TIMEZONE_FROM;END_DATE;END_TIME;TIMEZONE_TO;REASON;DIRECTION;AVERAGE_POWER_MW;MAXIMUM_POWER_MW;TOTAL_ENERGY_MWH;INSTRUCTING_TSO;REQUESTING_TSO;AFFECTED_FACILITY;PRIMARY_ENERGY_TYPE
+23.10.2024;22:00;UTC;24.10.2024;08:00;UTC;Probestart (NetzRes);Wirkleistungseinspeisung erhöhen;119,5;120;1195;TransnetBW;Amprion;Grosskraftwerk Mannheim Block 8;Konventionell"#;
+
+        let expected = parse_redispatch_csv(german, "2024-10-23", "2024-10-24").unwrap();
+        let rows = parse_redispatch_csv(english, "2024-10-23", "2024-10-24").unwrap();
+
+        assert_eq!(rows, expected);
+        assert_eq!(rows[0].timestamp_utc, "2024-10-23T22:00:00Z");
+        assert_eq!(rows[0].direction, "increase_generation");
+        assert_eq!(rows[0].avg_power_mw, Some(119.5));
+        assert_eq!(rows[0].requesting_tso, "Amprion");
+        assert_eq!(rows[0].instructing_tso, Some("TransnetBW".to_string()));
+        assert_eq!(rows[0].energy_type, Some("conventional".to_string()));
+    }
+
+    #[test]
+    fn test_parse_redispatch_csv_header_case_insensitive() {
+        let csv = r#"Beginn_Datum;beginn_uhrzeit;Zeitzone_Von;Ende_Datum;Ende_Uhrzeit;Zeitzone_Bis;Grund_der_Massnahme;Richtung;Mittlere_Leistung_MW;Maximale_Leistung_MW;Gesamte_Arbeit_MWh;Anweisender_UENB;Anfordernder_UENB;Betroffene_Anlage;start_date_extra;PrimaerEnergieArt
+23.10.2024;22:00;UTC;24.10.2024;08:00;UTC;Netzengpass;Wirkleistungseinspeisung reduzieren;50;60;500;TenneT;TenneT;Anlage;x;Erneuerbar"#;
+
+        let rows = parse_redispatch_csv(csv, "2024-10-23", "2024-10-24").unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].direction, "reduce_generation");
+        assert_eq!(rows[0].total_energy_mwh, Some(500.0));
+        assert_eq!(rows[0].energy_type, Some("renewable".to_string()));
+    }
+
+    #[test]
+    fn test_normalize_redispatch_header() {
+        let headers = csv::StringRecord::from(vec!["Start_Date", "RICHTUNG", "Extra"]);
+
+        assert_eq!(
+            normalize_redispatch_header(&headers),
+            csv::StringRecord::from(vec!["BEGINN_DATUM", "RICHTUNG", "EXTRA"])
+        );
+    }
+
     #[test]
     fn test_parse_redispatch_csv_missing_column() {
         let csv = r#"BEGINN_DATUM;BEGINN_UHRZEIT
@@ -339,6 +655,28 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_redispatch_csv_ongoing_event() {
+        let csv = r#"BEGINN_DATUM;BEGINN_UHRZEIT;ZEITZONE_VON;ENDE_DATUM;ENDE_UHRZEIT;ZEITZONE_BIS;GRUND_DER_MASSNAHME;RICHTUNG;MITTLERE_LEISTUNG_MW;MAXIMALE_LEISTUNG_MW;GESAMTE_ARBEIT_MWH;ANWEISENDER_UENB;ANFORDERNDER_UENB;BETROFFENE_ANLAGE;PRIMAERENERGIEART
+24.10.2024;14:00;UTC;;;UTC;Strombedingter Redispatch;Wirkleistungseinspeisung reduzieren;50;60;;TenneT DE;TenneT DE;Windpark Nord;Erneuerbar"#;
+
+        let rows = parse_redispatch_csv(csv, "2024-10-24", "2024-10-25").unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].timestamp_utc, "2024-10-24T14:00:00Z");
+        assert_eq!(rows[0].interval_end_utc, None);
+        assert_eq!(rows[0].total_energy_mwh, None);
+    }
+
+    #[test]
+    fn test_parse_redispatch_csv_partial_end_is_error() {
+        // Only the end time is blank: malformed, not ongoing
+        let csv = r#"BEGINN_DATUM;BEGINN_UHRZEIT;ZEITZONE_VON;ENDE_DATUM;ENDE_UHRZEIT;ZEITZONE_BIS;GRUND_DER_MASSNAHME;RICHTUNG;MITTLERE_LEISTUNG_MW;MAXIMALE_LEISTUNG_MW;GESAMTE_ARBEIT_MWH;ANWEISENDER_UENB;ANFORDERNDER_UENB;BETROFFENE_ANLAGE;PRIMAERENERGIEART
+24.10.2024;14:00;UTC;25.10.2024;;UTC;Strombedingter Redispatch;Wirkleistungseinspeisung reduzieren;50;60;;TenneT DE;TenneT DE;Windpark Nord;Erneuerbar"#;
+
+        assert!(parse_redispatch_csv(csv, "2024-10-24", "2024-10-25").is_err());
+    }
+
     // ========================================================================
     // TrafficLight JSON Parser Tests
     // ========================================================================
@@ -359,6 +697,32 @@ mod tests {
         assert_eq!(rows[1].grid_status, "YELLOW");
     }
 
+    #[test]
+    fn test_parse_trafficlight_json_unknown_status_strict() {
+        let json =
+            r#"[{"From":"2024-10-24T00:00:00Z","To":"2024-10-24T00:01:00Z","Value":"ORANGE"}]"#;
+
+        assert!(parse_trafficlight_json(json, "2024-10-24", "2024-10-25").is_err());
+        assert!(
+            parse_trafficlight_json_with_mode(json, "2024-10-24", "2024-10-25", false).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_trafficlight_json_unknown_status_lenient() {
+        let json = r#"[
+  {"From":"2024-10-24T00:00:00Z","To":"2024-10-24T00:01:00Z","Value":"GREEN"},
+  {"From":"2024-10-24T00:01:00Z","To":"2024-10-24T00:02:00Z","Value":"ORANGE"}
+]"#;
+
+        let rows =
+            parse_trafficlight_json_with_mode(json, "2024-10-24", "2024-10-25", true).unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].grid_status, "GREEN");
+        assert_eq!(rows[1].grid_status, "ORANGE");
+    }
+
     #[test]
     fn test_parse_trafficlight_json_all_statuses() {
         let json = r#"[
@@ -389,7 +753,73 @@ mod tests {
     fn test_parse_trafficlight_json_invalid_json() {
         let json = "invalid json";
         let result = parse_trafficlight_json(json, "2024-10-24", "2024-10-25");
-        assert!(result.is_err());
+
+        let Err(NtpFdwError::Parse(ParseError::JsonFormat(msg))) = result else {
+            panic!("Expected JsonFormat error, got {:?}", result);
+        };
+        assert_eq!(
+            msg,
+            "TrafficLight response: expected value at line 1 column 1"
+        );
+    }
+
+    #[test]
+    fn test_parse_trafficlight_json_wrong_shape() {
+        // Valid JSON, but not an array of records: still a JSON error, not a CSV one
+        let json = r#"[{"From":"2024-10-24T00:00:00Z","Value":"GREEN"}]"#;
+
+        let err = parse_trafficlight_json(json, "2024-10-24", "2024-10-25").unwrap_err();
+
+        assert!(matches!(err, NtpFdwError::Parse(ParseError::JsonFormat(_))));
+        let msg = err.to_string();
+        assert!(msg.contains("JSON format error"), "{}", msg);
+        assert!(msg.contains("missing field `To`"), "{}", msg);
+        assert!(!msg.contains("CSV"), "{}", msg);
+    }
+
+    #[test]
+    fn test_parse_trafficlight_json_bom_prefix() {
+        let json = "\u{feff}[{\"From\":\"2024-10-24T00:00:00Z\",\"To\":\"2024-10-24T00:01:00Z\",\"Value\":\"GREEN\"}]";
+
+        let rows = parse_trafficlight_json(json, "2024-10-24", "2024-10-25").unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].grid_status, "GREEN");
+    }
+
+    #[test]
+    fn test_parse_trafficlight_json_xssi_prefix() {
+        let json = r#")]}'
+[
+  {"From":"2024-10-24T00:00:00Z","To":"2024-10-24T00:01:00Z","Value":"GREEN"},
+  {"From":"2024-10-24T00:01:00Z","To":"2024-10-24T00:02:00Z","Value":"RED"}
+]"#;
+
+        let rows = parse_trafficlight_json(json, "2024-10-24", "2024-10-25").unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].grid_status, "RED");
+
+        // BOM, XSSI prefix with trailing comma, and whitespace combined
+        let combined = format!("\u{feff}  )]}}',\n{}", &json[5..]);
+        assert_eq!(
+            parse_trafficlight_json(&combined, "2024-10-24", "2024-10-25")
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_parse_trafficlight_json_malformed_after_preamble() {
+        // Only the known preambles are stripped; other garbage still fails
+        for json in [")]}'", ")]}' [{", "while(1);[]", ")]}[]"] {
+            assert!(
+                parse_trafficlight_json(json, "2024-10-24", "2024-10-25").is_err(),
+                "{}",
+                json
+            );
+        }
     }
 
     #[test]
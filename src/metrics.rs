@@ -0,0 +1,134 @@
+//! Session-level counters behind the `scan_metrics` table
+//!
+//! Per-scan progress is only visible as `report_info` messages. These counters
+//! accumulate over all scans of the session instead: API requests and bytes
+//! received, OAuth2 retries, and (from the [`OAuth2Manager`]) token cache
+//! activity.
+//!
+//! Like the circuit breaker, the counters live on the `NtpFdw` singleton and
+//! start at zero when the FDW instance is initialized. Reading them is a scan
+//! of the `scan_metrics` foreign table, which the query router answers without
+//! any API request.
+//!
+//! [`OAuth2Manager`]: crate::oauth2::OAuth2Manager
+
+use std::cell::Cell;
+
+use crate::oauth2::TokenStats;
+
+/// `table` option value of the metrics foreign table
+pub(crate) const TABLE_NAME: &str = "scan_metrics";
+
+/// Counters incremented along the fetch path of every data scan
+#[derive(Debug, Default)]
+pub(crate) struct SessionMetrics {
+    /// Scans of the data tables (reads of `scan_metrics` are not counted)
+    scans: Cell<u64>,
+
+    /// API requests dispatched, failed ones included
+    requests: Cell<u64>,
+
+    /// Response body bytes of successful requests
+    bytes_received: Cell<u64>,
+
+    /// Requests re-issued after the token expired
+    retries: Cell<u64>,
+}
+
+impl SessionMetrics {
+    /// Count one data table scan
+    pub(crate) fn record_scan(&self) {
+        increment(&self.scans, 1);
+    }
+
+    /// Count one dispatched API request and the size of its response body
+    ///
+    /// `bytes` is 0 for failed requests.
+    pub(crate) fn record_request(&self, bytes: usize) {
+        increment(&self.requests, 1);
+        increment(&self.bytes_received, bytes as u64);
+    }
+
+    /// Count one request retried with a refreshed token
+    pub(crate) fn record_retry(&self) {
+        increment(&self.retries, 1);
+    }
+
+    /// Current counter values, combined with the OAuth2 token counters
+    pub(crate) fn snapshot(&self, tokens: TokenStats) -> MetricsRow {
+        MetricsRow {
+            scans: self.scans.get(),
+            requests: self.requests.get(),
+            bytes_received: self.bytes_received.get(),
+            retries: self.retries.get(),
+            token_cache_hits: tokens.cache_hits,
+            token_cache_misses: tokens.cache_misses,
+            tokens_fetched: tokens.tokens_fetched,
+        }
+    }
+}
+
+/// Add to a counter, saturating instead of wrapping
+fn increment(counter: &Cell<u64>, by: u64) {
+    counter.set(counter.get().saturating_add(by));
+}
+
+/// The single row of the `scan_metrics` table
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct MetricsRow {
+    pub(crate) scans: u64,
+    pub(crate) requests: u64,
+    pub(crate) bytes_received: u64,
+    pub(crate) retries: u64,
+    pub(crate) token_cache_hits: u64,
+    pub(crate) token_cache_misses: u64,
+    pub(crate) tokens_fetched: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters_accumulate() {
+        let metrics = SessionMetrics::default();
+
+        metrics.record_scan();
+        metrics.record_request(1_024);
+        metrics.record_request(0);
+        metrics.record_retry();
+        metrics.record_scan();
+        metrics.record_request(512);
+
+        let tokens = TokenStats {
+            cache_hits: 4,
+            cache_misses: 1,
+            tokens_fetched: 1,
+        };
+        assert_eq!(
+            metrics.snapshot(tokens),
+            MetricsRow {
+                scans: 2,
+                requests: 3,
+                bytes_received: 1_536,
+                retries: 1,
+                token_cache_hits: 4,
+                token_cache_misses: 1,
+                tokens_fetched: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_counters_saturate() {
+        let metrics = SessionMetrics::default();
+        metrics.bytes_received.set(u64::MAX - 1);
+
+        metrics.record_request(10);
+
+        assert_eq!(
+            metrics.snapshot(TokenStats::default()).bytes_received,
+            u64::MAX
+        );
+    }
+}
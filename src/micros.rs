@@ -0,0 +1,199 @@
+//! Microsecond timestamps, the unit of PostgreSQL TIMESTAMPTZ
+//!
+//! Qual values and `Cell::Timestamptz` cells are `i64` microseconds since the
+//! Unix epoch, while rows carry ISO 8601 strings and the host clock reports
+//! seconds. [`Micros`] marks a value as microseconds and owns the conversions
+//! between these forms, so seconds or milliseconds cannot be compared against
+//! a bound by accident. The `Cell` interface stays `i64`: wrap on the way in,
+//! unwrap with [`Micros::get`] on the way out.
+
+use std::fmt;
+
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+
+/// Microseconds per second
+const PER_SECOND: i64 = 1_000_000;
+
+/// Microseconds per day
+const PER_DAY: i64 = 86_400 * PER_SECOND;
+
+/// Microseconds since 1970-01-01T00:00:00Z
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct Micros(pub(crate) i64);
+
+impl Micros {
+    /// Convert seconds since epoch (e.g. the host clock), saturating on overflow
+    pub(crate) fn from_secs(secs: i64) -> Self {
+        Self(secs.saturating_mul(PER_SECOND))
+    }
+
+    /// Parse an RFC 3339 timestamp ("2024-10-24T06:00:00Z", any offset)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Micros)` - Instant of the timestamp
+    /// * `Err(String)` - Not an RFC 3339 timestamp
+    pub(crate) fn from_rfc3339(s: &str) -> Result<Self, String> {
+        DateTime::parse_from_rfc3339(s)
+            .map(|dt| Self(dt.timestamp_micros()))
+            .map_err(|e| {
+                format!(
+                    "Failed to parse ISO 8601 timestamp '{}': {}. Expected format: YYYY-MM-DDTHH:MM:SSZ",
+                    s, e
+                )
+            })
+    }
+
+    /// Parse an RFC 3339 timestamp or a date-only string ("2024-10-20")
+    ///
+    /// Dates are taken as the start of the day (00:00:00 UTC).
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        if let Ok(micros) = Self::from_rfc3339(s) {
+            return Some(micros);
+        }
+
+        let date = NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()?;
+        let midnight = date.and_hms_opt(0, 0, 0)?.and_utc();
+        Some(Self(midnight.timestamp_micros()))
+    }
+
+    /// Raw value for `Cell::Timestamptz` and `TimestampBounds`
+    pub(crate) fn get(self) -> i64 {
+        self.0
+    }
+
+    /// UTC date (YYYY-MM-DD) of the instant
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - Date string in YYYY-MM-DD format
+    /// * `Err(String)` - Timestamp is out of the representable range
+    pub(crate) fn to_date_string(self) -> Result<String, String> {
+        DateTime::from_timestamp_micros(self.0)
+            .map(|dt| dt.format("%Y-%m-%d").to_string())
+            .ok_or_else(|| {
+                format!(
+                    "Invalid timestamp: {} microseconds ({} seconds) is out of valid range",
+                    self.0,
+                    self.0 / PER_SECOND
+                )
+            })
+    }
+
+    /// Whether the instant is exactly midnight UTC
+    pub(crate) fn is_midnight(self) -> bool {
+        self.0.rem_euclid(PER_DAY) == 0
+    }
+
+    /// Compare against a qual bound with its SQL operator
+    ///
+    /// `self <operator> bound` for `>=`, `>`, `=`, `<` and `<=`; unknown
+    /// operators don't filter (`true`).
+    pub(crate) fn satisfies(self, operator: &str, bound: Micros) -> bool {
+        match operator {
+            ">=" => self >= bound,
+            ">" => self > bound,
+            "=" => self == bound,
+            "<" => self < bound,
+            "<=" => self <= bound,
+            _ => true,
+        }
+    }
+}
+
+/// RFC 3339 UTC timestamp for messages, raw microseconds if out of range
+impl fmt::Display for Micros {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match DateTime::<Utc>::from_timestamp_micros(self.0) {
+            Some(dt) => f.write_str(&dt.to_rfc3339_opts(SecondsFormat::AutoSi, true)),
+            None => write!(f, "{} µs", self.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2024-10-24T00:00:00Z
+    const OCT_24: i64 = 1_729_728_000_000_000;
+
+    #[test]
+    fn test_from_rfc3339() {
+        assert_eq!(
+            Micros::from_rfc3339("2024-10-24T06:00:00Z").unwrap(),
+            Micros(OCT_24 + 6 * 3_600 * PER_SECOND)
+        );
+        // Offsets are normalized to UTC
+        assert_eq!(
+            Micros::from_rfc3339("2024-10-24T08:00:00+02:00").unwrap(),
+            Micros(OCT_24 + 6 * 3_600 * PER_SECOND)
+        );
+        assert_eq!(
+            Micros::from_rfc3339("2024-10-24T00:00:00.5Z").unwrap(),
+            Micros(OCT_24 + 500_000)
+        );
+
+        let err = Micros::from_rfc3339("2024-10-24").unwrap_err();
+        assert!(err.contains("Failed to parse ISO 8601 timestamp '2024-10-24'"));
+    }
+
+    #[test]
+    fn test_parse_accepts_dates() {
+        assert_eq!(Micros::parse("2024-10-24"), Some(Micros(OCT_24)));
+        assert_eq!(Micros::parse("2024-10-24T00:00:00Z"), Some(Micros(OCT_24)));
+        assert_eq!(Micros::parse("2024-13-01"), None);
+        assert_eq!(Micros::parse("not-a-date"), None);
+    }
+
+    #[test]
+    fn test_from_secs() {
+        assert_eq!(Micros::from_secs(1_729_728_000), Micros(OCT_24));
+        assert_eq!(Micros::from_secs(i64::MAX), Micros(i64::MAX));
+    }
+
+    #[test]
+    fn test_to_date_string() {
+        assert_eq!(Micros(OCT_24).to_date_string().unwrap(), "2024-10-24");
+        assert_eq!(
+            Micros(OCT_24 + PER_DAY - 1).to_date_string().unwrap(),
+            "2024-10-24"
+        );
+        // Before the epoch the date rounds down, not toward 1970
+        assert_eq!(Micros(-1).to_date_string().unwrap(), "1969-12-31");
+        assert!(Micros(i64::MAX).to_date_string().is_err());
+    }
+
+    #[test]
+    fn test_is_midnight() {
+        assert!(Micros(OCT_24).is_midnight());
+        assert!(Micros(-PER_DAY).is_midnight());
+        assert!(!Micros(OCT_24 + PER_SECOND).is_midnight());
+    }
+
+    #[test]
+    fn test_satisfies() {
+        let bound = Micros(OCT_24);
+        let later = Micros(OCT_24 + 1);
+
+        assert!(later.satisfies(">=", bound));
+        assert!(later.satisfies(">", bound));
+        assert!(!later.satisfies("<", bound));
+        assert!(!later.satisfies("<=", bound));
+        assert!(!later.satisfies("=", bound));
+        assert!(bound.satisfies("=", bound));
+        assert!(bound.satisfies("<=", bound));
+        assert!(!bound.satisfies("<", bound));
+        assert!(later.satisfies("<>", bound));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Micros(OCT_24).to_string(), "2024-10-24T00:00:00Z");
+        assert_eq!(
+            Micros(OCT_24 + 1_500).to_string(),
+            "2024-10-24T00:00:00.001500Z"
+        );
+        assert_eq!(Micros(i64::MAX).to_string(), format!("{} µs", i64::MAX));
+    }
+}
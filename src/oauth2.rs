@@ -4,7 +4,8 @@
 //!
 //! # Features
 //! - Thread-safe token caching with Arc<Mutex<>>
-//! - Proactive token refresh (5-minute buffer before expiration)
+//! - Proactive token refresh (5-minute buffer before expiration, half the
+//!   lifetime for tokens living under 10 minutes)
 //! - Uses only WASM-compatible Supabase HTTP interface
 //! - No external OAuth2 crates (WASM constraint)
 //!
@@ -23,7 +24,7 @@
 //! let token = manager.get_token()?;
 //! ```
 
-use crate::bindings::supabase::wrappers::time;
+use crate::bindings::supabase::wrappers::{time, utils};
 use crate::error::OAuth2Error;
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
@@ -50,6 +51,20 @@ pub struct OAuth2Config {
     pub scope: String,
 }
 
+/// Seconds before expiry at which a cached token is refreshed proactively
+const REFRESH_BUFFER_SECONDS: i64 = 300;
+
+/// Unix timestamp at which a token fetched at `now` is refreshed proactively
+///
+/// Tokens are refreshed [`REFRESH_BUFFER_SECONDS`] before expiry. Shorter-lived
+/// tokens (under twice the buffer) are refreshed after half their lifetime
+/// instead, so they are still cached rather than near expiry on arrival.
+fn refresh_at(now: i64, expires_in: u64) -> i64 {
+    let lifetime = i64::try_from(expires_in).unwrap_or(i64::MAX);
+    let buffer = REFRESH_BUFFER_SECONDS.min(lifetime / 2);
+    now.saturating_add(lifetime).saturating_sub(buffer)
+}
+
 /// Cached access token with expiration
 #[derive(Debug, Clone)]
 struct CachedToken {
@@ -58,20 +73,22 @@ struct CachedToken {
 
     /// Unix timestamp when token expires (seconds since epoch)
     expires_at: i64,
+
+    /// Unix timestamp from which the token is refreshed (see [`refresh_at`])
+    refresh_at: i64,
 }
 
 impl CachedToken {
     /// Check if token is expired or will expire soon
     ///
-    /// Uses 5-minute buffer (300 seconds) for proactive refresh
+    /// Uses 5-minute buffer (300 seconds) for proactive refresh, or half the
+    /// lifetime for shorter-lived tokens
     /// Now uses Supabase time::epoch_secs() instead of SystemTime (WASM-compatible)
     fn is_expired(&self) -> bool {
         let now = time::epoch_secs();
 
-        // Refresh 5 minutes before actual expiration (proactive refresh)
-        const REFRESH_BUFFER_SECONDS: i64 = 300;
-
-        now >= self.expires_at.saturating_sub(REFRESH_BUFFER_SECONDS)
+        // Refresh ahead of actual expiration (proactive refresh)
+        now >= self.refresh_at
     }
 }
 
@@ -95,6 +112,63 @@ struct TokenResponse {
     scope: String,
 }
 
+/// Reject token responses that can't be used or cached
+///
+/// # Returns
+///
+/// * `Ok(())` - Non-empty token with a non-zero lifetime
+/// * `Err(OAuth2Error::InvalidTokenResponse)` - Empty `access_token`, or an
+///   `expires_in` of 0 (the token is already expired when it arrives)
+fn validate_token_response(response: &TokenResponse) -> Result<(), OAuth2Error> {
+    if response.access_token.is_empty() {
+        return Err(OAuth2Error::InvalidTokenResponse(
+            "access_token is empty".to_string(),
+        ));
+    }
+
+    if response.expires_in == 0 {
+        return Err(OAuth2Error::InvalidTokenResponse(
+            "expires_in is 0: the token expires as soon as it is issued. \
+             Check the token endpoint configuration"
+                .to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Warning for a token granted with a different scope than requested
+///
+/// A narrower grant (typical for a misconfigured client) otherwise only shows
+/// up later as 403 responses from the data endpoints. Scopes are compared as
+/// space-separated sets; an empty granted scope means "as requested"
+/// (RFC 6749, section 5.1) and never warns.
+///
+/// # Returns
+///
+/// * `Some(String)` - Message naming both scopes
+/// * `None` - Granted scope matches or was not reported
+fn scope_mismatch_warning(requested: &str, granted: &str) -> Option<String> {
+    if granted.trim().is_empty() {
+        return None;
+    }
+
+    let mut requested_scopes: Vec<&str> = requested.split_whitespace().collect();
+    let mut granted_scopes: Vec<&str> = granted.split_whitespace().collect();
+    requested_scopes.sort_unstable();
+    requested_scopes.dedup();
+    granted_scopes.sort_unstable();
+    granted_scopes.dedup();
+    if requested_scopes == granted_scopes {
+        return None;
+    }
+
+    Some(format!(
+        "oauth2: token endpoint granted scope '{}' but '{}' was requested; data endpoints may answer 403",
+        granted, requested
+    ))
+}
+
 /// OAuth2 token manager with caching
 ///
 /// Thread-safe implementation using Arc<Mutex<>> for concurrent access
@@ -104,6 +178,22 @@ pub struct OAuth2Manager {
 
     /// Cached token (None if not yet fetched or expired)
     cached_token: Arc<Mutex<Option<CachedToken>>>,
+
+    /// Token cache counters (see [`OAuth2Manager::token_stats`])
+    stats: Arc<Mutex<TokenStats>>,
+}
+
+/// Token cache counters of an [`OAuth2Manager`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenStats {
+    /// `get_token` calls answered from the cache
+    pub cache_hits: u64,
+
+    /// `get_token` calls that had to fetch (empty, expired or cleared cache)
+    pub cache_misses: u64,
+
+    /// Tokens successfully fetched from the token endpoint
+    pub tokens_fetched: u64,
 }
 
 impl OAuth2Manager {
@@ -126,6 +216,21 @@ impl OAuth2Manager {
         Self {
             config,
             cached_token: Arc::new(Mutex::new(None)),
+            stats: Arc::new(Mutex::new(TokenStats::default())),
+        }
+    }
+
+    /// Token cache counters since the manager was created
+    ///
+    /// A poisoned counter lock reads as all zeros.
+    pub fn token_stats(&self) -> TokenStats {
+        self.stats.lock().map(|stats| *stats).unwrap_or_default()
+    }
+
+    /// Update the token cache counters (skipped if the lock is poisoned)
+    fn record(&self, update: impl FnOnce(&mut TokenStats)) {
+        if let Ok(mut stats) = self.stats.lock() {
+            update(&mut stats);
         }
     }
 
@@ -148,6 +253,14 @@ impl OAuth2Manager {
     /// // Use token in Authorization header: format!("Bearer {}", token)
     /// ```
     pub fn get_token(&self) -> Result<String, OAuth2Error> {
+        self.get_token_with(|| self.fetch_token())
+    }
+
+    /// [`OAuth2Manager::get_token`] with the token fetch of a cache miss
+    fn get_token_with<F>(&self, fetch: F) -> Result<String, OAuth2Error>
+    where
+        F: FnOnce() -> Result<CachedToken, OAuth2Error>,
+    {
         // Lock the cache (thread-safe, handle poisoning gracefully)
         let mut cache = self
             .cached_token
@@ -161,12 +274,15 @@ impl OAuth2Manager {
         if let Some(ref token) = *cache {
             if !token.is_expired() {
                 // Return cached token (still valid)
+                self.record(|stats| stats.cache_hits += 1);
                 return Ok(token.access_token.clone());
             }
         }
 
         // Cache empty or expired, fetch new token
-        let new_token = self.fetch_token()?;
+        self.record(|stats| stats.cache_misses += 1);
+        let new_token = fetch()?;
+        self.record(|stats| stats.tokens_fetched += 1);
 
         // Update cache
         *cache = Some(new_token.clone());
@@ -174,6 +290,49 @@ impl OAuth2Manager {
         Ok(new_token.access_token)
     }
 
+    /// Drop the cached token and fetch a new one right away
+    ///
+    /// Backs the `refresh_token` maintenance table: [`OAuth2Manager::clear_cache`]
+    /// followed by [`OAuth2Manager::get_token`], with the token endpoint POST,
+    /// clock and log supplied by the caller (the scan host in production, a
+    /// mock in tests).
+    ///
+    /// # Arguments
+    /// * `post` - POSTs a form-urlencoded body to a URL, returning status and body
+    /// * `now` - Current time in seconds since epoch
+    /// * `report` - Logs a granted-scope mismatch
+    ///
+    /// # Returns
+    /// - `Ok((token, expires_at))` - New access token and its expiry (seconds since epoch)
+    /// - `Err(OAuth2Error)` - Token fetch failed (the cache stays empty)
+    pub(crate) fn refresh_token<P, R>(
+        &self,
+        post: P,
+        now: i64,
+        report: R,
+    ) -> Result<(String, i64), OAuth2Error>
+    where
+        P: FnOnce(&str, &str) -> Result<(u16, String), String>,
+        R: Fn(&str),
+    {
+        self.clear_cache();
+
+        let mut expires_at = 0;
+        let token =
+            self.get_token_with(|| {
+                let (status, body) = post(&self.config.token_url, &self.token_request_body())
+                    .map_err(|err| OAuth2Error::FetchFailed {
+                        status: 0,
+                        body: err,
+                    })?;
+                let token = self.token_from_response(status, body, now, &report)?;
+                expires_at = token.expires_at;
+                Ok(token)
+            })?;
+
+        Ok((token, expires_at))
+    }
+
     /// Fetch new access token from OAuth2 endpoint
     ///
     /// Performs HTTP POST with client credentials flow
@@ -187,14 +346,6 @@ impl OAuth2Manager {
     /// - Form-urlencoded body (not JSON!)
     /// - Parses JSON response
     fn fetch_token(&self) -> Result<CachedToken, OAuth2Error> {
-        // Build form-urlencoded request body
-        let body = format!(
-            "grant_type=client_credentials&client_id={}&client_secret={}&scope={}",
-            urlencoding::encode(&self.config.client_id),
-            urlencoding::encode(&self.config.client_secret),
-            urlencoding::encode(&self.config.scope)
-        );
-
         // Build HTTP request using Supabase interface
         let request = crate::bindings::supabase::wrappers::http::Request {
             method: crate::bindings::supabase::wrappers::http::Method::Post,
@@ -203,7 +354,7 @@ impl OAuth2Manager {
                 "content-type".to_string(),
                 "application/x-www-form-urlencoded".to_string(),
             )],
-            body,
+            body: self.token_request_body(),
         };
 
         // Make HTTP POST request
@@ -215,48 +366,73 @@ impl OAuth2Manager {
                 }
             })?;
 
+        // Token lifetime starts at the Supabase clock (WASM-compatible)
+        self.token_from_response(
+            response.status_code,
+            response.body,
+            time::epoch_secs(),
+            utils::report_info,
+        )
+    }
+
+    /// Form-urlencoded client credentials request body
+    fn token_request_body(&self) -> String {
+        format!(
+            "grant_type=client_credentials&client_id={}&client_secret={}&scope={}",
+            urlencoding::encode(&self.config.client_id),
+            urlencoding::encode(&self.config.client_secret),
+            urlencoding::encode(&self.config.scope)
+        )
+    }
+
+    /// Turn a token endpoint response into a cacheable token
+    ///
+    /// # Arguments
+    /// * `status` / `body` - HTTP status code and body of the token endpoint
+    /// * `now` - Current time in seconds since epoch (start of the token lifetime)
+    /// * `report` - Logs a granted-scope mismatch
+    ///
+    /// # Returns
+    /// - `Ok(CachedToken)` - Valid token expiring `expires_in` seconds after `now`
+    /// - `Err(OAuth2Error)` - HTTP error, invalid response, or parse error
+    fn token_from_response(
+        &self,
+        status: u16,
+        body: String,
+        now: i64,
+        report: impl Fn(&str),
+    ) -> Result<CachedToken, OAuth2Error> {
         // Check for HTTP errors
-        if response.status_code != 200 {
+        if status != 200 {
             // Handle specific error codes
-            return match response.status_code {
+            return match status {
                 401 => Err(OAuth2Error::InvalidCredentials),
-                _ => Err(OAuth2Error::FetchFailed {
-                    status: response.status_code,
-                    body: response.body.clone(),
-                }),
+                _ => Err(OAuth2Error::FetchFailed { status, body }),
             };
         }
 
         // Parse JSON response
-        let token_response: TokenResponse =
-            serde_json::from_str(&response.body).map_err(|err| {
-                OAuth2Error::InvalidTokenResponse(format!(
-                    "Failed to parse token response: {}. Body: {}",
-                    err, response.body
-                ))
-            })?;
+        let token_response: TokenResponse = serde_json::from_str(&body).map_err(|err| {
+            OAuth2Error::InvalidTokenResponse(format!(
+                "Failed to parse token response: {}. Body: {}",
+                err, body
+            ))
+        })?;
 
         // Validate response
-        if token_response.access_token.is_empty() {
-            return Err(OAuth2Error::InvalidTokenResponse(
-                "access_token is empty".to_string(),
-            ));
-        }
+        validate_token_response(&token_response)?;
 
-        if token_response.expires_in == 0 {
-            return Err(OAuth2Error::InvalidTokenResponse(
-                "expires_in is 0".to_string(),
-            ));
+        // Surface a narrower grant now rather than as 403s from the data endpoints
+        if let Some(warning) = scope_mismatch_warning(&self.config.scope, &token_response.scope) {
+            report(&warning);
         }
 
-        // Calculate expiration timestamp using Supabase time interface (WASM-compatible)
-        let now = time::epoch_secs();
-
         let expires_at = now + token_response.expires_in as i64;
 
         Ok(CachedToken {
             access_token: token_response.access_token,
             expires_at,
+            refresh_at: refresh_at(now, token_response.expires_in),
         })
     }
 
@@ -278,7 +454,7 @@ impl OAuth2Manager {
         // If lock fails (poisoned), cache is already effectively cleared
     }
 
-    /// Check if cached token is near expiry (within 5-minute buffer)
+    /// Check if cached token is near expiry (within its refresh buffer)
     ///
     /// Used for proactive token refresh before making API calls.
     /// This implements the "proactive" part of the hybrid refresh strategy
@@ -290,7 +466,8 @@ impl OAuth2Manager {
     ///
     /// # Proactive Refresh Strategy
     ///
-    /// The 5-minute buffer ensures tokens are refreshed BEFORE they expire,
+    /// The refresh buffer (5 minutes, or half the lifetime for tokens living
+    /// under 10 minutes) ensures tokens are refreshed BEFORE they expire,
     /// preventing 401 errors during multi-endpoint queries. This is the
     /// "proactive" component of Phase 1's hybrid strategy:
     ///
@@ -310,7 +487,7 @@ impl OAuth2Manager {
         // Handle mutex poisoning gracefully - assume expired on error (safe fallback)
         match self.cached_token.lock() {
             Ok(cache) => match cache.as_ref() {
-                Some(token) => token.is_expired(), // Uses 5-min buffer internally
+                Some(token) => token.is_expired(), // Uses the refresh buffer internally
                 None => true,                      // No token = needs refresh
             },
             Err(_) => true, // Lock poisoned = assume expired (triggers refresh)
@@ -355,6 +532,7 @@ mod tests {
         // Verify cache is initially empty
         let cache = manager.cached_token.lock().unwrap();
         assert!(cache.is_none(), "Cache should be empty on creation");
+        assert_eq!(manager.token_stats(), TokenStats::default());
     }
 
     #[test]
@@ -407,6 +585,87 @@ mod tests {
         assert_eq!(response.scope, ""); // Default empty string
     }
 
+    fn token_response(expires_in: u64) -> TokenResponse {
+        TokenResponse {
+            access_token: "token123".to_string(),
+            expires_in,
+            token_type: "Bearer".to_string(),
+            scope: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_validate_token_response_zero_lifetime() {
+        let err = validate_token_response(&token_response(0)).unwrap_err();
+
+        assert!(
+            matches!(err, OAuth2Error::InvalidTokenResponse(_)),
+            "{:?}",
+            err
+        );
+        assert!(err.to_string().contains("expires_in is 0"), "{}", err);
+    }
+
+    #[test]
+    fn test_validate_token_response_accepts_short_lifetimes() {
+        assert!(validate_token_response(&token_response(1)).is_ok());
+        assert!(validate_token_response(&token_response(120)).is_ok());
+        assert!(validate_token_response(&token_response(400)).is_ok());
+        assert!(validate_token_response(&token_response(3600)).is_ok());
+    }
+
+    #[test]
+    fn test_refresh_at_halves_buffer_for_short_lifetimes() {
+        // Full 5-minute buffer once the lifetime is at least twice as long
+        assert_eq!(refresh_at(1_000, 3600), 1_000 + 3600 - 300);
+        assert_eq!(refresh_at(1_000, 600), 1_000 + 300);
+
+        // Shorter tokens are used for half their lifetime before refreshing
+        assert_eq!(refresh_at(1_000, 400), 1_000 + 200);
+        assert_eq!(refresh_at(1_000, 120), 1_000 + 60);
+        assert_eq!(refresh_at(1_000, 1), 1_000 + 1);
+
+        assert_eq!(refresh_at(1_000, u64::MAX), i64::MAX - 300);
+    }
+
+    #[test]
+    fn test_validate_token_response_empty_token() {
+        let response = TokenResponse {
+            access_token: String::new(),
+            ..token_response(3600)
+        };
+        assert!(validate_token_response(&response)
+            .unwrap_err()
+            .to_string()
+            .contains("access_token is empty"));
+    }
+
+    #[test]
+    fn test_scope_mismatch_warning() {
+        let warning =
+            scope_mismatch_warning("ntpStatistic.read_all_public", "ntpStatistic.read_own")
+                .unwrap();
+
+        assert_eq!(
+            warning,
+            "oauth2: token endpoint granted scope 'ntpStatistic.read_own' but \
+             'ntpStatistic.read_all_public' was requested; data endpoints may answer 403"
+        );
+    }
+
+    #[test]
+    fn test_scope_mismatch_warning_matching() {
+        // Same scopes in another order, or no scope reported: no warning
+        assert_eq!(scope_mismatch_warning("a b", "b a"), None);
+        assert_eq!(
+            scope_mismatch_warning("ntpStatistic.read_all_public", ""),
+            None
+        );
+
+        // Missing one of several requested scopes warns
+        assert!(scope_mismatch_warning("a b", "a").is_some());
+    }
+
     #[test]
     fn test_token_response_invalid_json() {
         let json = r#"{"access_token": "missing_expires_in"}"#;
@@ -414,4 +673,81 @@ mod tests {
         let result: Result<TokenResponse, _> = serde_json::from_str(json);
         assert!(result.is_err(), "Should fail on missing expires_in");
     }
+
+    #[test]
+    fn test_refresh_token_fetches_new_token() {
+        let manager = OAuth2Manager::new(OAuth2Config {
+            token_url: "https://example.com/token".to_string(),
+            client_id: "test client".to_string(),
+            client_secret: "test_secret".to_string(),
+            scope: "test_scope".to_string(),
+        });
+        *manager.cached_token.lock().unwrap() = Some(CachedToken {
+            access_token: "old".to_string(),
+            expires_at: i64::MAX,
+            refresh_at: i64::MAX,
+        });
+
+        let mut posted = Vec::new();
+        let (token, expires_at) = manager
+            .refresh_token(
+                |url, body| {
+                    posted.push((url.to_string(), body.to_string()));
+                    Ok((
+                        200,
+                        r#"{"access_token": "new", "expires_in": 3600, "scope": "test_scope"}"#
+                            .to_string(),
+                    ))
+                },
+                1_749_988_800,
+                |msg| panic!("unexpected report: {}", msg),
+            )
+            .unwrap();
+
+        // The still-valid cached token is replaced, not reused
+        assert_eq!(token, "new");
+        assert_eq!(expires_at, 1_749_988_800 + 3600);
+        assert_eq!(
+            posted,
+            vec![(
+                "https://example.com/token".to_string(),
+                "grant_type=client_credentials&client_id=test+client&client_secret=test_secret&scope=test_scope"
+                    .to_string()
+            )]
+        );
+        assert_eq!(
+            manager.token_stats(),
+            TokenStats {
+                cache_hits: 0,
+                cache_misses: 1,
+                tokens_fetched: 1,
+            }
+        );
+        assert_eq!(
+            manager
+                .cached_token
+                .lock()
+                .unwrap()
+                .as_ref()
+                .unwrap()
+                .access_token,
+            "new"
+        );
+    }
+
+    #[test]
+    fn test_refresh_token_failure_leaves_cache_empty() {
+        let manager = OAuth2Manager::new(OAuth2Config {
+            token_url: "https://example.com/token".to_string(),
+            client_id: "test_client".to_string(),
+            client_secret: "wrong".to_string(),
+            scope: "test_scope".to_string(),
+        });
+
+        let result = manager.refresh_token(|_, _| Ok((401, String::new())), 1_749_988_800, |_| {});
+
+        assert!(matches!(result, Err(OAuth2Error::InvalidCredentials)));
+        assert!(manager.cached_token.lock().unwrap().is_none());
+        assert_eq!(manager.token_stats().tokens_fetched, 0);
+    }
 }
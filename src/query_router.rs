@@ -13,6 +13,12 @@
 //! - `price_type` → API endpoint (Spotmarktpreise, NegativePreise, marktpraemie, Jahresmarktpraemie)
 //! - `timestamp_utc` → ISO 8601 date range
 //!
+//! ## Date Ranges
+//! The date range is never split: each endpoint/product gets exactly one plan
+//! covering the whole `[date_from, date_to)` window. Plans for the same
+//! endpoint therefore never overlap, and a row on a range boundary cannot be
+//! fetched twice. Any future date chunking must keep chunk ranges disjoint.
+//!
 //! # Example
 //!
 //! ```rust
@@ -21,6 +27,7 @@
 //! // Route a solar extrapolation query
 //! let filters = QualFilters {
 //!     product_type: Some("solar".to_string()),
+//!     product_types: None,
 //!     data_category: Some("extrapolation".to_string()),
 //!     price_type: None,
 //!     timestamp_range: Some(DateRange {
@@ -28,6 +35,7 @@
 //!         end: "2024-10-25".to_string(),
 //!     }),
 //!     timestamp_bounds: None,
+//!     interval_end_bounds: None,
 //!     table_name: "renewable_energy_timeseries".to_string(),
 //! };
 //!
@@ -36,7 +44,9 @@
 //! ```
 
 use crate::error::{ApiError, NtpFdwError};
-use chrono::NaiveDate;
+use chrono::{Datelike, NaiveDate};
+use std::collections::BTreeMap;
+use std::fmt;
 
 // ============================================================================
 // Data Structures
@@ -67,6 +77,60 @@ pub struct QueryPlan {
     pub api_url: String,
 }
 
+impl QueryPlan {
+    /// Concise one-line description for logs and EXPLAIN-style output
+    ///
+    /// Same string as the `Display` impl.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use supabase_fdw_ntp::query_router::QueryPlan;
+    /// let plan = QueryPlan {
+    ///     endpoint: "prognose".to_string(),
+    ///     product: Some("Solar".to_string()),
+    ///     date_from: "2024-10-24".to_string(),
+    ///     date_to: "2024-10-25".to_string(),
+    ///     api_url: "https://api.example.com/prognose/Solar/2024-10-24/2024-10-25".to_string(),
+    /// };
+    /// assert_eq!(plan.describe(), "GET prognose/Solar [2024-10-24..2024-10-25]");
+    /// ```
+    pub fn describe(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for QueryPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.product {
+            Some(product) => write!(
+                f,
+                "GET {}/{} [{}..{}]",
+                self.endpoint, product, self.date_from, self.date_to
+            ),
+            None => write!(
+                f,
+                "GET {} [{}..{}]",
+                self.endpoint, self.date_from, self.date_to
+            ),
+        }
+    }
+}
+
+/// Server-option driven routing configuration
+///
+/// Defaults reproduce the plain per-product fan-out of [`route_query`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RouterConfig {
+    /// Product path segment of a combined "all products" renewable endpoint
+    ///
+    /// From server option `combined_renewable_endpoint` (e.g. "Alle" →
+    /// `hochrechnung/Alle/{from}/{to}`). When set, a renewable query with no
+    /// `product_type` and a single `data_category` issues one call to this
+    /// endpoint instead of one call per product.
+    pub combined_renewable_endpoint: Option<String>,
+}
+
 /// Extracted filters from SQL WHERE clause
 ///
 /// Parsed from Supabase FDW Context quals
@@ -77,6 +141,13 @@ pub struct QualFilters {
     /// From SQL: `WHERE product_type = 'solar'`
     pub product_type: Option<String>,
 
+    /// Product list filter, validated and deduplicated
+    ///
+    /// From SQL: `WHERE product_type = ANY(ARRAY['solar', 'wind_onshore'])` or
+    /// `WHERE product_type IN ('solar', 'wind_onshore')`. Takes precedence over
+    /// `product_type` when routing.
+    pub product_types: Option<Vec<String>>,
+
     /// Data category filter: "extrapolation", "online_actual"
     ///
     /// From SQL: `WHERE data_category = 'extrapolation'`
@@ -102,6 +173,14 @@ pub struct QualFilters {
     /// Solves bug where time components were stripped during qual parsing.
     pub timestamp_bounds: Option<TimestampBounds>,
 
+    /// Interval end bounds (full precision, for local filtering)
+    ///
+    /// From SQL: `WHERE interval_end_utc >= '2024-10-20T10:00:00'`
+    ///
+    /// Applied to each row's `interval_end_utc`; the routing window is derived
+    /// from these bounds together with `timestamp_range`.
+    pub interval_end_bounds: Option<TimestampBounds>,
+
     /// Table name: "renewable_energy_timeseries" or "electricity_market_prices"
     ///
     /// From Context.table
@@ -133,7 +212,7 @@ pub struct DateRange {
 ///
 /// - API routing: Use `DateRange` (date-only) to determine which dates to fetch
 /// - Local filtering: Use `TimestampBounds` (full timestamps) to filter fetched rows
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TimestampBounds {
     /// Lower bound timestamp in microseconds since epoch
     ///
@@ -385,6 +464,8 @@ pub fn extract_date_range(timestamp_range: Option<&DateRange>) -> DateRange {
 /// Route query to appropriate API endpoints
 ///
 /// Main entry point for query routing. Dispatches to table-specific routing functions.
+/// The `scan_metrics`, `scan_warnings` and `refresh_token` tables are answered
+/// by the FDW instance and route to no endpoint.
 ///
 /// # Arguments
 ///
@@ -402,6 +483,7 @@ pub fn extract_date_range(timestamp_range: Option<&DateRange>) -> DateRange {
 /// # use supabase_fdw_ntp::query_router::*;
 /// let filters = QualFilters {
 ///     product_type: Some("solar".to_string()),
+///     product_types: None,
 ///     data_category: Some("forecast".to_string()),
 ///     price_type: None,
 ///     timestamp_range: Some(DateRange {
@@ -409,6 +491,7 @@ pub fn extract_date_range(timestamp_range: Option<&DateRange>) -> DateRange {
 ///         end: "2024-10-25".to_string(),
 ///     }),
 ///     timestamp_bounds: None,
+///     interval_end_bounds: None,
 ///     table_name: "renewable_energy_timeseries".to_string(),
 /// };
 ///
@@ -416,13 +499,37 @@ pub fn extract_date_range(timestamp_range: Option<&DateRange>) -> DateRange {
 /// assert_eq!(plans.len(), 1); // Single optimized query
 /// ```
 pub fn route_query(filters: &QualFilters, base_url: &str) -> Result<Vec<QueryPlan>, NtpFdwError> {
+    route_query_with_config(filters, base_url, &RouterConfig::default())
+}
+
+/// Route query to API endpoints using server-option routing configuration
+///
+/// Same as [`route_query`], with renewable routing honoring `config`.
+pub fn route_query_with_config(
+    filters: &QualFilters,
+    base_url: &str,
+    config: &RouterConfig,
+) -> Result<Vec<QueryPlan>, NtpFdwError> {
     match filters.table_name.as_str() {
-        "renewable_energy_timeseries" => route_renewable(filters, base_url),
+        "renewable_energy_timeseries" => route_renewable_with_config(filters, base_url, config),
         "electricity_market_prices" => route_prices(filters, base_url),
+        #[cfg(feature = "grid")]
         "redispatch_events" => route_redispatch(filters, base_url),
+        #[cfg(feature = "grid")]
         "grid_status_timeseries" => route_grid_status(filters, base_url),
+        // Session counters are read from the FDW instance, never from the API
+        crate::metrics::TABLE_NAME => Ok(Vec::new()),
+        // Warnings of the last scan are kept on the FDW instance
+        crate::scan_warnings::TABLE_NAME => Ok(Vec::new()),
+        // Token refresh only talks to the token endpoint
+        crate::token_refresh::TABLE_NAME => Ok(Vec::new()),
+        #[cfg(not(feature = "grid"))]
+        "redispatch_events" | "grid_status_timeseries" => Err(NtpFdwError::Generic(format!(
+            "Table {} unavailable: grid tables not enabled in this build (Cargo feature 'grid')",
+            filters.table_name
+        ))),
         _ => Err(NtpFdwError::Generic(format!(
-            "Unknown table: {}. Expected one of: renewable_energy_timeseries, electricity_market_prices, redispatch_events, grid_status_timeseries.",
+            "Unknown table: {}. Expected one of: renewable_energy_timeseries, electricity_market_prices, redispatch_events, grid_status_timeseries, scan_metrics, scan_warnings, refresh_token.",
             filters.table_name
         ))),
     }
@@ -440,8 +547,17 @@ pub fn route_query(filters: &QualFilters, base_url: &str) -> Result<Vec<QueryPla
 /// | solar | (none) | prognose/Solar, hochrechnung/Solar, onlinehochrechnung/Solar | 3 |
 /// | wind_onshore | forecast | prognose/Wind | 1 |
 /// | wind_onshore | online_actual | onlinehochrechnung/Windonshore | 1 |
+/// | ANY('solar', 'wind_onshore') | online_actual | onlinehochrechnung/Solar, onlinehochrechnung/Windonshore | 2 |
+/// | wind | (none) | hochrechnung/Wind, onlinehochrechnung/Windonshore, onlinehochrechnung/Windoffshore | 3 |
 /// | (none) | (none) | ALL 9 endpoints | 9 |
 ///
+/// # Historical Range
+///
+/// `forecast` (prognose) was removed in v0.3.0 and is rejected by
+/// [`map_category_to_endpoint`] before any plan is built, so old-dated forecast
+/// queries never cost a round-trip. `extrapolation` and `online_actual` have
+/// history and are not date-restricted.
+///
 /// # Arguments
 ///
 /// * `filters` - Query filters
@@ -451,6 +567,13 @@ pub fn route_query(filters: &QualFilters, base_url: &str) -> Result<Vec<QueryPla
 ///
 /// List of query plans (1-9 endpoints)
 ///
+/// # Errors
+///
+/// Besides unknown values, fails when the filters leave no endpoint at all
+/// (`product_type = 'wind_offshore' AND data_category = 'extrapolation'`):
+/// the error explains the missing combination instead of the scan silently
+/// returning zero rows.
+///
 /// # Examples
 ///
 /// ```
@@ -458,6 +581,7 @@ pub fn route_query(filters: &QualFilters, base_url: &str) -> Result<Vec<QueryPla
 /// // Optimal query (1 endpoint)
 /// let filters = QualFilters {
 ///     product_type: Some("solar".to_string()),
+///     product_types: None,
 ///     data_category: Some("forecast".to_string()),
 ///     price_type: None,
 ///     timestamp_range: None,
@@ -471,6 +595,21 @@ pub fn route_query(filters: &QualFilters, base_url: &str) -> Result<Vec<QueryPla
 pub fn route_renewable(
     filters: &QualFilters,
     base_url: &str,
+) -> Result<Vec<QueryPlan>, NtpFdwError> {
+    route_renewable_with_config(filters, base_url, &RouterConfig::default())
+}
+
+/// Route renewable energy queries using server-option routing configuration
+///
+/// When `config.combined_renewable_endpoint` is set and the query asks for all
+/// products of a single data category, emits one plan with `product: None`
+/// pointing at the combined endpoint. The combined CSV is demultiplexed by
+/// `csv_parser::parse_combined_renewable_csv`. Otherwise falls back to the
+/// per-product fan-out of [`route_renewable`].
+pub fn route_renewable_with_config(
+    filters: &QualFilters,
+    base_url: &str,
+    config: &RouterConfig,
 ) -> Result<Vec<QueryPlan>, NtpFdwError> {
     // Extract date range (default: last 7 days)
     let date_range = extract_date_range(filters.timestamp_range.as_ref());
@@ -478,11 +617,40 @@ pub fn route_renewable(
     // Validate date range
     validate_date_range(&date_range.start, &date_range.end)?;
 
-    // Determine products to query
-    let products = match &filters.product_type {
-        Some(product_type) => vec![product_type.as_str()],
-        None => vec!["solar", "wind_onshore", "wind_offshore"],
+    // Single call to the combined endpoint (all products, one category)
+    if let (None, None, Some(category), Some(combined)) = (
+        &filters.product_type,
+        &filters.product_types,
+        &filters.data_category,
+        &config.combined_renewable_endpoint,
+    ) {
+        let api_endpoint = map_category_to_endpoint(category)?;
+        let api_url = build_api_url(
+            base_url,
+            api_endpoint,
+            Some(combined),
+            &date_range.start,
+            &date_range.end,
+        );
+
+        return Ok(vec![QueryPlan {
+            endpoint: api_endpoint.to_string(),
+            product: None,
+            date_from: date_range.start,
+            date_to: date_range.end,
+            api_url,
+        }]);
+    }
+
+    // Determine products to query (an ANY/IN list takes precedence)
+    let products: Vec<&str> = match (&filters.product_types, &filters.product_type) {
+        (Some(product_types), _) => product_types.iter().map(String::as_str).collect(),
+        (None, Some(product_type)) => vec![product_type.as_str()],
+        (None, None) => vec!["solar", "wind_onshore", "wind_offshore"],
     };
+    for product_type in &products {
+        check_product_type_alias(product_type)?;
+    }
 
     // Determine data categories to query
     let categories = match &filters.data_category {
@@ -493,14 +661,14 @@ pub fn route_renewable(
     let mut plans = Vec::new();
 
     // Generate query plans (Cartesian product of products × categories)
-    for product_type in products {
+    for product_type in &products {
         for category in &categories {
+            // Map data_category to API endpoint (unknown categories are named first)
+            let api_endpoint = map_category_to_endpoint(category)?;
+
             // Map product_type to API product name
             let api_products = map_product_to_api(product_type, category)?;
 
-            // Map data_category to API endpoint
-            let api_endpoint = map_category_to_endpoint(category)?;
-
             for api_product in api_products {
                 let api_url = build_api_url(
                     base_url,
@@ -521,9 +689,77 @@ pub fn route_renewable(
         }
     }
 
+    // Only filters can rule out every endpoint (e.g. wind_offshore has no
+    // extrapolation); explain instead of silently returning no rows.
+    // An empty ANY/IN list matches nothing anyway and keeps zero plans.
+    if plans.is_empty() && !products.is_empty() {
+        let quoted = |values: &[&str]| {
+            values
+                .iter()
+                .map(|value| format!("'{}'", value))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        return Err(NtpFdwError::Generic(format!(
+            "No API endpoint publishes product_type {} as data_category {}. \
+             wind_offshore is only available as 'online_actual'.",
+            quoted(&products),
+            quoted(&categories)
+        )));
+    }
+
     Ok(plans)
 }
 
+/// `product_type` value users write for "all wind", rejected by routing
+///
+/// Rows are either `wind_onshore` or `wind_offshore`, so no row would match it.
+const AMBIGUOUS_WIND_PRODUCT_TYPE: &str = "wind";
+
+/// Reject a `product_type` filter value spelled as a German API product name
+///
+/// The API product names `'Solar'`, `'Windonshore'` and `'Windoffshore'` (in
+/// any case) fail with an error naming the normalized value to use instead.
+/// `'wind'` (in any case, `'Wind'` being the onshore extrapolation product)
+/// fails with an error pointing to both wind products. Normalized and unknown
+/// values pass, so routing names unknown ones in its error.
+///
+/// # Examples
+///
+/// ```
+/// # use supabase_fdw_ntp::query_router::check_product_type_alias;
+/// assert!(check_product_type_alias("solar").is_ok());
+/// let err = check_product_type_alias("Windoffshore").unwrap_err();
+/// assert!(err.to_string().contains("use product_type = 'wind_offshore'"));
+/// assert!(check_product_type_alias("Wind").is_err());
+/// ```
+pub fn check_product_type_alias(product_type: &str) -> Result<(), NtpFdwError> {
+    const ALIASES: [(&str, &str); 3] = [
+        ("Solar", "solar"),
+        ("Windonshore", "wind_onshore"),
+        ("Windoffshore", "wind_offshore"),
+    ];
+
+    // 'wind' is no product type; the rows carry wind_onshore/wind_offshore
+    if product_type.eq_ignore_ascii_case(AMBIGUOUS_WIND_PRODUCT_TYPE) {
+        return Err(NtpFdwError::Generic(format!(
+            "product_type '{}' is ambiguous: use product_type IN ('wind_onshore', 'wind_offshore') \
+             for both wind products",
+            product_type
+        )));
+    }
+
+    match ALIASES.iter().find(|(alias, normalized)| {
+        alias.eq_ignore_ascii_case(product_type) && product_type != *normalized
+    }) {
+        Some((_, normalized)) => Err(NtpFdwError::Generic(format!(
+            "product_type '{}' is an API product name: use product_type = '{}'",
+            product_type, normalized
+        ))),
+        None => Ok(()),
+    }
+}
+
 /// Map database product type to API product name(s)
 ///
 /// Handles special case for wind products.
@@ -621,6 +857,15 @@ pub fn map_category_to_endpoint(category: &str) -> Result<&'static str, NtpFdwEr
 /// | negative_flag | NegativePreise | 1 |
 /// | (none) | ALL 4 endpoints | 4 |
 ///
+/// # Annual Prices Without a Date Filter
+///
+/// Jahresmarktpraemie takes a single year. Without a timestamp filter the
+/// default window gets one whole-year plan per calendar year it touches (see
+/// [`annual_plans`]), so a window straddling New Year fetches both years
+/// instead of only the first. Annual plans are deduplicated by year and come
+/// last, in ascending year order (see [`dedupe_annual_plans`]), so each year's
+/// annual rows are buffered once.
+///
 /// # Arguments
 ///
 /// * `filters` - Query filters
@@ -636,6 +881,7 @@ pub fn map_category_to_endpoint(category: &str) -> Result<&'static str, NtpFdwEr
 /// # use supabase_fdw_ntp::query_router::*;
 /// let filters = QualFilters {
 ///     product_type: None,
+///     product_types: None,
 ///     data_category: None,
 ///     price_type: Some("spot_market".to_string()),
 ///     timestamp_range: None,
@@ -654,7 +900,10 @@ pub fn route_prices(filters: &QualFilters, base_url: &str) -> Result<Vec<QueryPl
 
     // Determine price endpoints to query
     let endpoints = match &filters.price_type {
-        Some(price_type) => vec![map_price_type_to_endpoint(price_type)?],
+        Some(price_type) => {
+            check_price_type_alias(price_type)?;
+            vec![map_price_type_to_endpoint(price_type)?]
+        }
         None => vec![
             "Spotmarktpreise",
             "NegativePreise",
@@ -666,6 +915,11 @@ pub fn route_prices(filters: &QualFilters, base_url: &str) -> Result<Vec<QueryPl
     let mut plans = Vec::new();
 
     for endpoint in endpoints {
+        if endpoint == "Jahresmarktpraemie" && filters.timestamp_range.is_none() {
+            plans.extend(annual_plans(base_url, &date_range)?);
+            continue;
+        }
+
         let api_url = build_api_url(
             base_url,
             endpoint,
@@ -683,9 +937,114 @@ pub fn route_prices(filters: &QualFilters, base_url: &str) -> Result<Vec<QueryPl
         });
     }
 
+    Ok(dedupe_annual_plans(plans))
+}
+
+/// Keep one Jahresmarktpraemie plan per year, in ascending year order
+///
+/// A duplicate year would buffer that year's annual rows twice. The first plan
+/// of each year wins; annual plans move after all other plans, whose order is
+/// kept.
+pub fn dedupe_annual_plans(plans: Vec<QueryPlan>) -> Vec<QueryPlan> {
+    let (annual, mut plans): (Vec<_>, Vec<_>) = plans
+        .into_iter()
+        .partition(|plan| plan.endpoint == "Jahresmarktpraemie");
+
+    let mut by_year: BTreeMap<String, QueryPlan> = BTreeMap::new();
+    for plan in annual {
+        let year = plan.date_from.get(..4).unwrap_or_default().to_string();
+        by_year.entry(year).or_insert(plan);
+    }
+
+    plans.extend(by_year.into_values());
+    plans
+}
+
+/// One whole-year Jahresmarktpraemie plan per calendar year a window touches
+///
+/// `range.end` is exclusive, so a window ending on January 1st does not touch
+/// that year. Each plan covers `YYYY-01-01..(YYYY+1)-01-01`.
+///
+/// # Examples
+///
+/// ```
+/// # use supabase_fdw_ntp::query_router::*;
+/// let window = DateRange {
+///     start: "2024-12-28".to_string(),
+///     end: "2025-01-04".to_string(),
+/// };
+/// let plans = annual_plans("https://api.example.com", &window).unwrap();
+/// assert_eq!(plans.len(), 2);
+/// assert_eq!(plans[1].api_url, "https://api.example.com/Jahresmarktpraemie/2025");
+/// ```
+pub fn annual_plans(base_url: &str, range: &DateRange) -> Result<Vec<QueryPlan>, NtpFdwError> {
+    let parse = |date: &str| {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| {
+            NtpFdwError::Generic(format!(
+                "Invalid date '{}' in annual price window. Expected YYYY-MM-DD.",
+                date
+            ))
+        })
+    };
+    let from = parse(&range.start)?;
+    let to = parse(&range.end)?;
+
+    // Last day inside the window (a same-day window still covers its start day)
+    let last_day = to.pred_opt().filter(|day| *day >= from).unwrap_or(from);
+
+    let plans = (from.year()..=last_day.year())
+        .map(|year| {
+            let date_from = format!("{:04}-01-01", year);
+            let date_to = format!("{:04}-01-01", year + 1);
+            QueryPlan {
+                endpoint: "Jahresmarktpraemie".to_string(),
+                product: None,
+                api_url: build_api_url(base_url, "Jahresmarktpraemie", None, &date_from, &date_to),
+                date_from,
+                date_to,
+            }
+        })
+        .collect();
+
     Ok(plans)
 }
 
+/// Reject a `price_type` filter value spelled as a German API endpoint name
+///
+/// The names of the endpoints the price types route to (`'Spotmarktpreise'`,
+/// `'marktpraemie'`, `'Jahresmarktpraemie'`, `'NegativePreise'`, in any case)
+/// fail with an error naming the normalized value to use instead. Normalized
+/// and unknown values pass, so [`map_price_type_to_endpoint`] names unknown
+/// ones in its error.
+///
+/// # Examples
+///
+/// ```
+/// # use supabase_fdw_ntp::query_router::check_price_type_alias;
+/// assert!(check_price_type_alias("spot_market").is_ok());
+/// let err = check_price_type_alias("negativepreise").unwrap_err();
+/// assert!(err.to_string().contains("use price_type = 'negative_flag'"));
+/// ```
+pub fn check_price_type_alias(price_type: &str) -> Result<(), NtpFdwError> {
+    const ALIASES: [(&str, &str); 4] = [
+        ("Spotmarktpreise", "spot_market"),
+        ("marktpraemie", "market_premium"),
+        ("Jahresmarktpraemie", "annual_market_value"),
+        ("NegativePreise", "negative_flag"),
+    ];
+
+    match ALIASES
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(price_type))
+    {
+        Some((_, normalized)) => Err(NtpFdwError::Generic(format!(
+            "price_type '{}' is an API endpoint name: use price_type = '{}'",
+            price_type, normalized
+        ))),
+        None => Ok(()),
+    }
+}
+
 /// Map database price type to API endpoint name
 ///
 /// # Arguments
@@ -694,25 +1053,31 @@ pub fn route_prices(filters: &QualFilters, base_url: &str) -> Result<Vec<QueryPl
 ///
 /// # Returns
 ///
-/// API endpoint name
+/// API endpoint name. The day-ahead, intraday and auction series are extra
+/// columns of the spot price export, so `spot_day_ahead`, `spot_intraday` and
+/// `spot_auction` fetch `Spotmarktpreise` like `spot_market`; only the rows
+/// tagged with the requested series are returned.
 ///
 /// # Examples
 ///
 /// ```
 /// # use supabase_fdw_ntp::query_router::map_price_type_to_endpoint;
 /// assert_eq!(map_price_type_to_endpoint("spot_market").unwrap(), "Spotmarktpreise");
+/// assert_eq!(map_price_type_to_endpoint("spot_day_ahead").unwrap(), "Spotmarktpreise");
 /// assert_eq!(map_price_type_to_endpoint("market_premium").unwrap(), "marktpraemie");
 /// assert_eq!(map_price_type_to_endpoint("annual_market_value").unwrap(), "Jahresmarktpraemie");
 /// assert_eq!(map_price_type_to_endpoint("negative_flag").unwrap(), "NegativePreise");
 /// ```
 pub fn map_price_type_to_endpoint(price_type: &str) -> Result<&'static str, NtpFdwError> {
     match price_type {
-        "spot_market" => Ok("Spotmarktpreise"),
+        "spot_market" | "spot_day_ahead" | "spot_intraday" | "spot_auction" => {
+            Ok("Spotmarktpreise")
+        }
         "market_premium" => Ok("marktpraemie"),
         "annual_market_value" => Ok("Jahresmarktpraemie"),
         "negative_flag" => Ok("NegativePreise"),
         _ => Err(NtpFdwError::Generic(format!(
-            "Unknown price type: '{}'. Expected 'spot_market', 'market_premium', 'annual_market_value', or 'negative_flag'.",
+            "Unknown price type: '{}'. Expected 'spot_market', 'spot_day_ahead', 'spot_intraday', 'spot_auction', 'market_premium', 'annual_market_value', or 'negative_flag'.",
             price_type
         ))),
     }
@@ -737,6 +1102,7 @@ pub fn map_price_type_to_endpoint(price_type: &str) -> Result<&'static str, NtpF
 /// # use supabase_fdw_ntp::query_router::*;
 /// let filters = QualFilters {
 ///     product_type: None,
+///     product_types: None,
 ///     data_category: None,
 ///     price_type: None,
 ///     timestamp_range: Some(DateRange {
@@ -797,6 +1163,7 @@ pub fn route_grid_status(
 /// # use supabase_fdw_ntp::query_router::*;
 /// let filters = QualFilters {
 ///     product_type: None,
+///     product_types: None,
 ///     data_category: None,
 ///     price_type: None,
 ///     timestamp_range: Some(DateRange {
@@ -977,6 +1344,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_query_plan_display_standard() {
+        let plan = QueryPlan {
+            endpoint: "prognose".to_string(),
+            product: Some("Solar".to_string()),
+            date_from: "2024-10-24".to_string(),
+            date_to: "2024-10-25".to_string(),
+            api_url: "https://api.example.com/prognose/Solar/2024-10-24/2024-10-25".to_string(),
+        };
+
+        assert_eq!(
+            plan.to_string(),
+            "GET prognose/Solar [2024-10-24..2024-10-25]"
+        );
+        assert_eq!(plan.describe(), plan.to_string());
+    }
+
+    #[test]
+    fn test_query_plan_display_annual() {
+        let plan = QueryPlan {
+            endpoint: "Jahresmarktpraemie".to_string(),
+            product: None,
+            date_from: "2024-01-01".to_string(),
+            date_to: "2025-01-01".to_string(),
+            api_url: "https://api.example.com/Jahresmarktpraemie/2024".to_string(),
+        };
+
+        assert_eq!(
+            plan.describe(),
+            "GET Jahresmarktpraemie [2024-01-01..2025-01-01]"
+        );
+    }
+
+    #[test]
+    fn test_query_plan_display_monthly() {
+        let plan = QueryPlan {
+            endpoint: "marktpraemie".to_string(),
+            product: None,
+            date_from: "2024-10-01".to_string(),
+            date_to: "2024-12-31".to_string(),
+            api_url: "https://api.example.com/marktpraemie/10/2024/12/2024".to_string(),
+        };
+
+        assert_eq!(plan.describe(), "GET marktpraemie [2024-10-01..2024-12-31]");
+    }
+
     #[test]
     fn test_validate_date_range_valid() {
         assert!(validate_date_range("2024-10-24", "2024-10-25").is_ok());
@@ -1086,6 +1499,18 @@ mod tests {
             map_price_type_to_endpoint("spot_market").unwrap(),
             "Spotmarktpreise"
         );
+        assert_eq!(
+            map_price_type_to_endpoint("spot_day_ahead").unwrap(),
+            "Spotmarktpreise"
+        );
+        assert_eq!(
+            map_price_type_to_endpoint("spot_intraday").unwrap(),
+            "Spotmarktpreise"
+        );
+        assert_eq!(
+            map_price_type_to_endpoint("spot_auction").unwrap(),
+            "Spotmarktpreise"
+        );
         assert_eq!(
             map_price_type_to_endpoint("market_premium").unwrap(),
             "marktpraemie"
@@ -1114,6 +1539,7 @@ mod tests {
         // Optimal query: 1 endpoint
         let filters = QualFilters {
             product_type: Some("solar".to_string()),
+            product_types: None,
             data_category: Some("extrapolation".to_string()),
             price_type: None,
             timestamp_range: Some(DateRange {
@@ -1121,6 +1547,7 @@ mod tests {
                 end: "2024-10-25".to_string(),
             }),
             timestamp_bounds: None,
+            interval_end_bounds: None,
             table_name: "renewable_energy_timeseries".to_string(),
         };
 
@@ -1137,11 +1564,97 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_route_renewable_rejects_german_product_alias() {
+        let filters = QualFilters {
+            product_type: Some("solar".to_string()),
+            product_types: None,
+            data_category: Some("extrapolation".to_string()),
+            price_type: None,
+            timestamp_range: Some(DateRange {
+                start: "2024-10-24".to_string(),
+                end: "2024-10-25".to_string(),
+            }),
+            timestamp_bounds: None,
+            interval_end_bounds: None,
+            table_name: "renewable_energy_timeseries".to_string(),
+        };
+
+        let plans = route_renewable(&filters, "https://api.example.com").unwrap();
+
+        assert_eq!(plans.len(), 1);
+        assert_eq!(
+            plans[0].api_url,
+            "https://api.example.com/hochrechnung/Solar/2024-10-24/2024-10-25"
+        );
+
+        // API product names fail, naming the value to use
+        for (alias, normalized) in [
+            ("Solar", "solar"),
+            ("SOLAR", "solar"),
+            ("Windonshore", "wind_onshore"),
+            ("windoffshore", "wind_offshore"),
+        ] {
+            let filters = QualFilters {
+                product_type: Some(alias.to_string()),
+                ..filters.clone()
+            };
+            let err = route_renewable(&filters, "https://api.example.com").unwrap_err();
+            assert!(err
+                .to_string()
+                .contains(&format!("use product_type = '{}'", normalized)));
+        }
+    }
+
+    #[test]
+    fn test_route_renewable_no_endpoint_for_combination() {
+        // Offshore has no extrapolation (hochrechnung) endpoint
+        let filters = QualFilters {
+            product_type: Some("wind_offshore".to_string()),
+            product_types: None,
+            data_category: Some("extrapolation".to_string()),
+            price_type: None,
+            timestamp_range: None,
+            timestamp_bounds: None,
+            interval_end_bounds: None,
+            table_name: "renewable_energy_timeseries".to_string(),
+        };
+
+        let err = route_renewable(&filters, "https://api.example.com").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No API endpoint publishes product_type 'wind_offshore' as data_category 'extrapolation'. \
+             wind_offshore is only available as 'online_actual'."
+        );
+
+        // Removed forecast category is named as such, not as an unknown product
+        let filters = QualFilters {
+            data_category: Some("forecast".to_string()),
+            ..filters
+        };
+        let err = route_renewable(&filters, "https://api.example.com").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unknown data category: 'forecast'"));
+
+        // An empty ANY/IN list still routes to zero plans
+        let filters = QualFilters {
+            product_type: None,
+            product_types: Some(Vec::new()),
+            data_category: Some("extrapolation".to_string()),
+            ..filters
+        };
+        assert!(route_renewable(&filters, "https://api.example.com")
+            .unwrap()
+            .is_empty());
+    }
+
     #[test]
     fn test_route_renewable_solar_all_categories() {
         // No data_category filter: 2 endpoints
         let filters = QualFilters {
             product_type: Some("solar".to_string()),
+            product_types: None,
             data_category: None,
             price_type: None,
             timestamp_range: Some(DateRange {
@@ -1149,6 +1662,7 @@ mod tests {
                 end: "2024-10-25".to_string(),
             }),
             timestamp_bounds: None,
+            interval_end_bounds: None,
             table_name: "renewable_energy_timeseries".to_string(),
         };
 
@@ -1164,6 +1678,7 @@ mod tests {
         // Wind onshore with all categories: 2 endpoints
         let filters = QualFilters {
             product_type: Some("wind_onshore".to_string()),
+            product_types: None,
             data_category: None,
             price_type: None,
             timestamp_range: Some(DateRange {
@@ -1171,6 +1686,7 @@ mod tests {
                 end: "2024-10-25".to_string(),
             }),
             timestamp_bounds: None,
+            interval_end_bounds: None,
             table_name: "renewable_energy_timeseries".to_string(),
         };
 
@@ -1188,6 +1704,7 @@ mod tests {
         // Wind offshore only has online_actual: 1 endpoint
         let filters = QualFilters {
             product_type: Some("wind_offshore".to_string()),
+            product_types: None,
             data_category: None,
             price_type: None,
             timestamp_range: Some(DateRange {
@@ -1195,6 +1712,7 @@ mod tests {
                 end: "2024-10-25".to_string(),
             }),
             timestamp_bounds: None,
+            interval_end_bounds: None,
             table_name: "renewable_energy_timeseries".to_string(),
         };
 
@@ -1214,6 +1732,7 @@ mod tests {
         // - Wind offshore: onlinehochrechnung/Windoffshore (1)
         let filters = QualFilters {
             product_type: None,
+            product_types: None,
             data_category: None,
             price_type: None,
             timestamp_range: Some(DateRange {
@@ -1221,6 +1740,7 @@ mod tests {
                 end: "2024-10-25".to_string(),
             }),
             timestamp_bounds: None,
+            interval_end_bounds: None,
             table_name: "renewable_energy_timeseries".to_string(),
         };
 
@@ -1231,15 +1751,158 @@ mod tests {
         assert_eq!(plans.len(), 5);
     }
 
+    #[test]
+    fn test_route_renewable_product_list() {
+        // product_type = ANY(ARRAY['solar', 'wind_offshore']), no category
+        let filters = QualFilters {
+            product_type: None,
+            product_types: Some(vec!["solar".to_string(), "wind_offshore".to_string()]),
+            data_category: None,
+            price_type: None,
+            timestamp_range: Some(DateRange {
+                start: "2024-10-24".to_string(),
+                end: "2024-10-25".to_string(),
+            }),
+            timestamp_bounds: None,
+            interval_end_bounds: None,
+            table_name: "renewable_energy_timeseries".to_string(),
+        };
+
+        // Combined endpoint is not used for an explicit product list
+        let config = RouterConfig {
+            combined_renewable_endpoint: Some("Alle".to_string()),
+        };
+        let plans =
+            route_renewable_with_config(&filters, "https://api.example.com", &config).unwrap();
+
+        // Solar × 2 categories + wind offshore online_actual only
+        let products: Vec<_> = plans.iter().map(|p| p.product.as_deref()).collect();
+        assert_eq!(
+            products,
+            vec![Some("Solar"), Some("Solar"), Some("Windoffshore")]
+        );
+    }
+
+    #[test]
+    fn test_route_renewable_rejects_wind() {
+        let filters =
+            |product_type: Option<&str>, product_types: Option<Vec<String>>| QualFilters {
+                product_type: product_type.map(str::to_string),
+                product_types,
+                data_category: None,
+                price_type: None,
+                timestamp_range: Some(DateRange {
+                    start: "2024-10-24".to_string(),
+                    end: "2024-10-25".to_string(),
+                }),
+                timestamp_bounds: None,
+                interval_end_bounds: None,
+                table_name: "renewable_energy_timeseries".to_string(),
+            };
+
+        // Both = 'wind' and 'wind' in a list point to the two wind products
+        for filters in [
+            filters(Some("wind"), None),
+            filters(None, Some(vec!["solar".to_string(), "wind".to_string()])),
+        ] {
+            let err = route_query(&filters, "https://api.example.com").unwrap_err();
+            assert!(err
+                .to_string()
+                .contains("IN ('wind_onshore', 'wind_offshore')"));
+        }
+    }
+
+    #[test]
+    fn test_route_renewable_combined_endpoint() {
+        let filters = QualFilters {
+            product_type: None,
+            product_types: None,
+            data_category: Some("extrapolation".to_string()),
+            price_type: None,
+            timestamp_range: Some(DateRange {
+                start: "2024-10-24".to_string(),
+                end: "2024-10-25".to_string(),
+            }),
+            timestamp_bounds: None,
+            interval_end_bounds: None,
+            table_name: "renewable_energy_timeseries".to_string(),
+        };
+        let config = RouterConfig {
+            combined_renewable_endpoint: Some("Alle".to_string()),
+        };
+
+        let plans = route_query_with_config(&filters, "https://api.example.com", &config).unwrap();
+
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].endpoint, "hochrechnung");
+        assert_eq!(plans[0].product, None);
+        assert_eq!(
+            plans[0].api_url,
+            "https://api.example.com/hochrechnung/Alle/2024-10-24/2024-10-25"
+        );
+    }
+
+    #[test]
+    fn test_route_renewable_combined_endpoint_fallback() {
+        let config = RouterConfig {
+            combined_renewable_endpoint: Some("Alle".to_string()),
+        };
+
+        // Specific product: regular per-product plan
+        let filters = QualFilters {
+            product_type: Some("solar".to_string()),
+            product_types: None,
+            data_category: Some("extrapolation".to_string()),
+            price_type: None,
+            timestamp_range: Some(DateRange {
+                start: "2024-10-24".to_string(),
+                end: "2024-10-25".to_string(),
+            }),
+            timestamp_bounds: None,
+            interval_end_bounds: None,
+            table_name: "renewable_energy_timeseries".to_string(),
+        };
+        let plans =
+            route_renewable_with_config(&filters, "https://api.example.com", &config).unwrap();
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].product, Some("Solar".to_string()));
+
+        // All categories: fan-out (combined endpoint serves one category)
+        let filters = QualFilters {
+            product_type: None,
+            product_types: None,
+            data_category: None,
+            ..filters
+        };
+        let plans =
+            route_renewable_with_config(&filters, "https://api.example.com", &config).unwrap();
+        assert_eq!(plans.len(), 5);
+
+        // No combined endpoint configured: fan-out
+        let filters = QualFilters {
+            data_category: Some("online_actual".to_string()),
+            ..filters
+        };
+        let plans = route_renewable_with_config(
+            &filters,
+            "https://api.example.com",
+            &RouterConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(plans.len(), 3);
+    }
+
     #[test]
     fn test_route_renewable_default_date_range() {
         // No timestamp_range filter: should default to last 7 days
         let filters = QualFilters {
             product_type: Some("solar".to_string()),
+            product_types: None,
             data_category: Some("extrapolation".to_string()),
             price_type: None,
             timestamp_range: None,
             timestamp_bounds: None,
+            interval_end_bounds: None,
             table_name: "renewable_energy_timeseries".to_string(),
         };
 
@@ -1255,6 +1918,7 @@ mod tests {
     fn test_route_renewable_invalid_date_range() {
         let filters = QualFilters {
             product_type: Some("solar".to_string()),
+            product_types: None,
             data_category: Some("extrapolation".to_string()),
             price_type: None,
             timestamp_range: Some(DateRange {
@@ -1262,12 +1926,68 @@ mod tests {
                 end: "2024-10-24".to_string(), // Invalid: end < start
             }),
             timestamp_bounds: None,
+            interval_end_bounds: None,
             table_name: "renewable_energy_timeseries".to_string(),
         };
 
         assert!(route_renewable(&filters, "https://api.example.com").is_err());
     }
 
+    #[test]
+    fn test_route_renewable_long_forecast_window_rejected() {
+        // 60 days ahead: no far-future forecast fetches to clamp, forecast is rejected
+        let filters = QualFilters {
+            product_type: Some("solar".to_string()),
+            product_types: None,
+            data_category: Some("forecast".to_string()),
+            price_type: None,
+            timestamp_range: Some(DateRange {
+                start: "2024-10-24".to_string(),
+                end: "2024-12-23".to_string(),
+            }),
+            timestamp_bounds: None,
+            interval_end_bounds: None,
+            table_name: "renewable_energy_timeseries".to_string(),
+        };
+
+        let err = route_renewable(&filters, "https://api.example.com").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unknown data category: 'forecast'"));
+    }
+
+    #[test]
+    fn test_route_renewable_old_forecast_rejected() {
+        let filters = QualFilters {
+            product_type: Some("solar".to_string()),
+            product_types: None,
+            data_category: Some("forecast".to_string()),
+            price_type: None,
+            timestamp_range: Some(DateRange {
+                start: "2019-01-01".to_string(),
+                end: "2019-01-02".to_string(),
+            }),
+            timestamp_bounds: None,
+            interval_end_bounds: None,
+            table_name: "renewable_energy_timeseries".to_string(),
+        };
+
+        // Rejected at routing time: no plan, no fetch
+        let err = route_renewable(&filters, "https://api.example.com").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unknown data category: 'forecast'"));
+
+        // Historical extrapolation stays unrestricted
+        let filters = QualFilters {
+            data_category: Some("extrapolation".to_string()),
+            ..filters
+        };
+        let plans = route_renewable(&filters, "https://api.example.com").unwrap();
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].date_from, "2019-01-01");
+    }
+
     // ========================================================================
     // Price Routing Tests
     // ========================================================================
@@ -1276,6 +1996,7 @@ mod tests {
     fn test_route_prices_spot_market() {
         let filters = QualFilters {
             product_type: None,
+            product_types: None,
             data_category: None,
             price_type: Some("spot_market".to_string()),
             timestamp_range: Some(DateRange {
@@ -1283,6 +2004,7 @@ mod tests {
                 end: "2024-10-25".to_string(),
             }),
             timestamp_bounds: None,
+            interval_end_bounds: None,
             table_name: "electricity_market_prices".to_string(),
         };
 
@@ -1297,11 +2019,112 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_route_prices_spot_day_ahead() {
+        let filters = QualFilters {
+            product_type: None,
+            product_types: None,
+            data_category: None,
+            price_type: Some("spot_day_ahead".to_string()),
+            timestamp_range: Some(DateRange {
+                start: "2024-10-24".to_string(),
+                end: "2024-10-25".to_string(),
+            }),
+            timestamp_bounds: None,
+            interval_end_bounds: None,
+            table_name: "electricity_market_prices".to_string(),
+        };
+
+        let plans = route_prices(&filters, "https://api.example.com").unwrap();
+
+        // The series is a column of the spot export: one request, no other feeds
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].endpoint, "Spotmarktpreise");
+        assert_eq!(
+            plans[0].api_url,
+            "https://api.example.com/Spotmarktpreise/2024-10-24/2024-10-25"
+        );
+    }
+
+    #[test]
+    fn test_route_prices_spot_intraday() {
+        let filters = QualFilters {
+            product_type: None,
+            product_types: None,
+            data_category: None,
+            price_type: Some("spot_intraday".to_string()),
+            timestamp_range: Some(DateRange {
+                start: "2024-10-24".to_string(),
+                end: "2024-10-25".to_string(),
+            }),
+            timestamp_bounds: None,
+            interval_end_bounds: None,
+            table_name: "electricity_market_prices".to_string(),
+        };
+
+        let plans = route_prices(&filters, "https://api.example.com").unwrap();
+
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].endpoint, "Spotmarktpreise");
+        assert_eq!(
+            plans[0].api_url,
+            "https://api.example.com/Spotmarktpreise/2024-10-24/2024-10-25"
+        );
+    }
+
+    #[test]
+    fn test_route_prices_rejects_german_alias() {
+        let filters = QualFilters {
+            product_type: None,
+            product_types: None,
+            data_category: None,
+            price_type: Some("spot_market".to_string()),
+            timestamp_range: Some(DateRange {
+                start: "2024-10-24".to_string(),
+                end: "2024-10-25".to_string(),
+            }),
+            timestamp_bounds: None,
+            interval_end_bounds: None,
+            table_name: "electricity_market_prices".to_string(),
+        };
+
+        let plans = route_prices(&filters, "https://api.example.com").unwrap();
+
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].endpoint, "Spotmarktpreise");
+
+        // API endpoint names fail, naming the value to use
+        for (alias, normalized) in [
+            ("Spotmarktpreise", "spot_market"),
+            ("MARKTPRAEMIE", "market_premium"),
+            ("Jahresmarktpraemie", "annual_market_value"),
+            ("NegativePreise", "negative_flag"),
+        ] {
+            let filters = QualFilters {
+                price_type: Some(alias.to_string()),
+                ..filters.clone()
+            };
+            let err = route_prices(&filters, "https://api.example.com").unwrap_err();
+            assert!(err
+                .to_string()
+                .contains(&format!("use price_type = '{}'", normalized)));
+        }
+
+        // Unknown values still fail routing, named in the error
+        let filters = QualFilters {
+            price_type: Some("Strompreise".to_string()),
+            ..filters
+        };
+        let err = route_prices(&filters, "https://api.example.com").unwrap_err();
+        assert!(err.to_string().contains("'Strompreise'"));
+    }
+
     #[test]
     fn test_route_prices_all_types() {
         // No price_type filter: 4 endpoints
         let filters = QualFilters {
             product_type: None,
+            product_types: None,
             data_category: None,
             price_type: None,
             timestamp_range: Some(DateRange {
@@ -1309,6 +2132,7 @@ mod tests {
                 end: "2024-10-25".to_string(),
             }),
             timestamp_bounds: None,
+            interval_end_bounds: None,
             table_name: "electricity_market_prices".to_string(),
         };
 
@@ -1321,15 +2145,178 @@ mod tests {
         assert_eq!(plans[3].endpoint, "Jahresmarktpraemie");
     }
 
+    #[test]
+    fn test_route_plans_never_split_date_range() {
+        // Multi-week range: one plan per endpoint/product covering the whole window
+        let range = DateRange {
+            start: "2024-10-01".to_string(),
+            end: "2024-10-29".to_string(),
+        };
+        let renewable = QualFilters {
+            product_type: None,
+            product_types: None,
+            data_category: None,
+            price_type: None,
+            timestamp_range: Some(range.clone()),
+            timestamp_bounds: None,
+            interval_end_bounds: None,
+            table_name: "renewable_energy_timeseries".to_string(),
+        };
+        let prices = QualFilters {
+            table_name: "electricity_market_prices".to_string(),
+            ..renewable.clone()
+        };
+
+        for filters in [renewable, prices] {
+            let plans = route_query(&filters, "https://api.example.com").unwrap();
+            let mut keys: Vec<_> = plans
+                .iter()
+                .map(|plan| (plan.endpoint.clone(), plan.product.clone()))
+                .collect();
+            keys.sort();
+            keys.dedup();
+
+            assert_eq!(keys.len(), plans.len(), "{}", filters.table_name);
+            for plan in &plans {
+                assert_eq!(plan.date_from, range.start);
+                assert_eq!(plan.date_to, range.end);
+            }
+        }
+    }
+
+    #[test]
+    fn test_annual_plans_year_straddling_window() {
+        let window = DateRange {
+            start: "2024-12-28".to_string(),
+            end: "2025-01-04".to_string(),
+        };
+
+        let plans = annual_plans("https://api.example.com", &window).unwrap();
+
+        assert_eq!(plans.len(), 2);
+        assert_eq!(
+            plans[0].to_string(),
+            "GET Jahresmarktpraemie [2024-01-01..2025-01-01]"
+        );
+        assert_eq!(
+            plans[0].api_url,
+            "https://api.example.com/Jahresmarktpraemie/2024"
+        );
+        assert_eq!(
+            plans[1].to_string(),
+            "GET Jahresmarktpraemie [2025-01-01..2026-01-01]"
+        );
+        assert_eq!(
+            plans[1].api_url,
+            "https://api.example.com/Jahresmarktpraemie/2025"
+        );
+    }
+
+    #[test]
+    fn test_dedupe_annual_plans_one_plan_per_year() {
+        let window = |start: &str, end: &str| DateRange {
+            start: start.to_string(),
+            end: end.to_string(),
+        };
+        let spot = QueryPlan {
+            endpoint: "Spotmarktpreise".to_string(),
+            product: None,
+            date_from: "2024-12-28".to_string(),
+            date_to: "2025-01-04".to_string(),
+            api_url: "https://api.example.com/Spotmarktpreise/2024-12-28/2025-01-04".to_string(),
+        };
+
+        // Overlapping year coverage: 2025, then 2024 and 2025 again
+        let mut plans = annual_plans(
+            "https://api.example.com",
+            &window("2025-01-02", "2025-01-04"),
+        )
+        .unwrap();
+        plans.push(spot.clone());
+        plans.extend(
+            annual_plans(
+                "https://api.example.com",
+                &window("2024-12-28", "2025-01-04"),
+            )
+            .unwrap(),
+        );
+
+        let plans = dedupe_annual_plans(plans);
+
+        let described: Vec<String> = plans.iter().map(|plan| plan.to_string()).collect();
+        assert_eq!(
+            described,
+            vec![
+                spot.to_string(),
+                "GET Jahresmarktpraemie [2024-01-01..2025-01-01]".to_string(),
+                "GET Jahresmarktpraemie [2025-01-01..2026-01-01]".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_annual_plans_single_year() {
+        // End is exclusive: a window ending on January 1st stays in its year
+        for (start, end) in [
+            ("2024-10-18", "2024-10-25"),
+            ("2024-12-25", "2025-01-01"),
+            ("2024-12-31", "2024-12-31"),
+        ] {
+            let window = DateRange {
+                start: start.to_string(),
+                end: end.to_string(),
+            };
+            let plans = annual_plans("https://api.example.com", &window).unwrap();
+
+            assert_eq!(plans.len(), 1, "{}..{}", start, end);
+            assert_eq!(plans[0].date_from, "2024-01-01");
+            assert_eq!(plans[0].date_to, "2025-01-01");
+        }
+    }
+
+    #[test]
+    fn test_route_prices_annual_default_window_whole_year() {
+        let filters = QualFilters {
+            product_type: None,
+            product_types: None,
+            data_category: None,
+            price_type: Some("annual_market_value".to_string()),
+            timestamp_range: None,
+            timestamp_bounds: None,
+            interval_end_bounds: None,
+            table_name: "electricity_market_prices".to_string(),
+        };
+
+        let plans = route_prices(&filters, "https://api.example.com").unwrap();
+
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].date_from, "2024-01-01");
+        assert_eq!(plans[0].date_to, "2025-01-01");
+
+        // An explicit date filter is routed as given
+        let filters = QualFilters {
+            timestamp_range: Some(DateRange {
+                start: "2024-12-28".to_string(),
+                end: "2025-01-04".to_string(),
+            }),
+            ..filters
+        };
+        let plans = route_prices(&filters, "https://api.example.com").unwrap();
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].date_from, "2024-12-28");
+    }
+
     #[test]
     fn test_route_prices_default_date_range() {
         // No timestamp_range filter: should default to last 7 days
         let filters = QualFilters {
             product_type: None,
+            product_types: None,
             data_category: None,
             price_type: Some("spot_market".to_string()),
             timestamp_range: None,
             timestamp_bounds: None,
+            interval_end_bounds: None,
             table_name: "electricity_market_prices".to_string(),
         };
 
@@ -1348,6 +2335,7 @@ mod tests {
     fn test_route_query_renewable() {
         let filters = QualFilters {
             product_type: Some("solar".to_string()),
+            product_types: None,
             data_category: Some("extrapolation".to_string()),
             price_type: None,
             timestamp_range: Some(DateRange {
@@ -1355,6 +2343,7 @@ mod tests {
                 end: "2024-10-25".to_string(),
             }),
             timestamp_bounds: None,
+            interval_end_bounds: None,
             table_name: "renewable_energy_timeseries".to_string(),
         };
 
@@ -1368,6 +2357,7 @@ mod tests {
     fn test_route_query_prices() {
         let filters = QualFilters {
             product_type: None,
+            product_types: None,
             data_category: None,
             price_type: Some("spot_market".to_string()),
             timestamp_range: Some(DateRange {
@@ -1375,6 +2365,7 @@ mod tests {
                 end: "2024-10-25".to_string(),
             }),
             timestamp_bounds: None,
+            interval_end_bounds: None,
             table_name: "electricity_market_prices".to_string(),
         };
 
@@ -1388,10 +2379,12 @@ mod tests {
     fn test_route_query_unknown_table() {
         let filters = QualFilters {
             product_type: None,
+            product_types: None,
             data_category: None,
             price_type: None,
             timestamp_range: None,
             timestamp_bounds: None,
+            interval_end_bounds: None,
             table_name: "unknown_table".to_string(),
         };
 
@@ -1399,9 +2392,28 @@ mod tests {
     }
 
     #[test]
+    fn test_route_query_scan_metrics_no_requests() {
+        let filters = QualFilters {
+            product_type: None,
+            product_types: None,
+            data_category: None,
+            price_type: None,
+            timestamp_range: None,
+            timestamp_bounds: None,
+            interval_end_bounds: None,
+            table_name: "scan_metrics".to_string(),
+        };
+
+        let plans = route_query(&filters, "https://api.example.com").unwrap();
+        assert!(plans.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "grid")]
     fn test_route_query_redispatch() {
         let filters = QualFilters {
             product_type: None,
+            product_types: None,
             data_category: None,
             price_type: None,
             timestamp_range: Some(DateRange {
@@ -1409,6 +2421,7 @@ mod tests {
                 end: "2024-10-24".to_string(),
             }),
             timestamp_bounds: None,
+            interval_end_bounds: None,
             table_name: "redispatch_events".to_string(),
         };
 
@@ -1418,9 +2431,11 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "grid")]
     fn test_route_query_grid_status() {
         let filters = QualFilters {
             product_type: None,
+            product_types: None,
             data_category: None,
             price_type: None,
             timestamp_range: Some(DateRange {
@@ -1428,6 +2443,7 @@ mod tests {
                 end: "2024-10-25".to_string(),
             }),
             timestamp_bounds: None,
+            interval_end_bounds: None,
             table_name: "grid_status_timeseries".to_string(),
         };
 
@@ -1436,6 +2452,26 @@ mod tests {
         assert_eq!(plans[0].endpoint, "TrafficLight");
     }
 
+    #[test]
+    #[cfg(not(feature = "grid"))]
+    fn test_route_query_grid_tables_disabled() {
+        let filters = QualFilters {
+            product_type: None,
+            product_types: None,
+            data_category: None,
+            price_type: None,
+            timestamp_range: None,
+            timestamp_bounds: None,
+            interval_end_bounds: None,
+            table_name: "redispatch_events".to_string(),
+        };
+
+        let err = route_query(&filters, "https://api.example.com").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("grid tables not enabled in this build"));
+    }
+
     // ========================================================================
     // Grid Operations Routing Tests
     // ========================================================================
@@ -1444,6 +2480,7 @@ mod tests {
     fn test_route_redispatch_with_date_range() {
         let filters = QualFilters {
             product_type: None,
+            product_types: None,
             data_category: None,
             price_type: None,
             timestamp_range: Some(DateRange {
@@ -1451,6 +2488,7 @@ mod tests {
                 end: "2024-10-24".to_string(),
             }),
             timestamp_bounds: None,
+            interval_end_bounds: None,
             table_name: "redispatch_events".to_string(),
         };
 
@@ -1471,10 +2509,12 @@ mod tests {
     fn test_route_redispatch_default_date_range() {
         let filters = QualFilters {
             product_type: None,
+            product_types: None,
             data_category: None,
             price_type: None,
             timestamp_range: None,
             timestamp_bounds: None,
+            interval_end_bounds: None,
             table_name: "redispatch_events".to_string(),
         };
 
@@ -1491,6 +2531,7 @@ mod tests {
     fn test_route_grid_status_with_date_range() {
         let filters = QualFilters {
             product_type: None,
+            product_types: None,
             data_category: None,
             price_type: None,
             timestamp_range: Some(DateRange {
@@ -1498,6 +2539,7 @@ mod tests {
                 end: "2024-10-25".to_string(),
             }),
             timestamp_bounds: None,
+            interval_end_bounds: None,
             table_name: "grid_status_timeseries".to_string(),
         };
 
@@ -1518,10 +2560,12 @@ mod tests {
     fn test_route_grid_status_default_date_range() {
         let filters = QualFilters {
             product_type: None,
+            product_types: None,
             data_category: None,
             price_type: None,
             timestamp_range: None,
             timestamp_bounds: None,
+            interval_end_bounds: None,
             table_name: "grid_status_timeseries".to_string(),
         };
 
@@ -1538,6 +2582,7 @@ mod tests {
     fn test_route_redispatch_invalid_date_range() {
         let filters = QualFilters {
             product_type: None,
+            product_types: None,
             data_category: None,
             price_type: None,
             timestamp_range: Some(DateRange {
@@ -1545,6 +2590,7 @@ mod tests {
                 end: "2024-10-24".to_string(), // Invalid: end < start
             }),
             timestamp_bounds: None,
+            interval_end_bounds: None,
             table_name: "redispatch_events".to_string(),
         };
 
@@ -1555,6 +2601,7 @@ mod tests {
     fn test_route_grid_status_invalid_date_range() {
         let filters = QualFilters {
             product_type: None,
+            product_types: None,
             data_category: None,
             price_type: None,
             timestamp_range: Some(DateRange {
@@ -1562,6 +2609,7 @@ mod tests {
                 end: "2024-10-24".to_string(), // Invalid: end < start
             }),
             timestamp_bounds: None,
+            interval_end_bounds: None,
             table_name: "grid_status_timeseries".to_string(),
         };
 
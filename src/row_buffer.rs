@@ -0,0 +1,135 @@
+//! Buffered rows of one table with an iteration position
+//!
+//! `begin_scan` fetches and parses every row up front; `iter_scan` then hands
+//! them to PostgreSQL one at a time. `re_scan` (JOINs, cursors) restarts the
+//! iteration without re-fetching, and `end_scan` drops the rows. `NtpFdw`
+//! holds one `RowBuffer` per table, so these operations are implemented once
+//! here instead of once per table.
+
+/// Rows of one table plus the index of the next row to emit
+#[derive(Debug)]
+pub(crate) struct RowBuffer<T> {
+    rows: Vec<T>,
+    position: usize,
+}
+
+impl<T> Default for RowBuffer<T> {
+    fn default() -> Self {
+        Self {
+            rows: Vec::new(),
+            position: 0,
+        }
+    }
+}
+
+impl<T> From<Vec<T>> for RowBuffer<T> {
+    fn from(rows: Vec<T>) -> Self {
+        Self { rows, position: 0 }
+    }
+}
+
+impl<T> RowBuffer<T> {
+    /// Replace the rows and restart from the first one
+    pub(crate) fn fill(&mut self, rows: Vec<T>) {
+        self.rows = rows;
+        self.position = 0;
+    }
+
+    /// Next row to emit, advancing the position
+    ///
+    /// Bounds-checked: returns `None` once every row has been emitted, and on
+    /// every later call, without moving past the end.
+    pub(crate) fn next(&mut self) -> Option<&T> {
+        let row = self.rows.get(self.position)?;
+        self.position += 1;
+        Some(row)
+    }
+
+    /// Restart from the first row, keeping the rows (`re_scan`)
+    pub(crate) fn reset(&mut self) {
+        self.position = 0;
+    }
+
+    /// Drop the rows and restart (`end_scan`)
+    pub(crate) fn clear(&mut self) {
+        self.rows.clear();
+        self.position = 0;
+    }
+
+    /// Buffered rows, emitted or not
+    #[cfg(test)]
+    pub(crate) fn rows(&self) -> &[T] {
+        &self.rows
+    }
+
+    /// Index of the next row to emit
+    #[cfg(test)]
+    pub(crate) fn position(&self) -> usize {
+        self.position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_emits_rows_in_order() {
+        let mut buffer = RowBuffer::from(vec!["a", "b"]);
+
+        assert_eq!(buffer.next(), Some(&"a"));
+        assert_eq!(buffer.next(), Some(&"b"));
+        assert_eq!(buffer.next(), None);
+
+        // Exhausted buffers stay exhausted without moving past the end
+        assert_eq!(buffer.next(), None);
+        assert_eq!(buffer.position(), 2);
+    }
+
+    #[test]
+    fn test_reset_keeps_rows() {
+        let mut buffer = RowBuffer::from(vec![1, 2, 3]);
+        buffer.next();
+        buffer.next();
+
+        buffer.reset();
+
+        assert_eq!(buffer.position(), 0);
+        assert_eq!(buffer.rows(), &[1, 2, 3]);
+        assert_eq!(buffer.next(), Some(&1));
+    }
+
+    #[test]
+    fn test_clear_drops_rows() {
+        let mut buffer = RowBuffer::from(vec![1, 2, 3]);
+        buffer.next();
+
+        buffer.clear();
+
+        assert_eq!(buffer.position(), 0);
+        assert!(buffer.rows().is_empty());
+        assert_eq!(buffer.next(), None);
+    }
+
+    #[test]
+    fn test_fill_replaces_rows_and_restarts() {
+        let mut buffer = RowBuffer::from(vec![1, 2]);
+        buffer.next();
+        buffer.next();
+
+        buffer.fill(vec![7]);
+
+        assert_eq!(buffer.next(), Some(&7));
+        assert_eq!(buffer.next(), None);
+    }
+
+    #[test]
+    fn test_empty_buffer() {
+        let mut buffer = RowBuffer::<u8>::default();
+
+        assert_eq!(buffer.next(), None);
+        buffer.reset();
+        buffer.clear();
+        assert_eq!(buffer.position(), 0);
+    }
+}
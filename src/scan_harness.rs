@@ -0,0 +1,1992 @@
+//! In-memory scan harness for end-to-end lifecycle tests
+//!
+//! The WIT `Context` and `Row` resources only exist inside the Wasm host, so
+//! `begin_scan → iter_scan → end_scan` cannot be called directly in native
+//! tests. This module provides fakes for the [`ScanHost`] and [`RowSink`]
+//! seams and drives the same `NtpFdw` lifecycle methods the `Guest` impl
+//! calls, with a mock HTTP client standing in for `fetch_with_oauth_retry`.
+//!
+//! Test-only (`cfg(test)`): the crate is built as a `cdylib`, so integration
+//! tests under `tests/` cannot link against it.
+
+use std::cell::RefCell;
+
+use crate::bindings::supabase::wrappers::types::{Cell, TypeOid, Value};
+use crate::query_router::QueryPlan;
+use crate::{NtpFdw, RowSink, ScanHost, ScanQual};
+
+/// Fake scan context: quals, table options and projected columns
+#[derive(Debug, Default)]
+pub(crate) struct FakeContext {
+    quals: Vec<ScanQual>,
+    table_options: Vec<(String, String)>,
+    columns: Vec<String>,
+    column_types: Vec<(String, TypeOid)>,
+    now_secs: i64,
+    log: RefCell<Vec<String>>,
+    token_response: Option<(u16, String)>,
+    posts: RefCell<Vec<(String, String)>>,
+}
+
+impl FakeContext {
+    /// Context for a foreign table declared with `OPTIONS (table '<table>')`
+    pub(crate) fn new(table: &str) -> Self {
+        Self {
+            table_options: vec![("table".to_string(), table.to_string())],
+            now_secs: 1_749_988_800, // 2025-06-15T12:00:00Z
+            ..Self::default()
+        }
+    }
+
+    /// Add a pushed-down qual (`WHERE <field> <operator> <value>`)
+    pub(crate) fn qual(mut self, field: &str, operator: &str, value: Cell) -> Self {
+        self.quals.push(ScanQual {
+            field: field.to_string(),
+            operator: operator.to_string(),
+            value: Value::Cell(value),
+            use_or: false,
+        });
+        self
+    }
+
+    /// Add a pushed-down array qual (`WHERE <field> = ANY(ARRAY[<values>])`)
+    pub(crate) fn qual_any(mut self, field: &str, values: Vec<Cell>) -> Self {
+        self.quals.push(ScanQual {
+            field: field.to_string(),
+            operator: "=".to_string(),
+            value: Value::Array(values),
+            use_or: true,
+        });
+        self
+    }
+
+    /// Add a foreign table option (`OPTIONS (<key> '<value>')`)
+    pub(crate) fn option(mut self, key: &str, value: &str) -> Self {
+        self.table_options
+            .push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Set the projected columns, in target list order
+    pub(crate) fn columns(mut self, columns: &[&str]) -> Self {
+        self.columns = columns.iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    /// Declare the type of a column (`CREATE FOREIGN TABLE (<name> <type>)`)
+    ///
+    /// Only declared columns are reported by `column_types`, so scans of
+    /// untyped columns skip the column type check.
+    pub(crate) fn column_type(mut self, name: &str, type_oid: TypeOid) -> Self {
+        self.column_types.push((name.to_string(), type_oid));
+        self
+    }
+
+    /// Set the host clock (seconds since epoch)
+    pub(crate) fn now(mut self, secs: i64) -> Self {
+        self.now_secs = secs;
+        self
+    }
+
+    /// Answer `post_form` (the OAuth2 token endpoint) with a status and body
+    ///
+    /// Without a response set, `post_form` fails like an unreachable host.
+    pub(crate) fn token_response(mut self, status: u16, body: &str) -> Self {
+        self.token_response = Some((status, body.to_string()));
+        self
+    }
+
+    /// URLs and bodies POSTed through `post_form` so far
+    pub(crate) fn posts(&self) -> Vec<(String, String)> {
+        self.posts.borrow().clone()
+    }
+
+    /// Messages logged through `report_info` so far
+    pub(crate) fn log(&self) -> Vec<String> {
+        self.log.borrow().clone()
+    }
+}
+
+impl ScanHost for FakeContext {
+    fn quals(&self) -> Vec<ScanQual> {
+        self.quals.clone()
+    }
+
+    fn table_option(&self, key: &str) -> Option<String> {
+        self.table_options
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+    }
+
+    fn column_names(&self) -> Vec<String> {
+        self.columns.clone()
+    }
+
+    fn column_types(&self) -> Vec<(String, TypeOid)> {
+        self.column_types.clone()
+    }
+
+    fn now_secs(&self) -> i64 {
+        self.now_secs
+    }
+
+    fn report_info(&self, msg: &str) {
+        self.log.borrow_mut().push(msg.to_string());
+    }
+
+    fn post_form(&self, url: &str, body: &str) -> Result<(u16, String), String> {
+        self.posts
+            .borrow_mut()
+            .push((url.to_string(), body.to_string()));
+        self.token_response
+            .clone()
+            .ok_or_else(|| format!("connection refused: {}", url))
+    }
+}
+
+/// Fake output row collecting pushed cells
+#[derive(Debug, Default)]
+pub(crate) struct FakeRow {
+    cells: RefCell<Vec<Option<Cell>>>,
+}
+
+impl FakeRow {
+    /// Take the cells pushed since the last call
+    pub(crate) fn take(&self) -> Vec<Option<Cell>> {
+        self.cells.take()
+    }
+}
+
+impl RowSink for FakeRow {
+    fn push_cell(&self, cell: Option<&Cell>) {
+        self.cells.borrow_mut().push(cell.cloned());
+    }
+}
+
+/// Result of one harness scan
+#[derive(Debug)]
+pub(crate) struct ScanOutput {
+    /// Cells of each emitted row, in projected column order
+    pub(crate) rows: Vec<Vec<Option<Cell>>>,
+    /// API URLs passed to the mock HTTP client, in dispatch order
+    pub(crate) urls: Vec<String>,
+    /// `accept` header sent with each URL
+    pub(crate) accept_headers: Vec<String>,
+}
+
+/// Run `begin_scan → iter_scan → end_scan` against the fakes
+///
+/// `fetch` is the mock HTTP client: it receives each routed plan and returns
+/// its response body.
+///
+/// # Returns
+///
+/// * `Ok(ScanOutput)` - Emitted rows and fetched API URLs
+/// * `Err(String)` - Any lifecycle error
+pub(crate) fn run_scan<F>(
+    fdw: &mut NtpFdw,
+    ctx: &FakeContext,
+    mut fetch: F,
+) -> Result<ScanOutput, String>
+where
+    F: FnMut(&QueryPlan) -> Result<String, String>,
+{
+    let mut urls = Vec::new();
+    let mut accept_headers = Vec::new();
+
+    // begin_scan
+    let scan = fdw.load_scan(ctx, |plan, accept| {
+        urls.push(plan.api_url.clone());
+        accept_headers.push(accept.to_string());
+        fetch(plan)
+    })?;
+    fdw.store_scan(scan);
+
+    // iter_scan until exhausted
+    let row = FakeRow::default();
+    let mut rows = Vec::new();
+    while fdw.next_row(ctx, &row)?.is_some() {
+        rows.push(row.take());
+    }
+
+    // end_scan
+    fdw.clear_rows();
+
+    Ok(ScanOutput {
+        rows,
+        urls,
+        accept_headers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catch_panic;
+    use crate::circuit_breaker::CircuitBreaker;
+    use crate::column_transforms::ColumnTransforms;
+    use crate::oauth2::{OAuth2Config, OAuth2Manager};
+
+    /// 2024-10-24T00:00:00Z in microseconds since epoch
+    const OCT_24_MICROS: i64 = 1_729_728_000_000_000;
+    /// 2024-10-25T00:00:00Z in microseconds since epoch
+    const OCT_25_MICROS: i64 = 1_729_814_400_000_000;
+
+    fn test_fdw() -> NtpFdw {
+        NtpFdw {
+            api_base_url: "https://ds.netztransparenz.de/api/v1/data".to_string(),
+            ..NtpFdw::default()
+        }
+    }
+
+    fn render(cells: &[Option<Cell>]) -> Vec<String> {
+        cells
+            .iter()
+            .map(|cell| match cell {
+                Some(cell) => format!("{:?}", cell),
+                None => "NULL".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_solar_extrapolation_scan_end_to_end() {
+        let ctx = FakeContext::new("renewable_energy_timeseries")
+            .qual("product_type", "=", Cell::String("solar".to_string()))
+            .qual(
+                "data_category",
+                "=",
+                Cell::String("extrapolation".to_string()),
+            )
+            // 06:00 lower bound: a time component, so rows are filtered locally
+            .qual(
+                "timestamp_utc",
+                ">=",
+                Cell::Timestamptz(OCT_24_MICROS + 6 * 3_600_000_000),
+            )
+            .qual("timestamp_utc", "<", Cell::Timestamptz(OCT_25_MICROS))
+            .columns(&[
+                "timestamp_utc",
+                "product_type",
+                "data_category",
+                "tso_50hertz_mw",
+                "total_germany_mw",
+                "fetched_at",
+            ]);
+
+        // Mock HTTP client: the second row is outside the time bounds
+        let csv = "Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)\n\
+                   2024-10-24;06:00;UTC;06:15;UTC;100,5;200;300;150\n\
+                   2024-10-25;06:00;UTC;06:15;UTC;110;210;310;160";
+        let mut fdw = test_fdw();
+        let ScanOutput { rows, urls, .. } =
+            run_scan(&mut fdw, &ctx, |_| Ok(csv.to_string())).unwrap();
+
+        assert_eq!(
+            urls,
+            vec!["https://ds.netztransparenz.de/api/v1/data/hochrechnung/Solar/2024-10-24/2024-10-25"]
+        );
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            render(&rows[0]),
+            vec![
+                "Cell::Timestamptz(1729749600000000)",
+                "Cell::String(\"solar\")",
+                "Cell::String(\"extrapolation\")",
+                "Cell::Numeric(100.5)",
+                "Cell::Numeric(750.5)",
+                "NULL",
+            ]
+        );
+        assert!(ctx.log().contains(
+            &"begin_scan: date range adjustment MidnightEnd (exclusive midnight end, no adjustment)"
+                .to_string()
+        ));
+
+        // end_scan cleared the buffers
+        assert!(fdw.renewable_rows.rows().is_empty());
+    }
+
+    /// `timestamp_utc >= now() - interval '24 hours'` reaches the FDW as a
+    /// concrete Timestamptz qual evaluated by PostgreSQL
+    #[test]
+    fn test_relative_start_only_qual() {
+        let now_micros = 1_749_988_800 * 1_000_000; // FakeContext clock
+        let start = now_micros - 24 * 3_600_000_000;
+        let ctx = FakeContext::new("renewable_energy_timeseries")
+            .qual("product_type", "=", Cell::String("solar".to_string()))
+            .qual(
+                "data_category",
+                "=",
+                Cell::String("online_actual".to_string()),
+            )
+            .qual("timestamp_utc", ">=", Cell::Timestamptz(start));
+
+        let (filters, adjustment) = crate::parse_quals(&ctx, false).unwrap();
+
+        assert_eq!(adjustment, crate::RangeAdjustment::StartOnly);
+        let range = filters.timestamp_range.unwrap();
+        assert_eq!(range.start, "2025-06-14");
+        assert_eq!(range.end, "2025-06-21");
+        assert_eq!(
+            filters.timestamp_bounds,
+            Some(crate::query_router::TimestampBounds {
+                start: Some(start),
+                start_operator: Some(">=".to_string()),
+                end: None,
+                end_operator: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_midnight_end_bound_not_over_fetched() {
+        let scan_urls = |upper: &str| {
+            let ctx = FakeContext::new("renewable_energy_timeseries")
+                .qual("product_type", "=", Cell::String("solar".to_string()))
+                .qual(
+                    "data_category",
+                    "=",
+                    Cell::String("extrapolation".to_string()),
+                )
+                .qual(
+                    "timestamp_utc",
+                    ">=",
+                    Cell::String("2024-10-20T23:00:00Z".to_string()),
+                )
+                .qual(
+                    "timestamp_utc",
+                    upper,
+                    Cell::String("2024-10-21T00:00:00Z".to_string()),
+                )
+                .columns(&["timestamp_utc"]);
+            let csv = "Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)\n\
+                       2024-10-20;23:45;UTC;24:00;UTC;1;2;3;4";
+            run_scan(&mut test_fdw(), &ctx, |_| Ok(csv.to_string()))
+                .unwrap()
+                .urls
+        };
+
+        // `< midnight` excludes Oct 21 entirely: fetch Oct 20 only
+        assert_eq!(
+            scan_urls("<"),
+            vec!["https://ds.netztransparenz.de/api/v1/data/hochrechnung/Solar/2024-10-20/2024-10-21"]
+        );
+
+        // `<= midnight` still matches the 00:00 row of Oct 21
+        assert_eq!(
+            scan_urls("<="),
+            vec!["https://ds.netztransparenz.de/api/v1/data/hochrechnung/Solar/2024-10-20/2024-10-22"]
+        );
+    }
+
+    #[test]
+    fn test_sort_within_endpoint_orders_out_of_order_csv() {
+        let ctx = FakeContext::new("renewable_energy_timeseries")
+            .qual("product_type", "=", Cell::String("solar".to_string()))
+            .qual(
+                "data_category",
+                "=",
+                Cell::String("extrapolation".to_string()),
+            )
+            .qual("timestamp_utc", ">=", Cell::Timestamptz(OCT_24_MICROS))
+            .qual("timestamp_utc", "<", Cell::Timestamptz(OCT_25_MICROS))
+            .columns(&["timestamp_utc", "tso_50hertz_mw"]);
+        let csv = "Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)\n\
+                   2024-10-24;06:30;UTC;06:45;UTC;3;0;0;0\n\
+                   2024-10-24;06:00;UTC;06:15;UTC;1;0;0;0\n\
+                   2024-10-24;06:15;UTC;06:30;UTC;2;0;0;0";
+        let values = |sort: bool| {
+            let mut fdw = NtpFdw {
+                sort_within_endpoint: sort,
+                ..test_fdw()
+            };
+            run_scan(&mut fdw, &ctx, |_| Ok(csv.to_string()))
+                .unwrap()
+                .rows
+                .iter()
+                .map(|row| render(row)[1].clone())
+                .collect::<Vec<_>>()
+        };
+
+        // Default: file order
+        assert_eq!(
+            values(false),
+            vec![
+                "Cell::Numeric(3.0)",
+                "Cell::Numeric(1.0)",
+                "Cell::Numeric(2.0)"
+            ]
+        );
+
+        // Enabled: chronological by timestamp_utc
+        assert_eq!(
+            values(true),
+            vec![
+                "Cell::Numeric(1.0)",
+                "Cell::Numeric(2.0)",
+                "Cell::Numeric(3.0)"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_date_aligned_bounds_skip_local_timestamp_filter() {
+        let scan = |start_operator: &str| {
+            let ctx = FakeContext::new("renewable_energy_timeseries")
+                .qual("product_type", "=", Cell::String("solar".to_string()))
+                .qual(
+                    "data_category",
+                    "=",
+                    Cell::String("extrapolation".to_string()),
+                )
+                .qual(
+                    "timestamp_utc",
+                    start_operator,
+                    Cell::Timestamptz(OCT_24_MICROS),
+                )
+                .qual("timestamp_utc", "<", Cell::Timestamptz(OCT_25_MICROS))
+                .columns(&["timestamp_utc"]);
+            // The last line is outside the bounds: only a local filter would drop it
+            let csv = "Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)\n\
+                       2024-10-24;00:00;UTC;00:15;UTC;1;2;3;4\n\
+                       2024-10-24;12:00;UTC;12:15;UTC;1;2;3;4\n\
+                       2024-10-25;00:00;UTC;00:15;UTC;1;2;3;4";
+            run_scan(&mut test_fdw(), &ctx, |_| Ok(csv.to_string())).unwrap()
+        };
+
+        // `>= '2024-10-24' AND < '2024-10-25'` is exactly the fetched range: not re-filtered
+        let bypassed = scan(">=");
+        assert_eq!(
+            bypassed.urls,
+            vec!["https://ds.netztransparenz.de/api/v1/data/hochrechnung/Solar/2024-10-24/2024-10-25"]
+        );
+        assert_eq!(bypassed.rows.len(), 3);
+
+        // `>` excludes the midnight row, so the local filter still runs
+        let filtered = scan(">");
+        assert_eq!(filtered.urls, bypassed.urls);
+        assert_eq!(filtered.rows.len(), 1);
+    }
+
+    #[test]
+    fn test_fetched_at_qual_ignored() {
+        let scan = |fetched_at: bool| {
+            let mut ctx = FakeContext::new("renewable_energy_timeseries")
+                .qual("product_type", "=", Cell::String("solar".to_string()))
+                .qual(
+                    "data_category",
+                    "=",
+                    Cell::String("extrapolation".to_string()),
+                )
+                .qual(
+                    "timestamp_utc",
+                    ">=",
+                    Cell::String("2024-10-20".to_string()),
+                );
+            if fetched_at {
+                // Would shift the window to 2024-06-01 if read as a timestamp_utc bound
+                ctx = ctx.qual(
+                    "fetched_at",
+                    ">",
+                    Cell::Timestamptz(1_717_200_000_000_000), // 2024-06-01T00:00:00Z
+                );
+            }
+            let ctx = ctx.columns(&["timestamp_utc", "fetched_at"]);
+            let csv = "Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)\n\
+                       2024-10-20;06:00;UTC;06:15;UTC;1;2;3;4";
+            let output = run_scan(&mut test_fdw(), &ctx, |_| Ok(csv.to_string())).unwrap();
+            (output, ctx.log())
+        };
+
+        let (plain, plain_log) = scan(false);
+        let (with_fetched_at, log) = scan(true);
+
+        assert_eq!(with_fetched_at.urls, plain.urls);
+        assert_eq!(
+            with_fetched_at.urls,
+            vec!["https://ds.netztransparenz.de/api/v1/data/hochrechnung/Solar/2024-10-20/2024-10-27"]
+        );
+        let rendered =
+            |rows: &[Vec<Option<Cell>>]| rows.iter().map(|r| render(r)).collect::<Vec<_>>();
+        assert_eq!(rendered(&with_fetched_at.rows), rendered(&plain.rows));
+        assert_eq!(with_fetched_at.rows.len(), 1);
+
+        // Same date range adjustment, plus a note that the qual stays in PostgreSQL
+        let adjustment = |log: &[String]| {
+            log.iter()
+                .find(|msg| msg.starts_with("begin_scan: date range adjustment"))
+                .cloned()
+        };
+        assert_eq!(adjustment(&log), adjustment(&plain_log));
+        assert!(log.contains(
+            &"begin_scan: fetched_at > qual not pushed down (evaluated by PostgreSQL)".to_string()
+        ));
+    }
+
+    #[test]
+    fn test_fill_gaps_adds_null_row_for_missing_interval() {
+        let ctx = FakeContext::new("renewable_energy_timeseries")
+            .qual("product_type", "=", Cell::String("solar".to_string()))
+            .qual(
+                "data_category",
+                "=",
+                Cell::String("extrapolation".to_string()),
+            )
+            .qual(
+                "timestamp_utc",
+                ">=",
+                Cell::Timestamptz(OCT_24_MICROS + 6 * 3_600_000_000),
+            )
+            .qual(
+                "timestamp_utc",
+                "<",
+                Cell::Timestamptz(OCT_24_MICROS + 7 * 3_600_000_000),
+            )
+            .columns(&["timestamp_utc", "tso_50hertz_mw", "has_missing_data"]);
+
+        // 06:15 is missing from the response
+        let csv = "Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)\n\
+                   2024-10-24;06:00;UTC;06:15;UTC;100;200;300;150\n\
+                   2024-10-24;06:30;UTC;06:45;UTC;120;220;320;170\n\
+                   2024-10-24;06:45;UTC;07:00;UTC;130;230;330;180";
+        let mut fdw = NtpFdw {
+            fill_gaps: true,
+            ..test_fdw()
+        };
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &ctx, |_| Ok(csv.to_string())).unwrap();
+
+        let quarter = |n: i64| {
+            format!(
+                "Cell::Timestamptz({})",
+                OCT_24_MICROS + (24 + n) * 900_000_000
+            )
+        };
+        let rows: Vec<Vec<String>> = rows.iter().map(|row| render(row)).collect();
+        assert_eq!(
+            rows,
+            vec![
+                vec![
+                    quarter(0),
+                    "Cell::Numeric(100.0)".to_string(),
+                    "Cell::Bool(false)".to_string()
+                ],
+                vec![
+                    quarter(1),
+                    "NULL".to_string(),
+                    "Cell::Bool(true)".to_string()
+                ],
+                vec![
+                    quarter(2),
+                    "Cell::Numeric(120.0)".to_string(),
+                    "Cell::Bool(false)".to_string()
+                ],
+                vec![
+                    quarter(3),
+                    "Cell::Numeric(130.0)".to_string(),
+                    "Cell::Bool(false)".to_string()
+                ],
+            ]
+        );
+
+        // Off by default: the gap stays a missing row
+        let ScanOutput { rows, .. } =
+            run_scan(&mut test_fdw(), &ctx, |_| Ok(csv.to_string())).unwrap();
+        assert_eq!(rows.len(), 3);
+    }
+
+    #[test]
+    fn test_renewable_scan_null_sentinels() {
+        let ctx = FakeContext::new("renewable_energy_timeseries")
+            .qual("product_type", "=", Cell::String("solar".to_string()))
+            .qual(
+                "data_category",
+                "=",
+                Cell::String("extrapolation".to_string()),
+            )
+            .columns(&["tso_50hertz_mw", "tso_amprion_mw", "tso_tennet_mw"]);
+
+        // "k.A." is built in, "x" comes from the null_sentinels server option
+        let csv = "Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)\n\
+                   2024-10-24;06:00;UTC;06:15;UTC;k.A.;X;300;150";
+        let mut fdw = NtpFdw {
+            null_sentinels: vec!["x".to_string()],
+            ..test_fdw()
+        };
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &ctx, |_| Ok(csv.to_string())).unwrap();
+
+        assert_eq!(
+            render(&rows[0]),
+            vec!["NULL", "NULL", "Cell::Numeric(300.0)"]
+        );
+    }
+
+    #[test]
+    fn test_renewable_scan_null_if_zero_transform() {
+        let ctx = FakeContext::new("renewable_energy_timeseries")
+            .qual("product_type", "=", Cell::String("solar".to_string()))
+            .qual(
+                "data_category",
+                "=",
+                Cell::String("extrapolation".to_string()),
+            )
+            .columns(&["tso_50hertz_mw", "tso_amprion_mw"]);
+        let csv = "Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)\n\
+                   2024-10-24;06:00;UTC;06:15;UTC;0;0;300;150";
+        let mut fdw = NtpFdw {
+            column_transforms: ColumnTransforms::parse("tso_50hertz_mw=null_if_zero").unwrap(),
+            ..test_fdw()
+        };
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &ctx, |_| Ok(csv.to_string())).unwrap();
+
+        // Only the configured column is transformed
+        assert_eq!(render(&rows[0]), vec!["NULL", "Cell::Numeric(0.0)"]);
+    }
+
+    #[test]
+    fn test_price_scan_round2_transform() {
+        let ctx = FakeContext::new("electricity_market_prices")
+            .qual("price_type", "=", Cell::String("spot_market".to_string()))
+            .columns(&["price_eur_mwh", "price_ct_kwh"]);
+        let csv = "Datum;von;Zeitzone von;bis;Zeitzone bis;Spotmarktpreis in ct/kWh\n\
+                   23.10.2024;00:00;UTC;01:00;UTC;8,12345";
+        let mut fdw = NtpFdw {
+            column_transforms: ColumnTransforms::parse("price_eur_mwh=round2").unwrap(),
+            ..test_fdw()
+        };
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &ctx, |_| Ok(csv.to_string())).unwrap();
+
+        assert_eq!(render(&rows[0])[0], "Cell::Numeric(81.23)");
+        assert_ne!(render(&rows[0])[1], "Cell::Numeric(8.12)");
+    }
+
+    #[test]
+    fn test_renewable_scan_without_timezone_columns() {
+        let ctx = FakeContext::new("renewable_energy_timeseries")
+            .qual("product_type", "=", Cell::String("solar".to_string()))
+            .qual(
+                "data_category",
+                "=",
+                Cell::String("extrapolation".to_string()),
+            )
+            .columns(&["timestamp_utc", "tso_tennet_mw"]);
+        let csv = "Datum;von;bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)\n\
+                   2024-10-24;06:00;06:15;100;200;300;150";
+
+        // Default: implicit UTC
+        let mut fdw = test_fdw();
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &ctx, |_| Ok(csv.to_string())).unwrap();
+        assert_eq!(
+            render(&rows[0]),
+            vec![
+                format!("Cell::Timestamptz({})", OCT_24_MICROS + 6 * 3_600_000_000),
+                "Cell::Numeric(300.0)".to_string()
+            ]
+        );
+
+        // assume_utc_when_missing 'false'
+        let mut fdw = NtpFdw {
+            require_timezone_columns: true,
+            ..test_fdw()
+        };
+        let err = run_scan(&mut fdw, &ctx, |_| Ok(csv.to_string())).unwrap_err();
+        assert!(err.contains("Zeitzone von"));
+    }
+
+    #[test]
+    fn test_scan_warnings_record_assumed_utc() {
+        let ctx = FakeContext::new("renewable_energy_timeseries")
+            .qual("product_type", "=", Cell::String("solar".to_string()))
+            .qual(
+                "data_category",
+                "=",
+                Cell::String("extrapolation".to_string()),
+            )
+            .columns(&["timestamp_utc"]);
+        let without_zeitzone =
+            "Datum;von;bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)\n\
+                                2024-10-24;06:00;06:15;100;200;300;150\n\
+                                2024-10-24;06:15;06:30;100;200;300;150";
+        let warnings = FakeContext::new("scan_warnings").columns(&[
+            "kind",
+            "message",
+            "source_endpoint",
+            "row_count",
+        ]);
+
+        let mut fdw = test_fdw();
+        run_scan(&mut fdw, &ctx, |_| Ok(without_zeitzone.to_string())).unwrap();
+        assert!(
+            ctx.log().iter().any(|msg| msg
+                == "scan_warnings: 1 warning (1 assumed_utc), see the scan_warnings table"),
+            "{:?}",
+            ctx.log()
+        );
+
+        // Reading the warnings makes no request and keeps them
+        for _ in 0..2 {
+            let ScanOutput { rows, urls, .. } =
+                run_scan(&mut fdw, &warnings, |_| panic!("no request expected")).unwrap();
+            assert!(urls.is_empty());
+            assert_eq!(rows.len(), 1);
+            let row = render(&rows[0]);
+            assert_eq!(row[0], "Cell::String(\"assumed_utc\")");
+            assert!(row[1].contains("no Zeitzone columns"), "{}", row[1]);
+            assert!(
+                row[2].starts_with("Cell::String(\"hochrechnung/Solar/"),
+                "{}",
+                row[2]
+            );
+            assert_eq!(row[3], "Cell::I64(2)");
+        }
+
+        // The next data scan replaces them
+        let with_zeitzone = "Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)\n\
+                             2024-10-24;06:00;UTC;06:15;UTC;100;200;300;150";
+        let ctx = FakeContext::new("renewable_energy_timeseries")
+            .qual("product_type", "=", Cell::String("solar".to_string()))
+            .qual(
+                "data_category",
+                "=",
+                Cell::String("extrapolation".to_string()),
+            )
+            .columns(&["timestamp_utc"]);
+        run_scan(&mut fdw, &ctx, |_| Ok(with_zeitzone.to_string())).unwrap();
+        assert!(!ctx
+            .log()
+            .iter()
+            .any(|msg| msg.starts_with("scan_warnings:")));
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &warnings, |_| Ok(String::new())).unwrap();
+        assert!(rows.is_empty());
+    }
+
+    #[test]
+    fn test_max_rows_per_endpoint_truncates_with_warning() {
+        let ctx = FakeContext::new("renewable_energy_timeseries")
+            .qual("product_type", "=", Cell::String("solar".to_string()))
+            .qual(
+                "data_category",
+                "=",
+                Cell::String("extrapolation".to_string()),
+            )
+            .columns(&["timestamp_utc"]);
+        let body = "Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)\n\
+                    2024-10-24;06:00;UTC;06:15;UTC;100;200;300;150\n\
+                    2024-10-24;06:15;UTC;06:30;UTC;100;200;300;150\n\
+                    2024-10-24;06:30;UTC;06:45;UTC;100;200;300;150";
+        let warnings = FakeContext::new("scan_warnings").columns(&["kind", "message", "row_count"]);
+
+        // Three rows over a cap of two: the first two in response order are kept
+        let mut fdw = NtpFdw {
+            max_rows_per_endpoint: Some(2),
+            ..test_fdw()
+        };
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &ctx, |_| Ok(body.to_string())).unwrap();
+        assert_eq!(
+            rows.iter()
+                .map(|row| render(row)[0].clone())
+                .collect::<Vec<_>>(),
+            vec![
+                format!("Cell::Timestamptz({})", OCT_24_MICROS + 6 * 3_600_000_000),
+                format!(
+                    "Cell::Timestamptz({})",
+                    OCT_24_MICROS + 6 * 3_600_000_000 + 900_000_000
+                ),
+            ]
+        );
+        assert!(
+            ctx.log().iter().any(|msg| msg
+                == "scan_warnings: 1 warning (1 truncated_endpoint), see the scan_warnings table"),
+            "{:?}",
+            ctx.log()
+        );
+
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &warnings, |_| unreachable!()).unwrap();
+        assert_eq!(
+            render(&rows[0]),
+            vec![
+                "Cell::String(\"truncated_endpoint\")",
+                "Cell::String(\"2 of 3 rows kept, results are incomplete (max_rows_per_endpoint)\")",
+                "Cell::I64(1)",
+            ]
+        );
+
+        // At the cap nothing is dropped
+        let mut fdw = NtpFdw {
+            max_rows_per_endpoint: Some(3),
+            ..test_fdw()
+        };
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &ctx, |_| Ok(body.to_string())).unwrap();
+        assert_eq!(rows.len(), 3);
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &warnings, |_| unreachable!()).unwrap();
+        assert!(rows.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_distinct_on_fails_before_fetching() {
+        let ctx = FakeContext::new("renewable_energy_timeseries")
+            .qual("product_type", "=", Cell::String("solar".to_string()))
+            .qual(
+                "data_category",
+                "=",
+                Cell::String("extrapolation".to_string()),
+            )
+            .option("distinct_on", "timestamp_utc,no_such_column");
+
+        let mut fdw = test_fdw();
+        let err = run_scan(&mut fdw, &ctx, |_| panic!("no request expected")).unwrap_err();
+        assert!(err.contains("distinct_on"), "{}", err);
+    }
+
+    #[test]
+    fn test_product_label_map_relabels_output_only() {
+        // Filtering still uses the canonical name
+        let ctx = FakeContext::new("renewable_energy_timeseries")
+            .qual("product_type", "=", Cell::String("solar".to_string()))
+            .qual(
+                "data_category",
+                "=",
+                Cell::String("extrapolation".to_string()),
+            )
+            .columns(&["product_type", "api_product", "tso_tennet_mw"]);
+
+        let csv = "Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)\n\
+                   2024-10-24;06:00;UTC;06:15;UTC;100;200;300;150";
+        let mut fdw = NtpFdw {
+            product_labels: [("solar".to_string(), "PV".to_string())]
+                .into_iter()
+                .collect(),
+            ..test_fdw()
+        };
+        let ScanOutput { rows, urls, .. } =
+            run_scan(&mut fdw, &ctx, |_| Ok(csv.to_string())).unwrap();
+
+        assert_eq!(urls.len(), 1);
+        assert!(urls[0].contains("/hochrechnung/Solar/"));
+        assert_eq!(
+            render(&rows[0]),
+            vec![
+                "Cell::String(\"PV\")",
+                "Cell::String(\"Solar\")",
+                "Cell::Numeric(300.0)"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_online_actual_wind_scan_api_product() {
+        let ctx = FakeContext::new("renewable_energy_timeseries")
+            .qual(
+                "product_type",
+                "=",
+                Cell::String("wind_onshore".to_string()),
+            )
+            .qual(
+                "data_category",
+                "=",
+                Cell::String("online_actual".to_string()),
+            )
+            .qual("timestamp_utc", ">=", Cell::Timestamptz(OCT_24_MICROS))
+            .qual("timestamp_utc", "<", Cell::Timestamptz(OCT_25_MICROS))
+            .columns(&["product_type", "api_product"]);
+
+        let csv = "Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)\n\
+                   2024-10-24;10:00;UTC;11:00;UTC;1000;1100;1200;900";
+        let mut fdw = test_fdw();
+        let ScanOutput { rows, urls, .. } =
+            run_scan(&mut fdw, &ctx, |_| Ok(csv.to_string())).unwrap();
+
+        assert!(urls[0].contains("/onlinehochrechnung/Windonshore/"));
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            render(&rows[0]),
+            vec![
+                "Cell::String(\"wind_onshore\")",
+                "Cell::String(\"Windonshore\")",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_product_type_any_array_scan() {
+        let ctx = FakeContext::new("renewable_energy_timeseries")
+            .qual_any(
+                "product_type",
+                vec![
+                    Cell::String("solar".to_string()),
+                    Cell::String("wind_onshore".to_string()),
+                    Cell::String("solar".to_string()),
+                ],
+            )
+            .qual(
+                "data_category",
+                "=",
+                Cell::String("online_actual".to_string()),
+            )
+            .qual("timestamp_utc", ">=", Cell::Timestamptz(OCT_24_MICROS))
+            .qual("timestamp_utc", "<", Cell::Timestamptz(OCT_25_MICROS))
+            .columns(&["product_type"]);
+
+        let csv = "Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)\n\
+                   2024-10-24;10:00;UTC;11:00;UTC;1000;1100;1200;900";
+        let mut fdw = test_fdw();
+        let ScanOutput { rows, urls, .. } =
+            run_scan(&mut fdw, &ctx, |_| Ok(csv.to_string())).unwrap();
+
+        // One fetch per distinct product, wind_offshore not queried
+        assert_eq!(urls.len(), 2);
+        assert!(urls[0].contains("/onlinehochrechnung/Solar/"));
+        assert!(urls[1].contains("/onlinehochrechnung/Windonshore/"));
+        assert_eq!(rows.len(), 2);
+    }
+
+    #[test]
+    fn test_product_type_any_array_german_aliases() {
+        let ctx = FakeContext::new("renewable_energy_timeseries")
+            .qual_any(
+                "product_type",
+                vec![
+                    Cell::String("solar".to_string()),
+                    Cell::String("Windonshore".to_string()),
+                ],
+            )
+            .columns(&["product_type"]);
+
+        let mut fdw = test_fdw();
+        let err = run_scan(&mut fdw, &ctx, |_| panic!("alias must not fetch")).unwrap_err();
+
+        assert_eq!(
+            err,
+            "Failed to parse quals: product_type 'Windonshore' is an API product name: \
+             use product_type = 'wind_onshore'"
+        );
+    }
+
+    #[test]
+    fn test_product_type_any_array_invalid_element() {
+        let ctx = FakeContext::new("renewable_energy_timeseries")
+            .qual_any(
+                "product_type",
+                vec![
+                    Cell::String("solar".to_string()),
+                    Cell::String("biomass".to_string()),
+                ],
+            )
+            .columns(&["product_type"]);
+
+        let mut fdw = test_fdw();
+        let err = run_scan(&mut fdw, &ctx, |_| panic!("invalid list must not fetch")).unwrap_err();
+
+        assert_eq!(
+            err,
+            "Failed to parse quals: Invalid product_type in ANY/IN list: 'biomass'. \
+             Expected 'solar', 'wind_onshore', or 'wind_offshore'."
+        );
+    }
+
+    #[test]
+    fn test_query_window_columns_show_adjusted_range() {
+        // Same-date bounds: the API window is moved to end one day later
+        let ctx = FakeContext::new("renewable_energy_timeseries")
+            .qual("product_type", "=", Cell::String("solar".to_string()))
+            .qual(
+                "data_category",
+                "=",
+                Cell::String("extrapolation".to_string()),
+            )
+            .qual("timestamp_utc", ">=", Cell::Timestamptz(OCT_24_MICROS))
+            .qual(
+                "timestamp_utc",
+                "<=",
+                Cell::Timestamptz(OCT_24_MICROS + 12 * 3_600_000_000),
+            )
+            .columns(&["timestamp_utc", "query_window_start", "query_window_end"])
+            .column_type("query_window_start", TypeOid::Timestamptz);
+        let csv = "Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)\n\
+                   2024-10-24;06:00;UTC;06:15;UTC;100;200;300;150\n\
+                   2024-10-24;06:15;UTC;06:30;UTC;110;210;310;160";
+
+        let mut fdw = test_fdw();
+        let ScanOutput { rows, urls, .. } =
+            run_scan(&mut fdw, &ctx, |_| Ok(csv.to_string())).unwrap();
+
+        assert!(urls[0].ends_with("/2024-10-24/2024-10-25"), "{}", urls[0]);
+        assert_eq!(rows.len(), 2);
+        for row in &rows {
+            assert_eq!(
+                render(row)[1..],
+                [
+                    format!("Cell::Timestamptz({})", OCT_24_MICROS),
+                    format!("Cell::Timestamptz({})", OCT_25_MICROS),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_same_day_reversed_time_range_rejected() {
+        // Valid as dates (both 2024-10-24), contradictory as timestamps
+        let ctx = FakeContext::new("electricity_market_prices")
+            .qual(
+                "timestamp_utc",
+                ">=",
+                Cell::String("2024-10-24T12:00:00Z".to_string()),
+            )
+            .qual(
+                "timestamp_utc",
+                "<",
+                Cell::String("2024-10-24T10:00:00Z".to_string()),
+            )
+            .columns(&["price_eur_mwh"]);
+
+        let mut fdw = test_fdw();
+        let err = run_scan(&mut fdw, &ctx, |_| {
+            panic!("contradictory range must not fetch")
+        })
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            "Failed to parse quals: Contradictory timestamp_utc filters: \
+             timestamp_utc >= 2024-10-24T12:00:00Z AND timestamp_utc < 2024-10-24T10:00:00Z \
+             cannot match any row"
+        );
+    }
+
+    #[test]
+    fn test_equal_timestamp_bounds() {
+        let at = |lower: &str, upper: &str| {
+            FakeContext::new("electricity_market_prices")
+                .qual("timestamp_utc", lower, Cell::Timestamptz(OCT_24_MICROS))
+                .qual("timestamp_utc", upper, Cell::Timestamptz(OCT_24_MICROS))
+                .columns(&["price_eur_mwh"])
+        };
+
+        // Inclusive on both sides is a single instant, not a contradiction
+        let mut fdw = test_fdw();
+        assert!(run_scan(&mut fdw, &at(">=", "<="), |_| Ok(String::new())).is_ok());
+
+        let err = run_scan(&mut fdw, &at(">=", "<"), |_| Ok(String::new())).unwrap_err();
+        assert!(err.contains(
+            "timestamp_utc >= 2024-10-24T00:00:00Z AND timestamp_utc < 2024-10-24T00:00:00Z"
+        ));
+    }
+
+    #[test]
+    fn test_solar_forecast_scan_rejected() {
+        // The forecast (prognose) category was removed in v0.3.0
+        let ctx = FakeContext::new("renewable_energy_timeseries")
+            .qual("product_type", "=", Cell::String("solar".to_string()))
+            .qual("data_category", "=", Cell::String("forecast".to_string()))
+            .columns(&["timestamp_utc"]);
+
+        let mut fdw = test_fdw();
+        let err =
+            run_scan(&mut fdw, &ctx, |_| panic!("rejected query must not fetch")).unwrap_err();
+
+        assert!(err.starts_with("Failed to route query: "));
+    }
+
+    #[test]
+    fn test_as_of_qual_rejected() {
+        // Forecast vintages need the removed forecast endpoints
+        let ctx = FakeContext::new("renewable_energy_timeseries")
+            .qual("product_type", "=", Cell::String("solar".to_string()))
+            .qual(
+                "data_category",
+                "=",
+                Cell::String("extrapolation".to_string()),
+            )
+            .qual("as_of", "=", Cell::Timestamptz(OCT_24_MICROS))
+            .columns(&["timestamp_utc"]);
+
+        let mut fdw = test_fdw();
+        let err =
+            run_scan(&mut fdw, &ctx, |_| panic!("rejected query must not fetch")).unwrap_err();
+
+        assert!(err.contains("as_of = qual not supported"));
+        assert!(err.contains("removed in v0.3.0"));
+    }
+
+    #[test]
+    fn test_annual_market_value_single_product_scan() {
+        let ctx = FakeContext::new("electricity_market_prices")
+            .qual(
+                "price_type",
+                "=",
+                Cell::String("annual_market_value".to_string()),
+            )
+            .qual("product_category", "=", Cell::String("solar".to_string()))
+            // The year overlapping Oct 24 (see test_coarse_price_rows_overlapping_a_day)
+            .qual("interval_end_utc", ">", Cell::Timestamptz(OCT_24_MICROS))
+            .qual("timestamp_utc", "<", Cell::Timestamptz(OCT_25_MICROS))
+            .columns(&["price_type", "product_category"]);
+
+        // Mock HTTP client: the endpoint always returns every product
+        let body = "Alle Werte in ct/kWh;2024\n\
+                    JW;7,946\n\
+                    JW Wind an Land;7,116\n\
+                    JW Wind auf See;7,503\n\
+                    JW Solar;4,624";
+        let mut fdw = test_fdw();
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &ctx, |_| Ok(body.to_string())).unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            render(&rows[0]),
+            vec![
+                "Cell::String(\"annual_market_value\")",
+                "Cell::String(\"solar\")",
+            ]
+        );
+    }
+
+    /// Monthly and annual rows start before a mid-period day, so selecting the
+    /// rows that overlap it takes `interval_end_utc > day_start AND
+    /// timestamp_utc < day_end`; each condition is a plain comparison that
+    /// PostgreSQL's recheck of the returned rows agrees with
+    #[test]
+    fn test_coarse_price_rows_overlapping_a_day() {
+        let ctx = FakeContext::new("electricity_market_prices")
+            .qual("interval_end_utc", ">", Cell::Timestamptz(OCT_24_MICROS))
+            .qual("timestamp_utc", "<", Cell::Timestamptz(OCT_25_MICROS))
+            .columns(&["timestamp_utc", "granularity"]);
+
+        // Mock HTTP client: one body per price endpoint
+        let spot = "Datum;von;Zeitzone von;bis;Zeitzone bis;Spotmarktpreis in ct/kWh\n\
+                    23.10.2024;12:00;UTC;13:00;UTC;8,5\n\
+                    24.10.2024;12:00;UTC;13:00;UTC;8,1\n\
+                    25.10.2024;12:00;UTC;13:00;UTC;7,9";
+        let monthly = "Monat;MW-EPEX in ct/kWh;MW Wind Onshore in ct/kWh;PM Wind Onshore fernsteuerbar in ct/kWh;MW Wind Offshore in ct/kWh;PM Wind Offshore fernsteuerbar in ct/kWh;MW Solar in ct/kWh;PM Solar fernsteuerbar in ct/kWh;MW steuerbar in ct/kWh;PM steuerbar in ct/kWh\n\
+                       9/2024;7,1;6,2;0,4;6,8;0,4;4,9;0,4;7,1;0,2\n\
+                       10/2024;8,5;7,4;0,4;8,0;0,4;5,3;0,4;8,5;0,2\n\
+                       11/2024;9,8;8,6;0,4;9,1;0,4;3,2;0,4;9,8;0,2";
+        let annual = "Alle Werte in ct/kWh;2024\n\
+                      JW;7,946";
+        let mut fdw = test_fdw();
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &ctx, |plan| {
+            Ok(match plan.endpoint.as_str() {
+                "Spotmarktpreise" => spot,
+                "marktpraemie" => monthly,
+                "Jahresmarktpraemie" => annual,
+                _ => "",
+            }
+            .to_string())
+        })
+        .unwrap();
+
+        let mut kept: Vec<Vec<String>> = rows.iter().map(|row| render(row)).collect();
+        kept.dedup();
+        assert_eq!(
+            kept,
+            vec![
+                vec![
+                    "Cell::Timestamptz(1729771200000000)".to_string(), // 2024-10-24T12:00Z
+                    "Cell::String(\"hourly\")".to_string(),
+                ],
+                vec![
+                    "Cell::Timestamptz(1727740800000000)".to_string(), // 2024-10-01
+                    "Cell::String(\"monthly\")".to_string(),
+                ],
+                vec![
+                    "Cell::Timestamptz(1704067200000000)".to_string(), // 2024-01-01
+                    "Cell::String(\"annual\")".to_string(),
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_online_actual_partial_day_diagnostic() {
+        let scan_log = |day: &str| {
+            let ctx = FakeContext::new("renewable_energy_timeseries")
+                .qual("product_type", "=", Cell::String("solar".to_string()))
+                .qual(
+                    "data_category",
+                    "=",
+                    Cell::String("online_actual".to_string()),
+                )
+                .qual("timestamp_utc", ">=", Cell::String(day.to_string()))
+                .columns(&["timestamp_utc"]);
+
+            // Hourly series published up to 12:00; later hours are N.A.
+            let mut csv = "Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)".to_string();
+            for hour in 0..24 {
+                let value = if hour < 12 { "10,0" } else { "N.A." };
+                csv.push_str(&format!(
+                    "\n{};{:02}:00;UTC;{:02}:00;UTC;{};{};{};{}",
+                    day,
+                    hour,
+                    hour + 1,
+                    value,
+                    value,
+                    value,
+                    value
+                ));
+            }
+
+            let ScanOutput { rows, .. } =
+                run_scan(&mut test_fdw(), &ctx, |_| Ok(csv.clone())).unwrap();
+            assert_eq!(rows.len(), 24);
+            ctx.log()
+        };
+
+        // FakeContext clock: 2025-06-15T12:00:00Z
+        assert!(scan_log("2025-06-15").contains(
+            &"begin_scan: online_actual solar for 2025-06-15 is partial: 12 of 24 intervals published so far"
+                .to_string()
+        ));
+
+        // Past days are complete as published: no diagnostic
+        assert!(!scan_log("2025-06-10")
+            .iter()
+            .any(|line| line.contains("is partial")));
+    }
+
+    #[test]
+    fn test_expose_raw_round_trips_record() {
+        let header = "Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)";
+        let record = "2024-10-24;06:00;UTC;06:15;UTC;100,5;N.A.;300;150";
+        let csv = format!("{}\n{}", header, record);
+        let ctx = FakeContext::new("renewable_energy_timeseries")
+            .qual("product_type", "=", Cell::String("solar".to_string()))
+            .qual(
+                "data_category",
+                "=",
+                Cell::String("extrapolation".to_string()),
+            )
+            .columns(&["tso_50hertz_mw", "_raw"]);
+
+        let mut fdw = NtpFdw {
+            expose_raw: true,
+            ..test_fdw()
+        };
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &ctx, |_| Ok(csv.clone())).unwrap();
+        assert_eq!(
+            render(&rows[0]),
+            vec![
+                "Cell::Numeric(100.5)".to_string(),
+                format!("Cell::String({:?})", record),
+            ]
+        );
+
+        // Off by default: the column stays NULL
+        let ScanOutput { rows, .. } = run_scan(&mut test_fdw(), &ctx, |_| Ok(csv.clone())).unwrap();
+        assert_eq!(render(&rows[0]), vec!["Cell::Numeric(100.5)", "NULL"]);
+    }
+
+    #[test]
+    fn test_expose_raw_price_rows_share_record() {
+        let record = "23.10.2024;00:00;UTC;01:00;UTC;8,273;8,5";
+        let csv = format!(
+            "Datum;von;Zeitzone von;bis;Zeitzone bis;Spotmarktpreis in ct/kWh;Intraday in ct/kWh\n{}",
+            record
+        );
+        let ctx = FakeContext::new("electricity_market_prices")
+            .qual("price_type", "=", Cell::String("spot_market".to_string()))
+            .columns(&["price_type", "_raw"]);
+
+        let mut fdw = NtpFdw {
+            expose_raw: true,
+            ..test_fdw()
+        };
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &ctx, |_| Ok(csv.clone())).unwrap();
+
+        // The main and the extra price column row come from the same line
+        assert!(!rows.is_empty());
+        for row in &rows {
+            assert_eq!(render(row)[1], format!("Cell::String({:?})", record));
+        }
+    }
+
+    #[test]
+    fn test_price_scan_accepts_csv() {
+        let ctx = FakeContext::new("electricity_market_prices")
+            .qual("price_type", "=", Cell::String("spot_market".to_string()))
+            .columns(&["price_eur_mwh"]);
+
+        let mut fdw = test_fdw();
+        let ScanOutput { accept_headers, .. } =
+            run_scan(&mut fdw, &ctx, |_| Ok(String::new())).unwrap();
+
+        assert_eq!(accept_headers, vec!["text/csv"]);
+    }
+
+    #[test]
+    fn test_price_scan_bidding_zone_filter() {
+        let ctx = FakeContext::new("electricity_market_prices")
+            .qual("price_type", "=", Cell::String("spot_market".to_string()))
+            .qual("bidding_zone", "=", Cell::String("DE-LU".to_string()))
+            .columns(&["bidding_zone", "currency", "price_eur_mwh"]);
+        let csv =
+            "Datum;von;Zeitzone von;bis;Zeitzone bis;Gebotszone;Währung;Spotmarktpreis in ct/kWh\n\
+                   23.10.2024;00:00;UTC;01:00;UTC;DE-LU;EUR;8,5\n\
+                   23.10.2024;00:00;UTC;01:00;UTC;AT;EUR;9,2";
+
+        let mut fdw = test_fdw();
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &ctx, |_| Ok(csv.to_string())).unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            render(&rows[0]),
+            vec![
+                "Cell::String(\"DE-LU\")",
+                "Cell::String(\"EUR\")",
+                "Cell::Numeric(85.0)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_price_scan_spot_intraday_keeps_only_its_series() {
+        let ctx = FakeContext::new("electricity_market_prices")
+            .qual("price_type", "=", Cell::String("spot_intraday".to_string()))
+            .columns(&["price_type", "price_eur_mwh"]);
+        let csv = "Datum;von;Zeitzone von;bis;Zeitzone bis;Spotmarktpreis in ct/kWh;Day-Ahead in ct/kWh;Intraday in ct/kWh\n\
+                   23.10.2024;00:00;UTC;01:00;UTC;8,5;8,4;9,1";
+
+        let mut fdw = test_fdw();
+        let ScanOutput { rows, urls, .. } =
+            run_scan(&mut fdw, &ctx, |_| Ok(csv.to_string())).unwrap();
+
+        assert_eq!(urls.len(), 1);
+        assert!(urls[0].contains("/Spotmarktpreise/"), "{}", urls[0]);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            render(&rows[0]),
+            vec!["Cell::String(\"spot_intraday\")", "Cell::Numeric(91.0)"]
+        );
+    }
+
+    #[test]
+    fn test_price_scan_spot_auction_keeps_only_its_series() {
+        let ctx = FakeContext::new("electricity_market_prices")
+            .qual("price_type", "=", Cell::String("spot_auction".to_string()))
+            .columns(&["price_type", "price_eur_mwh"]);
+        let csv =
+            "Datum;von;Zeitzone von;bis;Zeitzone bis;Spotmarktpreis in ct/kWh;Auktion in ct/kWh\n\
+                   23.10.2024;00:00;UTC;01:00;UTC;8,5;8,7";
+
+        let mut fdw = test_fdw();
+        let ScanOutput { rows, urls, .. } =
+            run_scan(&mut fdw, &ctx, |_| Ok(csv.to_string())).unwrap();
+
+        assert_eq!(urls.len(), 1);
+        assert!(urls[0].contains("/Spotmarktpreise/"), "{}", urls[0]);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            render(&rows[0]),
+            vec!["Cell::String(\"spot_auction\")", "Cell::Numeric(87.0)"]
+        );
+    }
+
+    #[test]
+    fn test_price_scan_warns_on_renewable_only_qual() {
+        let ctx = FakeContext::new("electricity_market_prices")
+            .qual("price_type", "=", Cell::String("spot_market".to_string()))
+            .qual("product_type", "=", Cell::String("solar".to_string()))
+            .columns(&["price_type"]);
+        let csv = "Datum;von;Zeitzone von;bis;Zeitzone bis;Spotmarktpreis in ct/kWh\n\
+                   23.10.2024;00:00;UTC;01:00;UTC;8,5";
+
+        let mut fdw = test_fdw();
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &ctx, |_| Ok(csv.to_string())).unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert!(
+            ctx.log().iter().any(|msg| msg
+                == "begin_scan: WARNING: product_type = qual not routed: product_type is not a column of electricity_market_prices but of renewable_energy_timeseries; it doesn't filter the API request and is always NULL here"),
+            "{:?}",
+            ctx.log()
+        );
+    }
+
+    #[test]
+    fn test_price_scan_renewable_only_qual_fails_when_strict() {
+        let ctx = FakeContext::new("electricity_market_prices")
+            .qual("price_type", "=", Cell::String("spot_market".to_string()))
+            .qual("product_type", "=", Cell::String("solar".to_string()))
+            .columns(&["price_type"]);
+
+        let mut fdw = NtpFdw {
+            qual_columns_strict: true,
+            ..test_fdw()
+        };
+        let mut fetched = false;
+        let err = run_scan(&mut fdw, &ctx, |_| {
+            fetched = true;
+            Ok(String::new())
+        })
+        .unwrap_err();
+
+        assert!(
+            err.contains("Failed to parse quals: product_type = qual not routed"),
+            "{}",
+            err
+        );
+        assert!(!fetched);
+    }
+
+    #[test]
+    fn test_unknown_table_option_suggests_closest_name() {
+        let ctx = FakeContext::new("renewable_energy_timeserie")
+            .qual("product_type", "=", Cell::String("solar".to_string()))
+            .columns(&["timestamp_utc"]);
+
+        let mut fdw = test_fdw();
+        let mut fetched = false;
+        let err = run_scan(&mut fdw, &ctx, |_| {
+            fetched = true;
+            Ok(String::new())
+        })
+        .unwrap_err();
+
+        assert!(
+            err.contains("Unknown table 'renewable_energy_timeserie'"),
+            "{}",
+            err
+        );
+        assert!(
+            err.contains("Did you mean 'renewable_energy_timeseries'?"),
+            "{}",
+            err
+        );
+        assert!(!fetched);
+    }
+
+    #[test]
+    fn test_column_types_match() {
+        let ctx = FakeContext::new("electricity_market_prices")
+            .qual("price_type", "=", Cell::String("spot_market".to_string()))
+            .columns(&["timestamp_utc", "price_eur_mwh", "is_negative", "_raw"])
+            .column_type("timestamp_utc", TypeOid::Timestamptz)
+            .column_type("price_eur_mwh", TypeOid::Numeric)
+            .column_type("is_negative", TypeOid::Bool)
+            // VARCHAR is reported as Other and not checked
+            .column_type("_raw", TypeOid::Other("character varying".to_string()));
+
+        let csv = "Datum;von;Zeitzone von;bis;Zeitzone bis;Spotmarktpreis in ct/kWh
+                   23.10.2024;00:00;UTC;01:00;UTC;8,273";
+        let mut fdw = test_fdw();
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &ctx, |_| Ok(csv.to_string())).unwrap();
+
+        assert_eq!(rows.len(), 1);
+    }
+
+    #[test]
+    fn test_column_type_mismatch_rejected() {
+        let ctx = FakeContext::new("electricity_market_prices")
+            .qual("price_type", "=", Cell::String("spot_market".to_string()))
+            .columns(&["timestamp_utc", "price_eur_mwh", "price_type"])
+            .column_type("timestamp_utc", TypeOid::Date)
+            .column_type("price_eur_mwh", TypeOid::String)
+            .column_type("price_type", TypeOid::String);
+
+        let mut fdw = test_fdw();
+        let mut fetched = false;
+        let err = run_scan(&mut fdw, &ctx, |_| {
+            fetched = true;
+            Ok(String::new())
+        })
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            "Column type mismatch in foreign table 'electricity_market_prices': \
+             timestamp_utc is date, expected timestamptz; price_eur_mwh is text, expected numeric. \
+             Fix the column types in CREATE FOREIGN TABLE"
+        );
+        // Checked before routing: no request was made
+        assert!(!fetched);
+    }
+
+    #[test]
+    #[cfg(feature = "grid")]
+    fn test_grid_status_scan_accepts_json() {
+        // TrafficLight is JSON-only
+        let ctx = FakeContext::new("grid_status_timeseries").columns(&["grid_status"]);
+
+        let mut fdw = test_fdw();
+        let ScanOutput { accept_headers, .. } =
+            run_scan(&mut fdw, &ctx, |_| Ok(String::new())).unwrap();
+
+        assert_eq!(accept_headers, vec!["application/json"]);
+    }
+
+    #[test]
+    #[cfg(feature = "grid")]
+    fn test_grid_status_transitions_only_scan() {
+        let json = r#"[
+  {"From":"2024-10-24T00:00:00Z","To":"2024-10-24T00:01:00Z","Value":"GREEN"},
+  {"From":"2024-10-24T00:01:00Z","To":"2024-10-24T00:02:00Z","Value":"GREEN"},
+  {"From":"2024-10-24T00:02:00Z","To":"2024-10-24T00:03:00Z","Value":"YELLOW"},
+  {"From":"2024-10-24T00:03:00Z","To":"2024-10-24T00:04:00Z","Value":"RED"},
+  {"From":"2024-10-24T00:04:00Z","To":"2024-10-24T00:05:00Z","Value":"RED"},
+  {"From":"2024-10-24T00:05:00Z","To":"2024-10-24T00:06:00Z","Value":"GREEN"}
+]"#;
+        let ctx = FakeContext::new("grid_status_timeseries")
+            .option("transitions_only", "true")
+            .qual("timestamp_utc", ">=", Cell::Timestamptz(OCT_24_MICROS))
+            .qual("timestamp_utc", "<", Cell::Timestamptz(OCT_25_MICROS))
+            .columns(&["timestamp_utc", "grid_status"]);
+
+        let mut fdw = test_fdw();
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &ctx, |_| Ok(json.to_string())).unwrap();
+
+        let statuses: Vec<String> = rows.iter().map(|row| render(row)[1].clone()).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                "Cell::String(\"GREEN\")",
+                "Cell::String(\"YELLOW\")",
+                "Cell::String(\"RED\")",
+                "Cell::String(\"GREEN\")",
+            ]
+        );
+        // Each row is the minute its status began
+        assert_eq!(
+            render(&rows[2])[0],
+            format!("Cell::Timestamptz({})", OCT_24_MICROS + 3 * 60_000_000)
+        );
+
+        // A status filter selects among the transitions
+        let ctx = FakeContext::new("grid_status_timeseries")
+            .option("transitions_only", "true")
+            .qual("grid_status", "=", Cell::String("GREEN".to_string()))
+            .qual("timestamp_utc", ">=", Cell::Timestamptz(OCT_24_MICROS))
+            .qual("timestamp_utc", "<", Cell::Timestamptz(OCT_25_MICROS))
+            .columns(&["timestamp_utc"]);
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &ctx, |_| Ok(json.to_string())).unwrap();
+        assert_eq!(rows.len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "grid")]
+    fn test_scan_warnings_record_lenient_grid_status() {
+        let json = r#"[
+  {"From":"2024-10-24T00:00:00Z","To":"2024-10-24T00:01:00Z","Value":"GREEN"},
+  {"From":"2024-10-24T00:01:00Z","To":"2024-10-24T00:02:00Z","Value":"BLUE"},
+  {"From":"2024-10-24T00:02:00Z","To":"2024-10-24T00:03:00Z","Value":"BLUE"}
+]"#;
+        let ctx = FakeContext::new("grid_status_timeseries")
+            .qual("timestamp_utc", ">=", Cell::Timestamptz(OCT_24_MICROS))
+            .qual("timestamp_utc", "<", Cell::Timestamptz(OCT_25_MICROS))
+            .columns(&["grid_status"]);
+
+        let mut fdw = NtpFdw {
+            grid_status_lenient: true,
+            ..test_fdw()
+        };
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &ctx, |_| Ok(json.to_string())).unwrap();
+        assert_eq!(rows.len(), 3);
+
+        let warnings = FakeContext::new("scan_warnings").columns(&["kind", "message", "row_count"]);
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &warnings, |_| Ok(String::new())).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            render(&rows[0]),
+            vec![
+                "Cell::String(\"unknown_grid_status\")",
+                "Cell::String(\"unknown grid_status 'BLUE' kept as-is\")",
+                "Cell::I64(2)",
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "grid")]
+    fn test_redispatch_bucketize_scan() {
+        let ctx = FakeContext::new("redispatch_events")
+            .qual("timestamp_utc", ">=", Cell::Timestamptz(OCT_24_MICROS))
+            .qual("timestamp_utc", "<", Cell::Timestamptz(OCT_25_MICROS))
+            .columns(&["timestamp_utc", "interval_minutes", "total_energy_mwh"]);
+        let csv = "BEGINN_DATUM;BEGINN_UHRZEIT;ZEITZONE_VON;ENDE_DATUM;ENDE_UHRZEIT;ZEITZONE_BIS;GRUND_DER_MASSNAHME;RICHTUNG;MITTLERE_LEISTUNG_MW;MAXIMALE_LEISTUNG_MW;GESAMTE_ARBEIT_MWH;ANWEISENDER_UENB;ANFORDERNDER_UENB;BETROFFENE_ANLAGE;PRIMAERENERGIEART\n\
+                   24.10.2024;06:00;UTC;24.10.2024;06:45;UTC;Strombedingter Redispatch;Wirkleistungseinspeisung reduzieren;120;120;90;TenneT DE;TenneT DE;;Erneuerbar";
+
+        let mut fdw = NtpFdw {
+            redispatch_bucketize: true,
+            ..test_fdw()
+        };
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &ctx, |_| Ok(csv.to_string())).unwrap();
+
+        let rows: Vec<Vec<String>> = rows.iter().map(|row| render(row)).collect();
+        assert_eq!(
+            rows,
+            [0, 1, 2]
+                .iter()
+                .map(|quarter| vec![
+                    format!(
+                        "Cell::Timestamptz({})",
+                        OCT_24_MICROS + 6 * 3_600_000_000 + quarter * 900_000_000
+                    ),
+                    "Cell::I32(15)".to_string(),
+                    "Cell::Numeric(30.0)".to_string(),
+                ])
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_scan_fetch_error_aborts() {
+        let ctx = FakeContext::new("electricity_market_prices")
+            .qual("price_type", "=", Cell::String("spot_market".to_string()))
+            .columns(&["price_eur_mwh"]);
+
+        let mut fdw = test_fdw();
+        let err = run_scan(&mut fdw, &ctx, |_| Err("HTTP 500".to_string())).unwrap_err();
+
+        assert!(err.contains("HTTP 500"));
+    }
+
+    #[test]
+    fn test_circuit_breaker_fails_scan_during_cooldown() {
+        const NOW: i64 = 1_749_988_800;
+        let annual = |now: i64| {
+            FakeContext::new("electricity_market_prices")
+                .qual(
+                    "price_type",
+                    "=",
+                    Cell::String("annual_market_value".to_string()),
+                )
+                .columns(&["price_eur_mwh"])
+                .now(now)
+        };
+
+        let mut fdw = NtpFdw {
+            circuit_breaker: RefCell::new(CircuitBreaker::new(2, 300)),
+            ..test_fdw()
+        };
+        let failing = |_: &QueryPlan| Err("HTTP 500".to_string());
+
+        // Two consecutive failures trip the breaker
+        assert!(run_scan(&mut fdw, &annual(NOW), failing).is_err());
+        let ctx = annual(NOW + 10);
+        assert!(run_scan(&mut fdw, &ctx, failing).is_err());
+        assert!(ctx
+            .log()
+            .iter()
+            .any(|msg| msg.contains("endpoint Jahresmarktpraemie tripped")));
+
+        // During the cooldown the scan fails without fetching, instead of
+        // returning the endpoint's rows as missing
+        let err = run_scan(&mut fdw, &annual(NOW + 100), |_| {
+            panic!("open endpoint must not fetch")
+        })
+        .unwrap_err();
+        assert!(
+            err.starts_with(
+                "Endpoint Jahresmarktpraemie not fetched: circuit breaker open for 210s"
+            ),
+            "{}",
+            err
+        );
+
+        // Once the cooldown has passed the endpoint is fetched again
+        let body = "JW;7,946";
+        let ScanOutput { rows, urls, .. } =
+            run_scan(&mut fdw, &annual(NOW + 310), |_| Ok(body.to_string())).unwrap();
+        assert_eq!(urls.len(), 1);
+        assert_eq!(rows.len(), 1);
+    }
+
+    #[test]
+    fn test_scan_metrics_accumulate_across_scans() {
+        let spot = || {
+            FakeContext::new("electricity_market_prices")
+                .qual("price_type", "=", Cell::String("spot_market".to_string()))
+                .columns(&["price_eur_mwh"])
+        };
+        let metrics = || {
+            FakeContext::new("scan_metrics").columns(&[
+                "scans",
+                "requests",
+                "bytes_received",
+                "retries",
+                "tokens_fetched",
+            ])
+        };
+
+        let mut fdw = test_fdw();
+        let body = "Datum;von;Zeitzone von;bis;Zeitzone bis;Spotmarktpreis in ct/kWh\n\
+                    24.10.2024;00:00;UTC;01:00;UTC;8,273\n";
+        run_scan(&mut fdw, &spot(), |_| Ok(body.to_string())).unwrap();
+        assert!(run_scan(&mut fdw, &spot(), |_| Err("HTTP 500".to_string())).is_err());
+        fdw.metrics.record_retry();
+
+        // Reading the counters makes no API request and is not counted as a scan
+        let ScanOutput { rows, urls, .. } = run_scan(&mut fdw, &metrics(), |_| {
+            panic!("scan_metrics must not fetch")
+        })
+        .unwrap();
+        assert!(urls.is_empty());
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            render(&rows[0]),
+            vec![
+                "Cell::I64(2)",
+                "Cell::I64(2)",
+                format!("Cell::I64({})", body.len()).as_str(),
+                "Cell::I64(1)",
+                "Cell::I64(0)",
+            ]
+        );
+
+        // Counters persist on the instance and keep growing
+        run_scan(&mut fdw, &spot(), |_| Ok(String::new())).unwrap();
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &metrics(), |_| unreachable!()).unwrap();
+        assert_eq!(render(&rows[0])[..2], ["Cell::I64(3)", "Cell::I64(3)"]);
+    }
+
+    fn token_fdw() -> NtpFdw {
+        NtpFdw {
+            oauth2_manager: Some(OAuth2Manager::new(OAuth2Config {
+                token_url: "https://identity.netztransparenz.de/users/connect/token".to_string(),
+                client_id: "client".to_string(),
+                client_secret: "secret".to_string(),
+                scope: "ntpStatistic.read_all_public".to_string(),
+            })),
+            headers: RefCell::new(vec![(
+                "authorization".to_string(),
+                "Bearer old".to_string(),
+            )]),
+            ..test_fdw()
+        }
+    }
+
+    #[test]
+    fn test_refresh_token_fetches_new_token() {
+        let ctx = FakeContext::new("refresh_token")
+            .columns(&["refreshed_at", "expires_at", "expires_in_seconds", "token"])
+            .token_response(
+                200,
+                r#"{"access_token": "fresh", "expires_in": 3600, "scope": "ntpStatistic.read_all_public"}"#,
+            );
+        let mut fdw = token_fdw();
+
+        let ScanOutput { rows, urls, .. } = run_scan(&mut fdw, &ctx, |_| {
+            panic!("refresh_token must not fetch data")
+        })
+        .unwrap();
+
+        // One token request, no data request; the token itself is never a cell
+        assert!(urls.is_empty());
+        let posts = ctx.posts();
+        assert_eq!(posts.len(), 1);
+        assert_eq!(
+            posts[0].0,
+            "https://identity.netztransparenz.de/users/connect/token"
+        );
+        assert!(posts[0].1.starts_with("grant_type=client_credentials&"));
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            render(&rows[0]),
+            vec![
+                "Cell::Timestamptz(1749988800000000)",
+                "Cell::Timestamptz(1749992400000000)",
+                "Cell::I64(3600)",
+                "NULL",
+            ]
+        );
+        assert!(ctx
+            .log()
+            .contains(&"refresh_token: new token expires in 3600 s".to_string()));
+
+        // Later data requests use the new token
+        assert_eq!(
+            *fdw.headers.borrow(),
+            vec![("authorization".to_string(), "Bearer fresh".to_string())]
+        );
+        let stats = fdw.oauth2_manager.as_ref().unwrap().token_stats();
+        assert_eq!(stats.tokens_fetched, 1);
+
+        // Every scan refreshes again, even with a valid cached token
+        run_scan(&mut fdw, &ctx, |_| unreachable!()).unwrap();
+        assert_eq!(ctx.posts().len(), 2);
+        let stats = fdw.oauth2_manager.as_ref().unwrap().token_stats();
+        assert_eq!((stats.cache_hits, stats.tokens_fetched), (0, 2));
+    }
+
+    #[test]
+    fn test_refresh_token_failure() {
+        let mut fdw = token_fdw();
+
+        let ctx = FakeContext::new("refresh_token").columns(&["expires_at"]);
+        let err = run_scan(&mut fdw, &ctx, |_| unreachable!()).unwrap_err();
+        assert!(
+            err.starts_with("Failed to refresh OAuth2 token:")
+                && err.contains("connection refused"),
+            "{}",
+            err
+        );
+
+        let ctx = ctx.token_response(401, "");
+        assert!(run_scan(&mut fdw, &ctx, |_| unreachable!()).is_err());
+        assert_eq!(
+            *fdw.headers.borrow(),
+            vec![("authorization".to_string(), "Bearer old".to_string())]
+        );
+
+        // Dry runs fetch nothing
+        let mut fdw = NtpFdw {
+            dry_run: true,
+            ..token_fdw()
+        };
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &ctx, |_| unreachable!()).unwrap();
+        assert!(rows.is_empty());
+        assert_eq!(ctx.posts().len(), 2);
+    }
+
+    #[test]
+    fn test_each_response_is_parsed_before_the_next_request() {
+        let ctx = FakeContext::new("renewable_energy_timeseries")
+            .qual(
+                "data_category",
+                "=",
+                Cell::String("extrapolation".to_string()),
+            )
+            .columns(&["timestamp_utc"]);
+
+        // The first product's body is malformed: no further endpoint is fetched
+        let mut fdw = test_fdw();
+        let mut requests = 0;
+        let err = run_scan(&mut fdw, &ctx, |_| {
+            requests += 1;
+            Ok("not a csv".to_string())
+        })
+        .unwrap_err();
+
+        assert!(err.contains("Failed to parse renewable CSV"), "{}", err);
+        assert_eq!(requests, 1);
+    }
+
+    #[test]
+    fn test_re_scan_before_begin_scan() {
+        // Freshly initialized instance that never scanned
+        let mut fdw = test_fdw();
+        fdw.rewind();
+
+        assert_eq!(fdw.renewable_rows.position(), 0);
+        assert_eq!(fdw.price_rows.position(), 0);
+        assert!(fdw.renewable_rows.rows().is_empty());
+
+        // A subsequent scan works normally
+        let ctx = FakeContext::new("electricity_market_prices")
+            .qual("price_type", "=", Cell::String("spot_market".to_string()))
+            .columns(&["price_eur_mwh"]);
+        let ScanOutput { rows, .. } = run_scan(&mut fdw, &ctx, |_| Ok(String::new())).unwrap();
+        assert!(rows.is_empty());
+    }
+
+    /// Row sink that panics on the first cell, standing in for a converter bug
+    struct PanickingRow;
+
+    impl RowSink for PanickingRow {
+        fn push_cell(&self, _cell: Option<&Cell>) {
+            panic!("injected converter panic");
+        }
+    }
+
+    #[test]
+    fn test_iter_scan_panic_surfaces_as_error() {
+        let ctx = FakeContext::new("renewable_energy_timeseries")
+            .qual("product_type", "=", Cell::String("solar".to_string()))
+            .qual(
+                "data_category",
+                "=",
+                Cell::String("extrapolation".to_string()),
+            )
+            .columns(&["tso_tennet_mw"]);
+        let csv = "Datum;von;Zeitzone von;bis;Zeitzone bis;50Hertz (MW);Amprion (MW);TenneT TSO (MW);TransnetBW (MW)\n\
+                   2024-10-24;06:00;UTC;06:15;UTC;100;200;300;150";
+
+        let mut fdw = test_fdw();
+        let scan = fdw.load_scan(&ctx, |_, _| Ok(csv.to_string())).unwrap();
+        fdw.store_scan(scan);
+
+        let err = catch_panic("iter_scan", || fdw.next_row(&ctx, &PanickingRow)).unwrap_err();
+
+        assert_eq!(err, "iter_scan panicked: injected converter panic");
+    }
+
+    #[test]
+    fn test_begin_scan_panic_surfaces_as_error() {
+        let ctx = FakeContext::new("electricity_market_prices").columns(&["price_eur_mwh"]);
+
+        let mut fdw = test_fdw();
+        let err = catch_panic("begin_scan", || {
+            run_scan(&mut fdw, &ctx, |plan| {
+                panic!("injected panic fetching {}", plan.endpoint)
+            })
+        })
+        .unwrap_err();
+
+        assert!(err.starts_with("begin_scan panicked: injected panic fetching "));
+    }
+
+    #[test]
+    fn test_this_mut_before_init_is_error() {
+        // No test calls `init`, so the singleton is never created
+        let err = NtpFdw::this_mut().err().unwrap();
+
+        assert!(err.contains("not initialized"));
+    }
+
+    #[test]
+    fn test_dry_run_scan_returns_no_rows() {
+        let ctx = FakeContext::new("electricity_market_prices").columns(&["price_eur_mwh"]);
+
+        let mut fdw = NtpFdw {
+            dry_run: true,
+            ..test_fdw()
+        };
+        let ScanOutput { rows, urls, .. } =
+            run_scan(&mut fdw, &ctx, |_| panic!("dry run must not fetch")).unwrap();
+
+        assert!(rows.is_empty());
+        assert!(urls.is_empty());
+        assert!(ctx.log().iter().any(|msg| msg.starts_with("dry_run: ")));
+    }
+
+    #[test]
+    fn test_dry_run_reports_the_plans_a_scan_fetches() {
+        // No price_type: one request per price endpoint
+        let ctx = FakeContext::new("electricity_market_prices")
+            .qual(
+                "timestamp_utc",
+                ">=",
+                Cell::String("2023-12-30".to_string()),
+            )
+            .qual("timestamp_utc", "<", Cell::String("2024-01-02".to_string()))
+            .columns(&["price_eur_mwh"]);
+
+        let mut fetched = Vec::new();
+        run_scan(&mut test_fdw(), &ctx, |plan| {
+            fetched.push(format!("dry_run: {} (not fetched)", plan));
+            Ok(String::new())
+        })
+        .unwrap();
+
+        let dry_ctx = FakeContext::new("electricity_market_prices")
+            .qual(
+                "timestamp_utc",
+                ">=",
+                Cell::String("2023-12-30".to_string()),
+            )
+            .qual("timestamp_utc", "<", Cell::String("2024-01-02".to_string()))
+            .columns(&["price_eur_mwh"]);
+        let mut fdw = NtpFdw {
+            dry_run: true,
+            ..test_fdw()
+        };
+        run_scan(&mut fdw, &dry_ctx, |_| panic!("dry run must not fetch")).unwrap();
+        let reported: Vec<String> = dry_ctx
+            .log()
+            .into_iter()
+            .filter(|msg| msg.starts_with("dry_run: "))
+            .collect();
+
+        assert_eq!(fetched.len(), 4);
+        assert_eq!(reported, fetched);
+    }
+}
@@ -0,0 +1,233 @@
+//! Non-fatal warnings of the last scan, behind the `scan_warnings` table
+//!
+//! Lenient server options change results without failing the scan: a CSV
+//! without `Zeitzone` columns is read as UTC (`assume_utc_when_missing`),
+//! unknown grid statuses and energy types are passed through as-is
+//! (`grid_status_lenient`, `energy_type_strict 'false'`), endpoints with an
+//! open circuit breaker return no rows, and responses beyond
+//! `max_rows_per_endpoint` are truncated. Each data scan collects these as
+//! [`ScanWarning`]s, logs a one-line summary, and keeps them on the `NtpFdw`
+//! singleton until the next data scan. Reading them is a scan of the
+//! `scan_warnings` foreign table, which the query router answers without any
+//! API request (like `scan_metrics`).
+
+use std::fmt;
+
+use crate::query_router::QueryPlan;
+
+/// `table` option value of the warnings foreign table
+pub(crate) const TABLE_NAME: &str = "scan_warnings";
+
+/// What lenient behavior a warning records
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WarningKind {
+    /// Response without `Zeitzone` columns, times read as UTC
+    AssumedUtc,
+    /// Unknown TrafficLight status kept as-is
+    #[cfg_attr(not(feature = "grid"), allow(dead_code))]
+    UnknownGridStatus,
+    /// Unknown Primärenergieart kept as-is
+    #[cfg_attr(not(feature = "grid"), allow(dead_code))]
+    UnknownEnergyType,
+    /// Response rows beyond `max_rows_per_endpoint` dropped
+    TruncatedEndpoint,
+}
+
+impl fmt::Display for WarningKind {
+    /// Value of the `kind` column
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::AssumedUtc => "assumed_utc",
+            Self::UnknownGridStatus => "unknown_grid_status",
+            Self::UnknownEnergyType => "unknown_energy_type",
+            Self::TruncatedEndpoint => "truncated_endpoint",
+        })
+    }
+}
+
+/// One row of the `scan_warnings` table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ScanWarning {
+    pub(crate) kind: WarningKind,
+    pub(crate) message: String,
+    /// Request the warning came from, in the rows' `source_endpoint` format
+    pub(crate) source_endpoint: String,
+    /// Rows affected (the dropped rows for truncated endpoints)
+    pub(crate) row_count: u64,
+}
+
+impl ScanWarning {
+    /// Warning for the response of `plan`
+    pub(crate) fn new(
+        kind: WarningKind,
+        plan: &QueryPlan,
+        message: String,
+        row_count: usize,
+    ) -> Self {
+        let source_endpoint = match &plan.product {
+            Some(product) => format!(
+                "{}/{}/{}/{}",
+                plan.endpoint, product, plan.date_from, plan.date_to
+            ),
+            None => format!("{}/{}/{}", plan.endpoint, plan.date_from, plan.date_to),
+        };
+        Self {
+            kind,
+            message,
+            source_endpoint,
+            row_count: row_count as u64,
+        }
+    }
+}
+
+/// Warnings of one value kept as-is, one per distinct value in first-seen order
+///
+/// `values` are the values of the parsed rows; `known` tells the normalized
+/// ones apart.
+#[cfg_attr(not(feature = "grid"), allow(dead_code))]
+pub(crate) fn unknown_value_warnings<'a>(
+    kind: WarningKind,
+    plan: &QueryPlan,
+    column: &str,
+    values: impl Iterator<Item = &'a str>,
+    known: impl Fn(&str) -> bool,
+) -> Vec<ScanWarning> {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for value in values.filter(|value| !known(value)) {
+        match counts.iter_mut().find(|(seen, _)| *seen == value) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((value, 1)),
+        }
+    }
+
+    counts
+        .into_iter()
+        .map(|(value, count)| {
+            ScanWarning::new(
+                kind,
+                plan,
+                format!("unknown {} '{}' kept as-is", column, value),
+                count,
+            )
+        })
+        .collect()
+}
+
+/// One-line summary for the scan log, `None` without warnings
+///
+/// E.g. `scan_warnings: 3 warnings (2 assumed_utc, 1 truncated_endpoint), see
+/// the scan_warnings table`.
+pub(crate) fn summary(warnings: &[ScanWarning]) -> Option<String> {
+    if warnings.is_empty() {
+        return None;
+    }
+
+    let mut kinds: Vec<(WarningKind, usize)> = Vec::new();
+    for warning in warnings {
+        match kinds.iter_mut().find(|(kind, _)| *kind == warning.kind) {
+            Some((_, count)) => *count += 1,
+            None => kinds.push((warning.kind, 1)),
+        }
+    }
+    let kinds: Vec<String> = kinds
+        .iter()
+        .map(|(kind, count)| format!("{} {}", count, kind))
+        .collect();
+
+    Some(format!(
+        "scan_warnings: {} warning{} ({}), see the {} table",
+        warnings.len(),
+        if warnings.len() == 1 { "" } else { "s" },
+        kinds.join(", "),
+        TABLE_NAME
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(endpoint: &str, product: Option<&str>) -> QueryPlan {
+        QueryPlan {
+            endpoint: endpoint.to_string(),
+            product: product.map(str::to_string),
+            date_from: "2024-10-24".to_string(),
+            date_to: "2024-10-25".to_string(),
+            api_url: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_source_endpoint_format() {
+        let warning = ScanWarning::new(
+            WarningKind::AssumedUtc,
+            &plan("hochrechnung", Some("Solar")),
+            String::new(),
+            96,
+        );
+        assert_eq!(
+            warning.source_endpoint,
+            "hochrechnung/Solar/2024-10-24/2024-10-25"
+        );
+
+        let warning = ScanWarning::new(
+            WarningKind::TruncatedEndpoint,
+            &plan("Spotmarktpreise", None),
+            String::new(),
+            24,
+        );
+        assert_eq!(
+            warning.source_endpoint,
+            "Spotmarktpreise/2024-10-24/2024-10-25"
+        );
+    }
+
+    #[test]
+    fn test_unknown_value_warnings_group_by_value() {
+        let warnings = unknown_value_warnings(
+            WarningKind::UnknownGridStatus,
+            &plan("TrafficLight", None),
+            "grid_status",
+            ["GREEN", "BLUE", "GREEN", "BLUE", "PURPLE"].into_iter(),
+            |value| value == "GREEN",
+        );
+
+        let rendered: Vec<(&str, u64)> = warnings
+            .iter()
+            .map(|w| (w.message.as_str(), w.row_count))
+            .collect();
+        assert_eq!(
+            rendered,
+            vec![
+                ("unknown grid_status 'BLUE' kept as-is", 2),
+                ("unknown grid_status 'PURPLE' kept as-is", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_summary() {
+        assert_eq!(summary(&[]), None);
+
+        let assumed = ScanWarning::new(
+            WarningKind::AssumedUtc,
+            &plan("hochrechnung", Some("Solar")),
+            String::new(),
+            96,
+        );
+        let truncated = ScanWarning::new(
+            WarningKind::TruncatedEndpoint,
+            &plan("hochrechnung", Some("Wind")),
+            String::new(),
+            12,
+        );
+        assert_eq!(
+            summary(std::slice::from_ref(&assumed)).unwrap(),
+            "scan_warnings: 1 warning (1 assumed_utc), see the scan_warnings table"
+        );
+        assert_eq!(
+            summary(&[assumed.clone(), truncated, assumed]).unwrap(),
+            "scan_warnings: 3 warnings (2 assumed_utc, 1 truncated_endpoint), see the scan_warnings table"
+        );
+    }
+}
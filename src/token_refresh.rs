@@ -0,0 +1,23 @@
+//! On-demand OAuth2 token refresh behind the `refresh_token` table
+//!
+//! The [`OAuth2Manager`] refreshes tokens on its own: proactively five minutes
+//! before expiry and reactively after a 401. After rotating the client secret
+//! or when debugging the identity provider, an operator wants a new token now
+//! instead. A scan of the `refresh_token` foreign table drops the cached token
+//! and fetches a new one; the query router answers it without any data API
+//! request (like `scan_metrics`). The single row reports when the token was
+//! fetched and when it expires, never the token itself.
+//!
+//! [`OAuth2Manager`]: crate::oauth2::OAuth2Manager
+
+/// `table` option value of the token refresh foreign table
+pub(crate) const TABLE_NAME: &str = "refresh_token";
+
+/// The single row of the `refresh_token` table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TokenRefreshRow {
+    /// Host clock when the new token was fetched (seconds since epoch)
+    pub(crate) refreshed_at: i64,
+    /// Expiry of the new token (seconds since epoch)
+    pub(crate) expires_at: i64,
+}
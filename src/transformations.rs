@@ -6,7 +6,7 @@
 //! # Transformations
 //!
 //! 1. **German decimal conversion**: `"119,5"` → `119.5`
-//! 2. **"N.A." → NULL mapping**: `"N.A."` → `None`
+//! 2. **"N.A." → NULL mapping**: `"N.A."`, `"k.A."`, `"-"`, ... (plus server option `null_sentinels`) → `None`
 //! 3. **Timestamp normalization**: `"23.10.2024" + "22:00"` → `"2024-10-23T22:00:00Z"`
 //! 4. **Interval calculation**: `(start, end)` → `15` minutes
 //! 5. **TSO zone flattening**: 4 CSV columns → 4 Option<f64> values
@@ -35,7 +35,7 @@
 //! ```
 
 use crate::error::ParseError;
-use chrono::{DateTime, Duration, NaiveDate, NaiveTime};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime};
 
 /// Helper struct for TSO zone data
 ///
@@ -60,6 +60,12 @@ pub struct TsoZones {
 ///
 /// Converts German decimal notation (comma) to standard notation (period) and parses to f64.
 ///
+/// Only plain decimals are accepted: an optional leading sign, digits, and at most one
+/// decimal separator (`,` or `.`). Literals that `f64::parse` would otherwise accept
+/// ("inf", "NaN", "1e5") are rejected, as are digit separators like "1_000".
+/// Values that overflow to infinity are rejected as well, so a successful result is
+/// always finite.
+///
 /// # Arguments
 ///
 /// * `value` - String value with German decimal format (e.g., "119,5")
@@ -78,30 +84,61 @@ pub struct TsoZones {
 /// assert_eq!(parse_german_decimal("0,000").unwrap(), 0.0);
 /// assert!(parse_german_decimal("").is_err());
 /// assert!(parse_german_decimal("abc").is_err());
+/// assert!(parse_german_decimal("inf").is_err());
 /// ```
 pub fn parse_german_decimal(value: &str) -> Result<f64, ParseError> {
-    if value.trim().is_empty() {
+    if !is_plain_decimal(value) {
         return Err(ParseError::InvalidDecimal(value.to_string()));
     }
 
     value
         .replace(',', ".")
         .parse::<f64>()
-        .map_err(|_| ParseError::InvalidDecimal(value.to_string()))
+        .ok()
+        // Non-finite results (e.g. digit strings beyond f64::MAX) would reach
+        // PostgreSQL as infinite NUMERIC cells
+        .filter(|parsed| parsed.is_finite())
+        .ok_or_else(|| ParseError::InvalidDecimal(value.to_string()))
+}
+
+/// Check for `[+-]?digits[(,|.)digits]` with at least one digit overall
+fn is_plain_decimal(value: &str) -> bool {
+    let unsigned = value.strip_prefix(['+', '-']).unwrap_or(value);
+
+    let mut digits = 0;
+    let mut separators = 0;
+    for c in unsigned.chars() {
+        match c {
+            '0'..='9' => digits += 1,
+            ',' | '.' => separators += 1,
+            _ => return false,
+        }
+    }
+
+    digits > 0 && separators <= 1
 }
 
 // ============================================================================
 // Transformation 2: "N.A." → NULL Mapping
 // ============================================================================
 
+/// Built-in NULL sentinels for TSO zone values (compared case-insensitively)
+///
+/// "N.A." = Not Available, "N.E." = Nicht Erfasst (Not Recorded),
+/// "k.A." = keine Angabe (not specified), "n/v" = nicht verfügbar, "-" = no value.
+/// The server option `null_sentinels` adds to this list.
+pub const NULL_SENTINELS: [&str; 10] = [
+    "N.A.", "NA", "N.A", "N.E.", "NE", "N.E", "K.A.", "K.A", "N/V", "-",
+];
+
 /// Parse value with "N.A." and "N.E." handling and negative value validation
 ///
-/// Converts "N.A." (not available), "N.E." (Nicht Erfasst = not recorded), and empty strings
-/// to None, otherwise parses as decimal. Rejects negative values as they are physically
+/// Converts the [`NULL_SENTINELS`] (e.g. "N.A." = not available, "N.E." = Nicht Erfasst
+/// = not recorded) and empty strings to None, otherwise parses as decimal. Rejects negative values as they are physically
 /// impossible for electrical production (MW).
 ///
 /// **Note:** This function is specifically for TSO zone production values (MW).
-/// For price parsing (which allows negative values), use `parse_german_decimal` directly.
+/// For prices (which may be negative), use [`parse_price_value_with_sentinels`].
 ///
 /// # Arguments
 ///
@@ -109,7 +146,7 @@ pub fn parse_german_decimal(value: &str) -> Result<f64, ParseError> {
 ///
 /// # Returns
 ///
-/// * `Ok(None)` - For "N.A.", "N.E.", or empty strings (represents SQL NULL)
+/// * `Ok(None)` - For a NULL sentinel or empty string (represents SQL NULL)
 /// * `Ok(Some(f64))` - For valid non-negative numeric values
 /// * `Err(ParseError::InvalidDecimal)` - For invalid formats or negative values
 ///
@@ -123,26 +160,34 @@ pub fn parse_german_decimal(value: &str) -> Result<f64, ParseError> {
 /// assert_eq!(parse_value("   ").unwrap(), None);
 /// assert_eq!(parse_value("123,456").unwrap(), Some(123.456));
 /// assert_eq!(parse_value("0,000").unwrap(), Some(0.0));
+/// assert_eq!(parse_value("k.A.").unwrap(), None);
 /// assert!(parse_value("-100,5").is_err()); // Negative production values are invalid
 /// ```
 pub fn parse_value(value: &str) -> Result<Option<f64>, ParseError> {
-    let trimmed = value.trim();
+    parse_value_with_sentinels(value, &[])
+}
 
-    // Case-insensitive NULL variant matching (handles API format changes)
-    // "N.A." = Not Available, "N.E." = Nicht Erfasst (Not Recorded)
-    let upper = trimmed.to_uppercase();
-    if upper == "N.A."
-        || upper == "NA"
-        || upper == "N.A"
-        || upper == "N.E."
-        || upper == "NE"
-        || upper == "N.E"
-        || trimmed.is_empty()
-    {
+/// Parse value, treating `extra_sentinels` as NULL in addition to the built-ins
+///
+/// Used with the server option `null_sentinels`. Matching is case-insensitive
+/// and ignores surrounding whitespace, like the built-in [`NULL_SENTINELS`].
+///
+/// # Examples
+///
+/// ```
+/// # use supabase_fdw_ntp::transformations::parse_value_with_sentinels;
+/// let extra = vec!["keine Daten".to_string()];
+/// assert_eq!(parse_value_with_sentinels("KEINE DATEN", &extra).unwrap(), None);
+/// assert_eq!(parse_value_with_sentinels("N.A.", &extra).unwrap(), None);
+/// assert_eq!(parse_value_with_sentinels("12,5", &extra).unwrap(), Some(12.5));
+/// ```
+pub fn parse_value_with_sentinels(
+    value: &str,
+    extra_sentinels: &[String],
+) -> Result<Option<f64>, ParseError> {
+    let Some(parsed) = parse_price_value_with_sentinels(value, extra_sentinels)? else {
         return Ok(None);
-    }
-
-    let parsed = parse_german_decimal(trimmed)?;
+    };
 
     // Validate: electrical production (MW) cannot be negative
     if parsed < 0.0 {
@@ -155,22 +200,111 @@ pub fn parse_value(value: &str) -> Result<Option<f64>, ParseError> {
     Ok(Some(parsed))
 }
 
+/// Parse a price value with the same NULL handling, allowing negative values
+///
+/// # Examples
+///
+/// ```
+/// # use supabase_fdw_ntp::transformations::parse_price_value_with_sentinels;
+/// assert_eq!(parse_price_value_with_sentinels("N.E.", &[]).unwrap(), None);
+/// assert_eq!(parse_price_value_with_sentinels("-", &[]).unwrap(), None);
+/// assert_eq!(parse_price_value_with_sentinels("-0,201", &[]).unwrap(), Some(-0.201));
+/// ```
+pub fn parse_price_value_with_sentinels(
+    value: &str,
+    extra_sentinels: &[String],
+) -> Result<Option<f64>, ParseError> {
+    let trimmed = value.trim();
+
+    // Case-insensitive NULL variant matching (handles API format changes)
+    let upper = trimmed.to_uppercase();
+    if trimmed.is_empty()
+        || NULL_SENTINELS.contains(&upper.as_str())
+        || extra_sentinels
+            .iter()
+            .any(|sentinel| sentinel.trim().to_uppercase() == upper)
+    {
+        return Ok(None);
+    }
+
+    parse_german_decimal(trimmed).map(Some)
+}
+
 // ============================================================================
 // Transformation 3: Timestamp Normalization
 // ============================================================================
 
+/// Check for the `24:00` end-of-day notation (not a valid `NaiveTime`)
+fn is_end_of_day(zeit: &str) -> bool {
+    matches!(zeit.trim(), "24:00" | "24:00:00")
+}
+
+/// Parse a CSV time of day: `HH:MM`, or `HH:MM:SS` for higher-resolution feeds
+fn parse_time_of_day(zeit: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(zeit, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(zeit, "%H:%M:%S"))
+        .ok()
+}
+
+/// Last two-digit year mapped into the 2000s (`00`-`69` -> 2000-2069, `70`-`99` -> 1970-1999)
+const TWO_DIGIT_YEAR_PIVOT: i32 = 69;
+
+/// Parse a CSV date column
+///
+/// The primary formats are tried first, so the common case costs one parse:
+/// - German: DD.MM.YYYY (e.g., "23.10.2024")
+/// - ISO: YYYY-MM-DD (e.g., "2024-10-24")
+///
+/// Occasional exports use these fallbacks:
+/// - Slash: DD/MM/YYYY (e.g., "23/10/2024")
+/// - Two-digit year: DD.MM.YY (e.g., "23.10.24"), century per [`TWO_DIGIT_YEAR_PIVOT`]
+fn parse_csv_date(datum: &str) -> Option<NaiveDate> {
+    // chrono's %Y also accepts "24" as the year 0024; leave those to the
+    // two-digit fallback instead of returning a date two millennia off
+    let four_digit_year = |date: &NaiveDate| date.year() >= 1000;
+
+    NaiveDate::parse_from_str(datum, "%d.%m.%Y")
+        .ok()
+        .filter(four_digit_year)
+        .or_else(|| NaiveDate::parse_from_str(datum, "%Y-%m-%d").ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(datum, "%d/%m/%Y")
+                .ok()
+                .filter(four_digit_year)
+        })
+        .or_else(|| parse_two_digit_year_date(datum))
+}
+
+/// Parse DD.MM.YY, mapping the year into 1970-2069
+fn parse_two_digit_year_date(datum: &str) -> Option<NaiveDate> {
+    let (_, year) = datum.rsplit_once('.')?;
+    if year.len() != 2 {
+        return None;
+    }
+
+    let date = NaiveDate::parse_from_str(datum, "%d.%m.%Y").ok()?;
+    let century = if date.year() <= TWO_DIGIT_YEAR_PIVOT {
+        2000
+    } else {
+        1900
+    };
+    date.with_year(century + date.year())
+}
+
 /// Parse timestamp from German or ISO date format
 ///
 /// Handles both date formats:
 /// - German: DD.MM.YYYY (e.g., "23.10.2024")
 /// - ISO: YYYY-MM-DD (e.g., "2024-10-24")
 ///
+/// DD/MM/YYYY and DD.MM.YY are accepted as fallbacks (see `parse_csv_date`).
+///
 /// Combines date and time into ISO 8601 format with UTC timezone.
 ///
 /// # Arguments
 ///
 /// * `datum` - Date string (DD.MM.YYYY or YYYY-MM-DD)
-/// * `zeit` - Time string (HH:MM)
+/// * `zeit` - Time string (HH:MM or HH:MM:SS)
 /// * `timezone` - Timezone indicator (must be "UTC")
 ///
 /// # Returns
@@ -204,14 +338,13 @@ pub fn parse_timestamp(datum: &str, zeit: &str, timezone: &str) -> Result<String
         return Err(ParseError::InvalidTimezone(timezone.to_string()));
     }
 
-    // Try parsing German format (DD.MM.YYYY) first, then ISO format (YYYY-MM-DD)
-    let date = NaiveDate::parse_from_str(datum, "%d.%m.%Y")
-        .or_else(|_| NaiveDate::parse_from_str(datum, "%Y-%m-%d"))
-        .map_err(|_| ParseError::InvalidTimestamp(format!("{} {}", datum, zeit)))?;
+    // German (DD.MM.YYYY) first, then ISO (YYYY-MM-DD), then the fallbacks
+    let date = parse_csv_date(datum)
+        .ok_or_else(|| ParseError::InvalidTimestamp(format!("{} {}", datum, zeit)))?;
 
-    // Parse time (HH:MM)
-    let time = NaiveTime::parse_from_str(zeit, "%H:%M")
-        .map_err(|_| ParseError::InvalidTimestamp(format!("{} {}", datum, zeit)))?;
+    // Parse time (HH:MM, falling back to HH:MM:SS)
+    let time = parse_time_of_day(zeit)
+        .ok_or_else(|| ParseError::InvalidTimestamp(format!("{} {}", datum, zeit)))?;
 
     // Combine into UTC datetime
     let datetime = date.and_time(time).and_utc();
@@ -223,13 +356,15 @@ pub fn parse_timestamp(datum: &str, zeit: &str, timezone: &str) -> Result<String
 /// Parse interval timestamps with midnight-crossing detection (Bug #5 fix)
 ///
 /// Handles the case where interval end time is <= start time, which indicates
-/// the interval crosses midnight (e.g., 23:45 - 00:00).
+/// the interval crosses midnight (e.g., 23:45 - 00:00). An end time of
+/// "24:00" (end of day, used by some exports instead of "00:00") is always
+/// 00:00 of the next day.
 ///
 /// # Arguments
 ///
 /// * `datum` - Date string (DD.MM.YYYY or YYYY-MM-DD)
-/// * `von` - Start time (HH:MM)
-/// * `bis` - End time (HH:MM)
+/// * `von` - Start time (HH:MM or HH:MM:SS)
+/// * `bis` - End time (HH:MM or HH:MM:SS, or "24:00" for end of day)
 /// * `tz_von` - Start timezone (must be "UTC")
 /// * `tz_bis` - End timezone (must be "UTC")
 ///
@@ -271,30 +406,37 @@ pub fn parse_interval_timestamps(
         )));
     }
 
-    // Parse times to detect midnight crossing
-    let start_time = NaiveTime::parse_from_str(von, "%H:%M")
-        .map_err(|_| ParseError::InvalidTimestamp(format!("Invalid time: {}", von)))?;
-    let end_time = NaiveTime::parse_from_str(bis, "%H:%M")
-        .map_err(|_| ParseError::InvalidTimestamp(format!("Invalid time: {}", bis)))?;
+    // Parse times to detect midnight crossing (full NaiveTime, including seconds)
+    let start_time = parse_time_of_day(von)
+        .ok_or_else(|| ParseError::InvalidTimestamp(format!("Invalid time: {}", von)))?;
+    let end_of_day = is_end_of_day(bis);
+    let end_time = if end_of_day {
+        None
+    } else {
+        Some(
+            parse_time_of_day(bis)
+                .ok_or_else(|| ParseError::InvalidTimestamp(format!("Invalid time: {}", bis)))?,
+        )
+    };
 
-    // Parse base date (supports both German DD.MM.YYYY and ISO YYYY-MM-DD)
-    let base_date = NaiveDate::parse_from_str(datum, "%d.%m.%Y")
-        .or_else(|_| NaiveDate::parse_from_str(datum, "%Y-%m-%d"))
-        .map_err(|_| ParseError::InvalidTimestamp(format!("Invalid date: {}", datum)))?;
+    // Parse base date (German DD.MM.YYYY, ISO YYYY-MM-DD or a fallback format)
+    let base_date = parse_csv_date(datum)
+        .ok_or_else(|| ParseError::InvalidTimestamp(format!("Invalid date: {}", datum)))?;
 
     // Parse start timestamp (always uses base date)
     let start_timestamp = parse_timestamp(datum, von, tz_von)?;
 
-    // Detect midnight crossing: if end_time <= start_time, assume next day
-    let end_date = if end_time <= start_time {
-        base_date + Duration::days(1)
-    } else {
-        base_date
+    // "24:00" is 00:00 of the next day, whatever the start time. Otherwise
+    // detect midnight crossing: if end_time <= start_time, assume next day
+    let end_date = match end_time {
+        Some(end_time) if end_time > start_time => base_date,
+        _ => base_date + Duration::days(1),
     };
 
     // Format end date for parsing
     let end_date_str = end_date.format("%d.%m.%Y").to_string();
-    let end_timestamp = parse_timestamp(&end_date_str, bis, tz_bis)?;
+    let end_zeit = if end_of_day { "00:00" } else { bis };
+    let end_timestamp = parse_timestamp(&end_date_str, end_zeit, tz_bis)?;
 
     Ok((start_timestamp, end_timestamp))
 }
@@ -358,6 +500,46 @@ pub fn calculate_interval_minutes(start: &str, end: &str) -> Result<i16, ParseEr
     })
 }
 
+/// Calculate interval duration in minutes for long-running events
+///
+/// Widened variant of [`calculate_interval_minutes`] for redispatch events,
+/// which can span days or weeks and exceed the i16 range (~22.75 days).
+/// Renewable and price intervals keep using the i16 version.
+///
+/// # Arguments
+///
+/// * `start` - Start timestamp (ISO 8601 format)
+/// * `end` - End timestamp (ISO 8601 format)
+///
+/// # Returns
+///
+/// * `Ok(Some(i32))` - Duration in minutes
+/// * `Ok(None)` - Duration does not fit in i32 (surfaced as NULL)
+/// * `Err(ParseError::InvalidTimestamp)` - If timestamps cannot be parsed
+///
+/// # Examples
+///
+/// ```
+/// # use supabase_fdw_ntp::transformations::calculate_interval_minutes_wide;
+/// // 23-day redispatch event (exceeds i16)
+/// let minutes = calculate_interval_minutes_wide(
+///     "2024-10-01T00:00:00Z",
+///     "2024-10-24T00:00:00Z"
+/// ).unwrap();
+/// assert_eq!(minutes, Some(33_120));
+/// ```
+pub fn calculate_interval_minutes_wide(start: &str, end: &str) -> Result<Option<i32>, ParseError> {
+    let start_dt = DateTime::parse_from_rfc3339(start)
+        .map_err(|_| ParseError::InvalidTimestamp(start.to_string()))?;
+    let end_dt = DateTime::parse_from_rfc3339(end)
+        .map_err(|_| ParseError::InvalidTimestamp(end.to_string()))?;
+
+    let minutes = end_dt.signed_duration_since(start_dt).num_minutes();
+
+    // Overflow degrades to NULL instead of failing the whole scan
+    Ok(i32::try_from(minutes).ok())
+}
+
 // ============================================================================
 // Transformation 5: TSO Zone Flattening
 // ============================================================================
@@ -394,6 +576,14 @@ pub fn calculate_interval_minutes(start: &str, end: &str) -> Result<i16, ParseEr
 /// assert_eq!(zones.tso_transnetbw_mw, Some(150.2));
 /// ```
 pub fn parse_tso_zones(row_data: &[(&str, &str)]) -> Result<TsoZones, ParseError> {
+    parse_tso_zones_with_sentinels(row_data, &[])
+}
+
+/// Parse TSO zone values, treating `extra_sentinels` as NULL (see [`parse_value_with_sentinels`])
+pub fn parse_tso_zones_with_sentinels(
+    row_data: &[(&str, &str)],
+    extra_sentinels: &[String],
+) -> Result<TsoZones, ParseError> {
     let mut zones = TsoZones {
         tso_50hertz_mw: None,
         tso_amprion_mw: None,
@@ -403,10 +593,18 @@ pub fn parse_tso_zones(row_data: &[(&str, &str)]) -> Result<TsoZones, ParseError
 
     for (col_name, value) in row_data {
         match *col_name {
-            "50Hertz (MW)" => zones.tso_50hertz_mw = parse_value(value)?,
-            "Amprion (MW)" => zones.tso_amprion_mw = parse_value(value)?,
-            "TenneT TSO (MW)" => zones.tso_tennet_mw = parse_value(value)?,
-            "TransnetBW (MW)" => zones.tso_transnetbw_mw = parse_value(value)?,
+            "50Hertz (MW)" => {
+                zones.tso_50hertz_mw = parse_value_with_sentinels(value, extra_sentinels)?
+            }
+            "Amprion (MW)" => {
+                zones.tso_amprion_mw = parse_value_with_sentinels(value, extra_sentinels)?
+            }
+            "TenneT TSO (MW)" => {
+                zones.tso_tennet_mw = parse_value_with_sentinels(value, extra_sentinels)?
+            }
+            "TransnetBW (MW)" => {
+                zones.tso_transnetbw_mw = parse_value_with_sentinels(value, extra_sentinels)?
+            }
             _ => {} // Ignore other columns
         }
     }
@@ -619,19 +817,22 @@ pub fn build_source_endpoint(
 /// Parse redispatch timestamp from German format
 ///
 /// Handles German date format (DD.MM.YYYY) combined with 24-hour time (HH:MM).
-/// Validates timezone is UTC.
+/// Besides "UTC", exports label German local time as "MEZ" (CET) or "MESZ"
+/// (CEST); those are converted from Europe/Berlin with its DST rules. In the
+/// hour repeated when clocks go back, the label picks the occurrence.
 ///
 /// # Arguments
 ///
 /// * `datum` - Date in DD.MM.YYYY format (e.g., "23.10.2024")
 /// * `uhrzeit` - Time in HH:MM format (e.g., "22:00")
-/// * `zeitzone` - Timezone (must be "UTC")
+/// * `zeitzone` - Timezone ("UTC", "MEZ" or "MESZ")
 ///
 /// # Returns
 ///
 /// * `Ok(String)` - ISO 8601 timestamp (e.g., "2024-10-23T22:00:00Z")
-/// * `Err(ParseError::InvalidTimezone)` - If timezone is not "UTC"
-/// * `Err(ParseError::InvalidTimestamp)` - If date or time format is invalid
+/// * `Err(ParseError::InvalidTimezone)` - If timezone is not "UTC", "MEZ" or "MESZ"
+/// * `Err(ParseError::InvalidTimestamp)` - If date or time format is invalid,
+///   or the local time is skipped when clocks go forward
 ///
 /// # Examples
 ///
@@ -639,16 +840,23 @@ pub fn build_source_endpoint(
 /// # use supabase_fdw_ntp::transformations::parse_redispatch_timestamp;
 /// let dt = parse_redispatch_timestamp("23.10.2024", "22:00", "UTC").unwrap();
 /// assert_eq!(dt, "2024-10-23T22:00:00Z");
+///
+/// // German summer time is UTC+2
+/// let dt = parse_redispatch_timestamp("23.07.2024", "22:00", "MESZ").unwrap();
+/// assert_eq!(dt, "2024-07-23T20:00:00Z");
 /// ```
 pub fn parse_redispatch_timestamp(
     datum: &str,
     uhrzeit: &str,
     zeitzone: &str,
 ) -> Result<String, ParseError> {
-    // Validate timezone
-    if zeitzone != "UTC" {
-        return Err(ParseError::InvalidTimezone(zeitzone.to_string()));
-    }
+    // Validate timezone; MEZ/MESZ carry the UTC offset of their label
+    let label_offset_hours = match zeitzone {
+        "UTC" => None,
+        "MEZ" => Some(1),
+        "MESZ" => Some(2),
+        _ => return Err(ParseError::InvalidTimezone(zeitzone.to_string())),
+    };
 
     // Concatenate date and time
     let dt_string = format!("{} {}", datum, uhrzeit);
@@ -659,12 +867,45 @@ pub fn parse_redispatch_timestamp(
         .map_err(|_| ParseError::InvalidTimestamp(dt_string.clone()))?;
 
     // Convert to UTC DateTime
-    let utc_dt = naive_dt.and_utc();
+    let utc_dt = match label_offset_hours {
+        None => naive_dt.and_utc(),
+        Some(hours) => berlin_to_utc(naive_dt, hours)
+            .ok_or_else(|| ParseError::InvalidTimestamp(format!("{} {}", dt_string, zeitzone)))?,
+    };
 
     // Format as ISO 8601
     Ok(utc_dt.format("%Y-%m-%dT%H:%M:%SZ").to_string())
 }
 
+/// Convert a German local time (Europe/Berlin) to UTC
+///
+/// `label_offset_hours` (1 for MEZ, 2 for MESZ) only decides the repeated
+/// hour when clocks go back; otherwise the DST rules give the offset.
+///
+/// # Returns
+///
+/// `None` for local times skipped when clocks go forward (02:00-02:59 on the
+/// last Sunday of March).
+fn berlin_to_utc(
+    local: chrono::NaiveDateTime,
+    label_offset_hours: i32,
+) -> Option<chrono::DateTime<chrono::Utc>> {
+    use chrono::{LocalResult, Offset, TimeZone};
+
+    let instant = match chrono_tz::Europe::Berlin.from_local_datetime(&local) {
+        LocalResult::Single(instant) => instant,
+        LocalResult::Ambiguous(earliest, latest) => {
+            if earliest.offset().fix().local_minus_utc() == label_offset_hours * 3600 {
+                earliest
+            } else {
+                latest
+            }
+        }
+        LocalResult::None => return None,
+    };
+    Some(instant.with_timezone(&chrono::Utc))
+}
+
 /// Normalize German direction to English enum
 ///
 /// Maps German redispatch direction text to standardized English enum values.
@@ -704,6 +945,42 @@ pub fn normalize_direction(richtung: &str) -> Result<String, ParseError> {
     }
 }
 
+/// Normalize German primary energy type to English enum
+///
+/// Maps the redispatch `PRIMAERENERGIEART` text to standardized English enum values.
+///
+/// # Arguments
+///
+/// * `energieart` - German energy type text from CSV
+///
+/// # Returns
+///
+/// * `Ok(String)` - "conventional", "renewable" or "other"
+/// * `Err(ParseError::UnknownEnergyType)` - For unknown energy type values
+///
+/// # Mappings
+///
+/// - `"Konventionell"` → `"conventional"`
+/// - `"Erneuerbar"` → `"renewable"`
+/// - `"Sonstige"` / `"Sonstiges"` → `"other"`
+///
+/// # Examples
+///
+/// ```
+/// # use supabase_fdw_ntp::transformations::normalize_energy_type;
+/// assert_eq!(normalize_energy_type("Konventionell").unwrap(), "conventional");
+/// assert_eq!(normalize_energy_type("Erneuerbar").unwrap(), "renewable");
+/// assert!(normalize_energy_type("Kernkraft").is_err());
+/// ```
+pub fn normalize_energy_type(energieart: &str) -> Result<String, ParseError> {
+    match energieart {
+        "Konventionell" => Ok("conventional".to_string()),
+        "Erneuerbar" => Ok("renewable".to_string()),
+        "Sonstige" | "Sonstiges" => Ok("other".to_string()),
+        _ => Err(ParseError::UnknownEnergyType(energieart.to_string())),
+    }
+}
+
 /// Parse ISO 8601 timestamp
 ///
 /// Parses ISO 8601 timestamp strings (used by TrafficLight JSON endpoint).
@@ -809,7 +1086,7 @@ mod tests {
     use super::*;
 
     // ========================================================================
-    // Tests for parse_german_decimal (4 tests)
+    // Tests for parse_german_decimal
     // ========================================================================
 
     #[test]
@@ -838,6 +1115,88 @@ mod tests {
         assert!(parse_german_decimal("   ").is_err());
     }
 
+    #[test]
+    fn test_german_decimal_rejects_float_literals() {
+        assert!(parse_german_decimal("inf").is_err());
+        assert!(parse_german_decimal("-inf").is_err());
+        assert!(parse_german_decimal("infinity").is_err());
+        assert!(parse_german_decimal("NaN").is_err());
+        assert!(parse_german_decimal("1e5").is_err());
+    }
+
+    #[test]
+    fn test_german_decimal_rejects_non_finite() {
+        assert!(matches!(
+            parse_german_decimal("inf"),
+            Err(ParseError::InvalidDecimal(_))
+        ));
+        assert!(matches!(
+            parse_german_decimal("-inf"),
+            Err(ParseError::InvalidDecimal(_))
+        ));
+        assert!(matches!(
+            parse_german_decimal("NaN"),
+            Err(ParseError::InvalidDecimal(_))
+        ));
+
+        // 400 digits overflow f64 to infinity
+        let overflow = "9".repeat(400);
+        assert!(matches!(
+            parse_german_decimal(&overflow),
+            Err(ParseError::InvalidDecimal(_))
+        ));
+        assert!(parse_german_decimal(&format!("-{},5", overflow)).is_err());
+    }
+
+    #[test]
+    fn test_german_decimal_rejects_malformed_numbers() {
+        assert!(parse_german_decimal("1_000").is_err());
+        assert!(parse_german_decimal("1,000,5").is_err());
+        assert!(parse_german_decimal("1,000.5").is_err());
+        assert!(parse_german_decimal("--1").is_err());
+        assert!(parse_german_decimal("1-").is_err());
+        assert!(parse_german_decimal("+").is_err());
+        assert!(parse_german_decimal(",").is_err());
+        assert!(parse_german_decimal(" 1,5").is_err());
+    }
+
+    #[test]
+    fn test_german_decimal_signs_and_bare_separator() {
+        assert_eq!(parse_german_decimal("-12,5").unwrap(), -12.5);
+        assert_eq!(parse_german_decimal("+12,5").unwrap(), 12.5);
+        assert_eq!(parse_german_decimal(",5").unwrap(), 0.5);
+        assert_eq!(parse_german_decimal("5,").unwrap(), 5.0);
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_german_decimal_never_panics(input in "\\PC*") {
+            if let Ok(parsed) = parse_german_decimal(&input) {
+                proptest::prop_assert!(parsed.is_finite());
+            }
+        }
+
+        #[test]
+        fn prop_german_decimal_ok_only_for_plain_decimals(input in "[-+0-9,._a-zA-Z ]{0,12}") {
+            if parse_german_decimal(&input).is_ok() {
+                let unsigned = input.trim_start_matches(['+', '-']);
+                proptest::prop_assert!(unsigned.chars().all(|c| c.is_ascii_digit() || c == ',' || c == '.'));
+            }
+        }
+
+        #[test]
+        fn prop_german_decimal_accepts_german_numbers(
+            negative in proptest::bool::ANY,
+            integer in 0u32..1_000_000,
+            fraction in 0u32..1000,
+        ) {
+            let sign = if negative { "-" } else { "" };
+            let input = format!("{}{},{:03}", sign, integer, fraction);
+            let expected: f64 = format!("{}{}.{:03}", sign, integer, fraction).parse().unwrap();
+            proptest::prop_assert_eq!(parse_german_decimal(&input).unwrap(), expected);
+        }
+    }
+
     // ========================================================================
     // Tests for parse_value (4 tests)
     // ========================================================================
@@ -853,6 +1212,35 @@ mod tests {
         assert_eq!(parse_value("N.E.").unwrap(), None);
     }
 
+    #[test]
+    fn test_parse_value_german_sentinels() {
+        // "k.A." = keine Angabe, "n/v" = nicht verfügbar
+        assert_eq!(parse_value("k.A.").unwrap(), None);
+        assert_eq!(parse_value("K.A").unwrap(), None);
+        assert_eq!(parse_value("n/v").unwrap(), None);
+        assert_eq!(parse_value("-").unwrap(), None);
+        assert_eq!(parse_value(" - ").unwrap(), None);
+    }
+
+    #[test]
+    fn test_parse_value_custom_sentinels() {
+        let extra = vec!["x".to_string(), " keine Daten ".to_string()];
+
+        assert_eq!(parse_value_with_sentinels("X", &extra).unwrap(), None);
+        assert_eq!(
+            parse_value_with_sentinels("Keine Daten", &extra).unwrap(),
+            None
+        );
+        assert_eq!(parse_value_with_sentinels("N.E.", &extra).unwrap(), None);
+        assert_eq!(
+            parse_value_with_sentinels("1,5", &extra).unwrap(),
+            Some(1.5)
+        );
+
+        // Not a sentinel without the configuration
+        assert!(parse_value("x").is_err());
+    }
+
     #[test]
     fn test_parse_value_empty() {
         assert_eq!(parse_value("").unwrap(), None);
@@ -930,6 +1318,92 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_timestamp_with_seconds() {
+        assert_eq!(
+            parse_timestamp("2024-10-24", "06:00:15", "UTC").unwrap(),
+            "2024-10-24T06:00:15Z"
+        );
+        assert!(parse_timestamp("2024-10-24", "06:00:61", "UTC").is_err());
+    }
+
+    #[test]
+    fn test_timestamp_fallback_formats() {
+        assert_eq!(
+            parse_timestamp("23.10.24", "22:00", "UTC").unwrap(),
+            "2024-10-23T22:00:00Z"
+        );
+        assert_eq!(
+            parse_timestamp("23/10/2024", "22:00", "UTC").unwrap(),
+            "2024-10-23T22:00:00Z"
+        );
+
+        // Century rule: 00-69 -> 2000s, 70-99 -> 1900s
+        assert_eq!(
+            parse_timestamp("01.01.69", "00:00", "UTC").unwrap(),
+            "2069-01-01T00:00:00Z"
+        );
+        assert_eq!(
+            parse_timestamp("01.01.70", "00:00", "UTC").unwrap(),
+            "1970-01-01T00:00:00Z"
+        );
+
+        // Only the exact fallback shapes are accepted
+        assert!(parse_timestamp("23.10.024", "22:00", "UTC").is_err());
+        assert!(parse_timestamp("23/10/24", "22:00", "UTC").is_err());
+        assert!(parse_timestamp("32/10/2024", "22:00", "UTC").is_err());
+    }
+
+    #[test]
+    fn test_interval_timestamps_fallback_formats() {
+        let (start, end) =
+            parse_interval_timestamps("31.12.24", "23:45", "00:00", "UTC", "UTC").unwrap();
+        assert_eq!(start, "2024-12-31T23:45:00Z");
+        assert_eq!(end, "2025-01-01T00:00:00Z");
+
+        let (start, end) =
+            parse_interval_timestamps("23/10/2024", "10:00", "11:00", "UTC", "UTC").unwrap();
+        assert_eq!(start, "2024-10-23T10:00:00Z");
+        assert_eq!(end, "2024-10-23T11:00:00Z");
+    }
+
+    #[test]
+    fn test_interval_timestamps_end_of_day() {
+        // Last quarter-hour of the day
+        let (start, end) =
+            parse_interval_timestamps("24.10.2024", "23:45", "24:00", "UTC", "UTC").unwrap();
+        assert_eq!(start, "2024-10-24T23:45:00Z");
+        assert_eq!(end, "2024-10-25T00:00:00Z");
+
+        // Whole-day interval: "24:00" after "00:00" is the next day, not the same instant
+        let (start, end) =
+            parse_interval_timestamps("31.12.2024", "00:00", "24:00:00", "UTC", "UTC").unwrap();
+        assert_eq!(start, "2024-12-31T00:00:00Z");
+        assert_eq!(end, "2025-01-01T00:00:00Z");
+
+        // "24:00" is only valid as the end time
+        assert!(parse_interval_timestamps("24.10.2024", "24:00", "00:15", "UTC", "UTC").is_err());
+        assert!(parse_interval_timestamps("24.10.2024", "23:45", "24:15", "UTC", "UTC").is_err());
+    }
+
+    #[test]
+    fn test_interval_timestamps_with_seconds() {
+        let (start, end) =
+            parse_interval_timestamps("24.10.2024", "06:00:15", "06:15:15", "UTC", "UTC").unwrap();
+        assert_eq!(start, "2024-10-24T06:00:15Z");
+        assert_eq!(end, "2024-10-24T06:15:15Z");
+
+        // Mixed precision: end only differs from start by seconds (same day)
+        let (_, end) =
+            parse_interval_timestamps("24.10.2024", "06:00", "06:00:15", "UTC", "UTC").unwrap();
+        assert_eq!(end, "2024-10-24T06:00:15Z");
+
+        // Midnight crossing compares full times including seconds
+        let (_, end) =
+            parse_interval_timestamps("24.10.2024", "23:59:45", "00:00:00", "UTC", "UTC").unwrap();
+        assert_eq!(end, "2024-10-25T00:00:00Z");
+    }
+
     // ========================================================================
     // Tests for calculate_interval_minutes (3 tests)
     // ========================================================================
@@ -955,6 +1429,31 @@ mod tests {
         assert_eq!(minutes, 15);
     }
 
+    #[test]
+    fn test_interval_wide_multi_week() {
+        // 23 days = 33,120 minutes, beyond i16::MAX
+        assert!(
+            calculate_interval_minutes("2024-10-01T00:00:00Z", "2024-10-24T00:00:00Z").is_err()
+        );
+        let minutes =
+            calculate_interval_minutes_wide("2024-10-01T00:00:00Z", "2024-10-24T00:00:00Z")
+                .unwrap();
+        assert_eq!(minutes, Some(33_120));
+    }
+
+    #[test]
+    fn test_interval_wide_overflow_is_none() {
+        let minutes =
+            calculate_interval_minutes_wide("0001-01-01T00:00:00Z", "9999-12-31T00:00:00Z")
+                .unwrap();
+        assert_eq!(minutes, None);
+    }
+
+    #[test]
+    fn test_interval_wide_invalid_timestamp() {
+        assert!(calculate_interval_minutes_wide("invalid", "2024-10-24T00:00:00Z").is_err());
+    }
+
     // ========================================================================
     // Tests for parse_tso_zones (3 tests)
     // ========================================================================
@@ -1139,6 +1638,35 @@ mod tests {
         assert_eq!(dt, "2024-10-24T00:00:00Z");
     }
 
+    #[test]
+    fn test_parse_redispatch_timestamp_mez_winter() {
+        // CET is UTC+1
+        let dt = parse_redispatch_timestamp("02.12.2024", "08:00", "MEZ").unwrap();
+        assert_eq!(dt, "2024-12-02T07:00:00Z");
+    }
+
+    #[test]
+    fn test_parse_redispatch_timestamp_mesz_summer() {
+        // CEST is UTC+2, also across midnight
+        let dt = parse_redispatch_timestamp("15.07.2024", "08:00", "MESZ").unwrap();
+        assert_eq!(dt, "2024-07-15T06:00:00Z");
+        let dt = parse_redispatch_timestamp("16.07.2024", "01:00", "MESZ").unwrap();
+        assert_eq!(dt, "2024-07-15T23:00:00Z");
+    }
+
+    #[test]
+    fn test_parse_redispatch_timestamp_dst_transitions() {
+        // 27.10.2024 02:30 happens twice; the label picks the occurrence
+        let dt = parse_redispatch_timestamp("27.10.2024", "02:30", "MESZ").unwrap();
+        assert_eq!(dt, "2024-10-27T00:30:00Z");
+        let dt = parse_redispatch_timestamp("27.10.2024", "02:30", "MEZ").unwrap();
+        assert_eq!(dt, "2024-10-27T01:30:00Z");
+
+        // 31.03.2024 02:30 doesn't exist in German local time
+        let result = parse_redispatch_timestamp("31.03.2024", "02:30", "MEZ");
+        assert!(matches!(result, Err(ParseError::InvalidTimestamp(_))));
+    }
+
     #[test]
     fn test_parse_redispatch_timestamp_invalid_timezone() {
         let result = parse_redispatch_timestamp("23.10.2024", "22:00", "CET");
@@ -1169,6 +1697,26 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_normalize_energy_type_known() {
+        assert_eq!(
+            normalize_energy_type("Konventionell").unwrap(),
+            "conventional"
+        );
+        assert_eq!(normalize_energy_type("Erneuerbar").unwrap(), "renewable");
+        assert_eq!(normalize_energy_type("Sonstige").unwrap(), "other");
+        assert_eq!(normalize_energy_type("Sonstiges").unwrap(), "other");
+    }
+
+    #[test]
+    fn test_normalize_energy_type_unknown() {
+        let result = normalize_energy_type("Kernkraft");
+        assert!(matches!(
+            result,
+            Err(ParseError::UnknownEnergyType(value)) if value == "Kernkraft"
+        ));
+    }
+
     #[test]
     fn test_parse_iso8601_timestamp_valid() {
         let dt = parse_iso8601_timestamp("2024-10-24T00:00:00Z").unwrap();
@@ -2,6 +2,12 @@
 //!
 //! These structs represent parsed rows from the NTP API, ready for conversion
 //! to PostgreSQL Cell types.
+//!
+//! Fields that are the same for every row of one response (product, category,
+//! price type, source endpoint) are `Rc<str>`: the parsers allocate them once
+//! per response and each row holds a reference, instead of a copy per row.
+
+use std::rc::Rc;
 
 /// Represents one row from renewable energy endpoints
 ///
@@ -30,12 +36,16 @@ pub struct RenewableRow {
     /// - "solar" (from API "Solar")
     /// - "wind_onshore" (from API "Wind" or "Windonshore")
     /// - "wind_offshore" (from API "Windoffshore")
-    pub product_type: String,
+    pub product_type: Rc<str>,
+
+    /// Product name as passed to the API, before normalization
+    /// - "Solar", "Wind", "Windonshore" or "Windoffshore"
+    pub api_product: Rc<str>,
 
     /// Data category (mapped from endpoint)
     /// - "extrapolation" (from hochrechnung)
     /// - "online_actual" (from onlinehochrechnung)
-    pub data_category: String,
+    pub data_category: Rc<str>,
 
     /// 50Hertz TSO zone production in MW
     /// None represents "N.A." values from API (missing/nighttime data)
@@ -52,7 +62,11 @@ pub struct RenewableRow {
 
     /// Source API endpoint path for traceability
     /// Example: "hochrechnung/Solar/2024-10-24/2024-10-25"
-    pub source_endpoint: String,
+    pub source_endpoint: Rc<str>,
+
+    /// Original CSV record, fields joined with `;` (`_raw` column)
+    /// None unless the `expose_raw` server option is enabled
+    pub raw: Option<String>,
 }
 
 /// Represents one row from electricity price endpoints
@@ -82,14 +96,14 @@ pub struct PriceRow {
     /// - "hourly" (spot market)
     /// - "monthly" (market premiums)
     /// - "annual" (annual market values)
-    pub granularity: String,
+    pub granularity: Rc<str>,
 
     /// Type of price data
     /// - "spot_market" (Spotmarktpreise)
     /// - "market_premium" (marktpraemie)
     /// - "annual_market_value" (Jahresmarktpraemie)
     /// - "negative_flag" (NegativePreise - boolean flags only)
-    pub price_type: String,
+    pub price_type: Rc<str>,
 
     /// Price in EUR per MWh (standard unit)
     /// None for negative_flag records (no actual price)
@@ -105,23 +119,41 @@ pub struct PriceRow {
     /// - None for spot market prices
     pub product_category: Option<String>,
 
-    /// For NegativePreise records: duration of consecutive negative prices
+    /// For NegativePreise records and monthly negative-hours flags: duration of
+    /// consecutive negative prices
+    /// - Some("15min") - at least 1 quarter hour (monthly flags only)
     /// - Some("1h") - at least 1 hour of negative prices
     /// - Some("3h") - at least 3 consecutive hours
     /// - Some("4h") - at least 4 consecutive hours
     /// - Some("6h") - at least 6 consecutive hours
-    /// - None for non-negative-flag records
+    /// - None for price records
     pub negative_logic_hours: Option<String>,
 
-    /// For NegativePreise records: TRUE if negative price condition is met
+    /// For NegativePreise records and monthly negative-hours flags: TRUE if
+    /// negative price condition is met
     /// - Some(true) - condition met (negative prices occurred)
     /// - Some(false) - condition not met
-    /// - None for non-negative-flag records (spot_market, market_premium, etc.)
+    /// - None for price records, and for monthly flags left blank by the API
     pub negative_flag_value: Option<bool>,
 
+    /// Bidding zone of the price, from an optional area column
+    /// - Some("DE-LU") - Germany/Luxembourg
+    /// - None when the feed has no area column (single-zone feeds)
+    pub bidding_zone: Option<String>,
+
+    /// Currency of the price, from an optional currency column
+    /// - Some("EUR")
+    /// - None when the feed has no currency column
+    pub currency: Option<String>,
+
     /// Source API endpoint path for traceability
     /// Example: "Spotmarktpreise/2024-10-24/2024-10-24"
-    pub source_endpoint: String,
+    pub source_endpoint: Rc<str>,
+
+    /// Original response record, fields joined with `;` (`_raw` column)
+    /// - One CSV line; unpivoted rows share the line they came from
+    /// - None unless the `expose_raw` server option is enabled
+    pub raw: Option<String>,
 }
 
 impl RenewableRow {
@@ -156,9 +188,11 @@ impl RenewableRow {
     /// #     timestamp_utc: "2024-10-24T06:00:00Z".to_string(),
     /// #     interval_end_utc: "2024-10-24T06:15:00Z".to_string(),
     /// #     interval_minutes: 15,
-    /// #     product_type: "solar".to_string(),
-    /// #     data_category: "extrapolation".to_string(),
-    /// #     source_endpoint: "hochrechnung/Solar/2024-10-24/2024-10-25".to_string(),
+    /// #     product_type: "solar".into(),
+    /// #     api_product: "Solar".into(),
+    /// #     data_category: "extrapolation".into(),
+    /// #     source_endpoint: "hochrechnung/Solar/2024-10-24/2024-10-25".into(),
+    /// #     raw: None,
     /// };
     ///
     /// assert_eq!(row.total_germany_mw(), 600.0); // 100 + 0 + 300 + 200
@@ -195,9 +229,11 @@ impl RenewableRow {
     /// #     timestamp_utc: "2024-10-24T06:00:00Z".to_string(),
     /// #     interval_end_utc: "2024-10-24T06:15:00Z".to_string(),
     /// #     interval_minutes: 15,
-    /// #     product_type: "solar".to_string(),
-    /// #     data_category: "extrapolation".to_string(),
-    /// #     source_endpoint: "hochrechnung/Solar/2024-10-24/2024-10-25".to_string(),
+    /// #     product_type: "solar".into(),
+    /// #     api_product: "Solar".into(),
+    /// #     data_category: "extrapolation".into(),
+    /// #     source_endpoint: "hochrechnung/Solar/2024-10-24/2024-10-25".into(),
+    /// #     raw: None,
     /// };
     ///
     /// assert!(!complete_row.has_missing_data()); // All zones have data
@@ -210,6 +246,79 @@ impl RenewableRow {
     }
 }
 
+/// Unit emitted in the `price_eur_mwh` column
+///
+/// Configured via the `price_unit` server option. Parsers always store prices
+/// in EUR/MWh (`PriceRow::price_eur_mwh` is canonical); the unit is only applied
+/// when converting rows to cells, so values are never converted twice.
+///
+/// - `EurMwh` (default, `'eur_mwh'`) - `price_eur_mwh` holds EUR/MWh
+/// - `CtKwh` (`'ct_kwh'`) - `price_eur_mwh` holds the unconverted API value in ct/kWh
+///
+/// The computed `price_ct_kwh` column is always ct/kWh regardless of this setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PriceUnit {
+    /// EUR per MWh (standard unit)
+    #[default]
+    EurMwh,
+
+    /// ct per kWh (German retail unit, as published by the API)
+    CtKwh,
+}
+
+impl PriceUnit {
+    /// Parse the `price_unit` server option value
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use supabase_fdw_ntp::PriceUnit;
+    /// assert_eq!(PriceUnit::from_option("eur_mwh").unwrap(), PriceUnit::EurMwh);
+    /// assert_eq!(PriceUnit::from_option("ct_kwh").unwrap(), PriceUnit::CtKwh);
+    /// assert!(PriceUnit::from_option("usd_mwh").is_err());
+    /// ```
+    pub fn from_option(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "eur_mwh" => Ok(PriceUnit::EurMwh),
+            "ct_kwh" => Ok(PriceUnit::CtKwh),
+            other => Err(format!(
+                "Invalid server option 'price_unit': '{}'. Expected 'eur_mwh' or 'ct_kwh'.",
+                other
+            )),
+        }
+    }
+
+    /// Detect the unit of an API price column from its header suffix
+    ///
+    /// `None` if the header names neither unit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use supabase_fdw_ntp::PriceUnit;
+    /// assert_eq!(PriceUnit::from_header("Spotmarktpreis in ct/kWh"), Some(PriceUnit::CtKwh));
+    /// assert_eq!(PriceUnit::from_header("Spotmarktpreis in EUR/MWh"), Some(PriceUnit::EurMwh));
+    /// assert_eq!(PriceUnit::from_header("Spotmarktpreis"), None);
+    /// ```
+    pub fn from_header(header: &str) -> Option<Self> {
+        if header.ends_with("in ct/kWh") {
+            Some(PriceUnit::CtKwh)
+        } else if header.ends_with("in EUR/MWh") {
+            Some(PriceUnit::EurMwh)
+        } else {
+            None
+        }
+    }
+
+    /// Convert a price in this unit to EUR/MWh (ct/kWh × 10)
+    pub fn to_eur_mwh(self, price: f64) -> f64 {
+        match self {
+            PriceUnit::CtKwh => crate::transformations::convert_price_to_eur_mwh(price),
+            PriceUnit::EurMwh => price,
+        }
+    }
+}
+
 impl PriceRow {
     /// Check if price is negative (oversupply condition)
     pub fn is_negative(&self) -> bool {
@@ -222,6 +331,16 @@ impl PriceRow {
     pub fn price_ct_kwh(&self) -> Option<f64> {
         self.price_eur_mwh.map(|price| price / 10.0)
     }
+
+    /// Price expressed in the configured `price_unit`
+    ///
+    /// Always derived from the canonical `price_eur_mwh` value.
+    pub fn price_in_unit(&self, unit: PriceUnit) -> Option<f64> {
+        match unit {
+            PriceUnit::EurMwh => self.price_eur_mwh,
+            PriceUnit::CtKwh => self.price_ct_kwh(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -234,13 +353,15 @@ mod tests {
             timestamp_utc: "2024-10-24T06:00:00Z".to_string(),
             interval_end_utc: "2024-10-24T06:15:00Z".to_string(),
             interval_minutes: 15,
-            product_type: "solar".to_string(),
-            data_category: "forecast".to_string(),
+            product_type: "solar".into(),
+            api_product: "Solar".into(),
+            data_category: "forecast".into(),
             tso_50hertz_mw: Some(100.0),
             tso_amprion_mw: Some(200.0),
             tso_tennet_mw: Some(300.0),
             tso_transnetbw_mw: Some(400.0),
-            source_endpoint: "prognose/Solar/2024-10-24/2024-10-25".to_string(),
+            source_endpoint: "prognose/Solar/2024-10-24/2024-10-25".into(),
+            raw: None,
         };
 
         assert_eq!(row.total_germany_mw(), 1000.0);
@@ -253,13 +374,15 @@ mod tests {
             timestamp_utc: "2024-10-24T00:00:00Z".to_string(),
             interval_end_utc: "2024-10-24T00:15:00Z".to_string(),
             interval_minutes: 15,
-            product_type: "solar".to_string(),
-            data_category: "forecast".to_string(),
+            product_type: "solar".into(),
+            api_product: "Solar".into(),
+            data_category: "forecast".into(),
             tso_50hertz_mw: None, // N.A. in CSV
             tso_amprion_mw: None,
             tso_tennet_mw: None,
             tso_transnetbw_mw: None,
-            source_endpoint: "prognose/Solar/2024-10-24/2024-10-25".to_string(),
+            source_endpoint: "prognose/Solar/2024-10-24/2024-10-25".into(),
+            raw: None,
         };
 
         assert_eq!(row.total_germany_mw(), 0.0); // None treated as 0
@@ -271,13 +394,16 @@ mod tests {
         let row = PriceRow {
             timestamp_utc: "2024-10-24T13:00:00Z".to_string(),
             interval_end_utc: "2024-10-24T14:00:00Z".to_string(),
-            granularity: "hourly".to_string(),
-            price_type: "spot_market".to_string(),
+            granularity: "hourly".into(),
+            price_type: "spot_market".into(),
             price_eur_mwh: Some(-45.23),
             product_category: None,
             negative_logic_hours: None, // Not a negative_flag record
             negative_flag_value: None,
-            source_endpoint: "Spotmarktpreise/2024-10-24/2024-10-24".to_string(),
+            bidding_zone: None,
+            currency: None,
+            source_endpoint: "Spotmarktpreise/2024-10-24/2024-10-24".into(),
+            raw: None,
         };
 
         assert!(row.is_negative());
@@ -289,13 +415,16 @@ mod tests {
         let row = PriceRow {
             timestamp_utc: "2024-10-24T14:00:00Z".to_string(),
             interval_end_utc: "2024-10-24T15:00:00Z".to_string(),
-            granularity: "hourly".to_string(),
-            price_type: "spot_market".to_string(),
+            granularity: "hourly".into(),
+            price_type: "spot_market".into(),
             price_eur_mwh: Some(82.73), // API: 8.273 ct/kWh × 10 = 82.73 EUR/MWh
             product_category: None,
             negative_logic_hours: None, // Not a negative_flag record
             negative_flag_value: None,
-            source_endpoint: "Spotmarktpreise/2024-10-24/2024-10-24".to_string(),
+            bidding_zone: None,
+            currency: None,
+            source_endpoint: "Spotmarktpreise/2024-10-24/2024-10-24".into(),
+            raw: None,
         };
 
         assert!(!row.is_negative());
@@ -308,13 +437,16 @@ mod tests {
         let row = PriceRow {
             timestamp_utc: "2024-10-24T13:00:00Z".to_string(),
             interval_end_utc: "2024-10-24T14:00:00Z".to_string(),
-            granularity: "hourly".to_string(),
-            price_type: "negative_flag".to_string(),
+            granularity: "hourly".into(),
+            price_type: "negative_flag".into(),
             price_eur_mwh: None, // No actual price in flag records
             product_category: None,
             negative_logic_hours: Some("3h".to_string()),
             negative_flag_value: Some(true),
-            source_endpoint: "NegativePreise/2024-10-24/2024-10-24".to_string(),
+            bidding_zone: None,
+            currency: None,
+            source_endpoint: "NegativePreise/2024-10-24/2024-10-24".into(),
+            raw: None,
         };
 
         // Negative flags don't have prices, so is_negative should be false
@@ -323,6 +455,89 @@ mod tests {
         assert_eq!(row.negative_flag_value, Some(true));
     }
 
+    #[test]
+    fn test_price_in_unit_eur_mwh() {
+        let row = PriceRow {
+            timestamp_utc: "2024-10-24T14:00:00Z".to_string(),
+            interval_end_utc: "2024-10-24T15:00:00Z".to_string(),
+            granularity: "hourly".into(),
+            price_type: "spot_market".into(),
+            price_eur_mwh: Some(82.73), // API: 8.273 ct/kWh
+            product_category: None,
+            negative_logic_hours: None,
+            negative_flag_value: None,
+            bidding_zone: None,
+            currency: None,
+            source_endpoint: "Spotmarktpreise/2024-10-24/2024-10-24".into(),
+            raw: None,
+        };
+
+        assert_eq!(row.price_in_unit(PriceUnit::EurMwh), Some(82.73));
+        // Computed ct/kWh column is unaffected by the unit setting
+        assert_eq!(row.price_ct_kwh(), Some(8.273));
+    }
+
+    #[test]
+    fn test_price_in_unit_ct_kwh() {
+        let row = PriceRow {
+            timestamp_utc: "2024-10-24T13:00:00Z".to_string(),
+            interval_end_utc: "2024-10-24T14:00:00Z".to_string(),
+            granularity: "hourly".into(),
+            price_type: "spot_market".into(),
+            price_eur_mwh: Some(-2.01), // API: -0.201 ct/kWh
+            product_category: None,
+            negative_logic_hours: None,
+            negative_flag_value: None,
+            bidding_zone: None,
+            currency: None,
+            source_endpoint: "Spotmarktpreise/2024-10-24/2024-10-24".into(),
+            raw: None,
+        };
+
+        // Unconverted API value, not converted twice
+        let value = row.price_in_unit(PriceUnit::CtKwh).unwrap();
+        assert!((value - (-0.201)).abs() < 1e-9);
+        assert_eq!(row.price_in_unit(PriceUnit::CtKwh), row.price_ct_kwh());
+        // Canonical value is untouched
+        assert_eq!(row.price_eur_mwh, Some(-2.01));
+    }
+
+    #[test]
+    fn test_price_in_unit_null_price() {
+        let row = PriceRow {
+            timestamp_utc: "2024-10-24T13:00:00Z".to_string(),
+            interval_end_utc: "2024-10-24T14:00:00Z".to_string(),
+            granularity: "hourly".into(),
+            price_type: "negative_flag".into(),
+            price_eur_mwh: None,
+            product_category: None,
+            negative_logic_hours: Some("1h".to_string()),
+            negative_flag_value: Some(true),
+            bidding_zone: None,
+            currency: None,
+            source_endpoint: "NegativePreise".into(),
+            raw: None,
+        };
+
+        assert_eq!(row.price_in_unit(PriceUnit::EurMwh), None);
+        assert_eq!(row.price_in_unit(PriceUnit::CtKwh), None);
+    }
+
+    #[test]
+    fn test_price_unit_from_option() {
+        assert_eq!(
+            PriceUnit::from_option("eur_mwh").unwrap(),
+            PriceUnit::EurMwh
+        );
+        assert_eq!(PriceUnit::from_option("ct_kwh").unwrap(), PriceUnit::CtKwh);
+        assert_eq!(
+            PriceUnit::from_option(" CT_KWH ").unwrap(),
+            PriceUnit::CtKwh
+        );
+        assert_eq!(PriceUnit::default(), PriceUnit::EurMwh);
+        assert!(PriceUnit::from_option("eur_kwh").is_err());
+    }
+
     #[test]
     fn test_renewable_row_partial_null_handling() {
         // Test mixed NULL/non-NULL TSO zones (real production scenario)
@@ -330,13 +545,15 @@ mod tests {
             timestamp_utc: "2024-10-24T06:00:00Z".to_string(),
             interval_end_utc: "2024-10-24T06:15:00Z".to_string(),
             interval_minutes: 15,
-            product_type: "solar".to_string(),
-            data_category: "forecast".to_string(),
+            product_type: "solar".into(),
+            api_product: "Solar".into(),
+            data_category: "forecast".into(),
             tso_50hertz_mw: Some(100.0),
             tso_amprion_mw: None, // <-- One zone missing
             tso_tennet_mw: Some(300.0),
             tso_transnetbw_mw: Some(400.0),
-            source_endpoint: "prognose/Solar/2024-10-24/2024-10-25".to_string(),
+            source_endpoint: "prognose/Solar/2024-10-24/2024-10-25".into(),
+            raw: None,
         };
 
         // Total should be 100 + 0 + 300 + 400 = 800.0
@@ -21,7 +21,7 @@
 /// # use supabase_fdw_ntp::types_grid::RedispatchRow;
 /// let row = RedispatchRow {
 ///     timestamp_utc: "2024-10-23T22:00:00Z".to_string(),
-///     interval_end_utc: "2024-10-24T08:00:00Z".to_string(),
+///     interval_end_utc: Some("2024-10-24T08:00:00Z".to_string()),
 ///     reason: "Probestart (NetzRes)".to_string(),
 ///     direction: "increase_generation".to_string(),
 ///     avg_power_mw: Some(119.5),
@@ -42,7 +42,10 @@ pub struct RedispatchRow {
 
     /// End time of intervention (ISO 8601 format)
     /// Example: "2024-10-24T08:00:00Z"
-    pub interval_end_utc: String,
+    ///
+    /// None for ongoing events (blank `ENDE_DATUM`/`ENDE_UHRZEIT`); surfaced as
+    /// NULL `interval_end_utc` and NULL `interval_minutes`
+    pub interval_end_utc: Option<String>,
 
     /// Reason for intervention (German text preserved)
     ///
@@ -73,14 +76,16 @@ pub struct RedispatchRow {
     /// None if data not available
     pub total_energy_mwh: Option<f64>,
 
-    /// TSO requesting intervention
+    /// TSO requesting intervention (from `ANFORDERNDER_UENB`)
     ///
     /// Values: '50Hertz' | 'Amprion' | 'TenneT' | 'TransnetBW'
     /// Can be combined: '50Hertz & Amprion & TenneT DE & TransnetBW'
     pub requesting_tso: String,
 
-    /// TSO issuing instruction (often same as requesting_tso)
-    /// None if not specified
+    /// TSO issuing instruction (from `ANWEISENDER_UENB`)
+    ///
+    /// Often same as requesting_tso (self-instructed) - the value is kept in that case.
+    /// None only if the column is empty
     pub instructing_tso: Option<String>,
 
     /// Power plant or facility name
@@ -165,7 +170,7 @@ mod tests {
     fn test_redispatch_row_creation() {
         let row = RedispatchRow {
             timestamp_utc: "2024-10-23T22:00:00Z".to_string(),
-            interval_end_utc: "2024-10-24T08:00:00Z".to_string(),
+            interval_end_utc: Some("2024-10-24T08:00:00Z".to_string()),
             reason: "Probestart (NetzRes)".to_string(),
             direction: "increase_generation".to_string(),
             avg_power_mw: Some(119.5),
@@ -187,7 +192,7 @@ mod tests {
     fn test_redispatch_row_with_nulls() {
         let row = RedispatchRow {
             timestamp_utc: "2024-10-24T14:00:00Z".to_string(),
-            interval_end_utc: "2024-10-24T21:00:00Z".to_string(),
+            interval_end_utc: Some("2024-10-24T21:00:00Z".to_string()),
             reason: "Strombedingter Redispatch".to_string(),
             direction: "reduce_generation".to_string(),
             avg_power_mw: None,
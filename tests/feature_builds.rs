@@ -0,0 +1,27 @@
+//! Build checks for non-default Cargo feature sets
+//!
+//! Runs `cargo check` as a subprocess, so the reduced builds are verified by a
+//! plain `cargo test` without a CI feature matrix.
+
+use std::process::Command;
+
+/// The crate must build without the `grid` feature (renewable + price tables only)
+#[test]
+fn test_builds_without_default_features() {
+    let output = Command::new(env!("CARGO"))
+        .args(["check", "--lib", "--offline", "--no-default-features"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        // Separate target dir: avoids waiting on the lock held by the outer build
+        .env(
+            "CARGO_TARGET_DIR",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/target/no-default-features"),
+        )
+        .output()
+        .expect("failed to run cargo check");
+
+    assert!(
+        output.status.success(),
+        "cargo check --no-default-features failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}